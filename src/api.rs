@@ -1,21 +1,33 @@
 //! Structs and utilities for handling API methods.
 
+pub mod config;
 mod endpoints;
 pub mod market_data;
+pub mod monitor;
 pub mod parameter;
+pub mod rate_limit;
+mod response;
+pub mod retry;
 pub mod trader;
 
+use futures::Stream;
 use reqwest::Client;
 
 use crate::token::Tokener;
 use crate::{error::Error, model};
 use parameter::{Market, Projection, TransactionType};
 
+pub use config::ApiConfig;
+pub use monitor::PositionQuote;
+pub use rate_limit::RateLimiter;
+pub use retry::RetryConfig;
+
 /// Interacting with the Schwab API.
 #[derive(Debug)]
 pub struct Api<T: Tokener> {
     pub tokener: T,
     client: Client,
+    config: ApiConfig,
 }
 
 impl<T: Tokener> Api<T> {
@@ -24,7 +36,25 @@ impl<T: Tokener> Api<T> {
     ///
     /// Will panic if no symbol found
     pub async fn new(tokener: T, client: Client) -> Result<Self, Error> {
-        let api = Api { tokener, client };
+        Self::new_with_client_config(tokener, client, ApiConfig::default()).await
+    }
+
+    /// Create API Struct sending every request through a client built from `config`
+    pub async fn new_with_config(tokener: T, config: ApiConfig) -> Result<Self, Error> {
+        let client = config.client()?;
+        Self::new_with_client_config(tokener, client, config).await
+    }
+
+    async fn new_with_client_config(
+        tokener: T,
+        client: Client,
+        config: ApiConfig,
+    ) -> Result<Self, Error> {
+        let api = Api {
+            tokener,
+            client,
+            config,
+        };
 
         if (api.get_quote("AAPL".to_string()).await?.send().await).is_err() {
             api.tokener.redo_authorization().await?;
@@ -33,6 +63,58 @@ impl<T: Tokener> Api<T> {
         Ok(api)
     }
 
+    /// Send the following requests to another server, e.g. a sandbox or a mock server
+    ///
+    /// See [`ApiConfig::with_base_url`]. To avoid reaching Schwab at all, pass the URL to
+    /// [`Api::new_with_config`] instead.
+    #[must_use]
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.config = self.config.with_base_url(url);
+        self
+    }
+
+    /// Retry the following requests answered with a transient error, see [`RetryConfig`]
+    #[must_use]
+    pub fn with_retry(mut self, config: RetryConfig) -> Self {
+        self.config.retry = Some(config);
+        self
+    }
+
+    /// Replay the following requests answered with `401 Unauthorized` once, with the token from [`Tokener::get_access_token`]
+    ///
+    /// The tokener is cloned into every request, e.g. use an `Arc<TokenChecker>`.
+    #[must_use]
+    pub fn with_reauthorization(mut self) -> Self
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let tokener = self.tokener.clone();
+        self.config.reauthorizer = Some(config::Reauthorizer(std::sync::Arc::new(move || {
+            let tokener = tokener.clone();
+            Box::pin(async move { tokener.get_access_token().await })
+        })));
+        self
+    }
+
+    /// Let at most `requests_per_second` of the following requests through per second, see [`RateLimiter`]
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `requests_per_second` is not positive.
+    #[must_use]
+    pub fn with_rate_limit(mut self, requests_per_second: f64) -> Self {
+        self.config.rate_limiter = Some(std::sync::Arc::new(RateLimiter::new(requests_per_second)));
+        self
+    }
+
+    /// The effective configuration, e.g. to log the settings in effect.
+    ///
+    /// Its `Debug` output leaves out the values of the default headers.
+    #[must_use]
+    pub fn config(&self) -> &ApiConfig {
+        &self.config
+    }
+
     pub async fn get_quotes(
         &self,
         symbols: Vec<String>,
@@ -41,16 +123,22 @@ impl<T: Tokener> Api<T> {
 
         Ok(market_data::GetQuotesRequest::new(
             &self.client,
+            &self.config,
             access_token,
             symbols,
         ))
     }
 
-    pub async fn get_quote(&self, symbol: String) -> Result<market_data::GetQuoteRequest, Error> {
+    pub async fn get_quote(
+        &self,
+        symbol: impl Into<model::Symbol>,
+    ) -> Result<market_data::GetQuoteRequest, Error> {
+        let symbol = checked_symbol(symbol)?;
         let access_token = self.tokener.get_access_token().await?;
 
         Ok(market_data::GetQuoteRequest::new(
             &self.client,
+            &self.config,
             access_token,
             symbol,
         ))
@@ -58,12 +146,14 @@ impl<T: Tokener> Api<T> {
 
     pub async fn get_option_chains(
         &self,
-        symbol: String,
+        symbol: impl Into<model::Symbol>,
     ) -> Result<market_data::GetOptionChainsRequest, Error> {
+        let symbol = checked_symbol(symbol)?;
         let access_token = self.tokener.get_access_token().await?;
 
         Ok(market_data::GetOptionChainsRequest::new(
             &self.client,
+            &self.config,
             access_token,
             symbol,
         ))
@@ -71,30 +161,98 @@ impl<T: Tokener> Api<T> {
 
     pub async fn get_option_expiration_chain(
         &self,
-        symbol: String,
+        symbol: impl Into<model::Symbol>,
     ) -> Result<market_data::GetOptionExpirationChainRequest, Error> {
+        let symbol = checked_symbol(symbol)?;
         let access_token = self.tokener.get_access_token().await?;
 
         Ok(market_data::GetOptionExpirationChainRequest::new(
             &self.client,
+            &self.config,
             access_token,
             symbol,
         ))
     }
 
+    /// Get the option expiration chain together with the quote of the underlying symbol
+    pub async fn get_option_expiration_chain_with_quote(
+        &self,
+        symbol: impl Into<model::Symbol>,
+    ) -> Result<(model::ExpirationChain, model::QuoteResponse), Error> {
+        let symbol = checked_symbol(symbol)?;
+        let access_token = self.tokener.get_access_token().await?;
+
+        let quote = market_data::GetQuoteRequest::new(
+            &self.client,
+            &self.config,
+            access_token.clone(),
+            symbol.clone(),
+        );
+        market_data::GetOptionExpirationChainRequest::new(
+            &self.client,
+            &self.config,
+            access_token,
+            symbol,
+        )
+        .send_with_quote(quote)
+        .await
+    }
+
     pub async fn get_price_history(
         &self,
-        symbol: String,
+        symbol: impl Into<model::Symbol>,
     ) -> Result<market_data::GetPriceHistoryRequest, Error> {
+        let symbol = checked_symbol(symbol)?;
         let access_token = self.tokener.get_access_token().await?;
 
         Ok(market_data::GetPriceHistoryRequest::new(
             &self.client,
+            &self.config,
             access_token,
             symbol,
         ))
     }
 
+    /// Daily candles over the last `years`, one of `1`, `2`, `3`, `5`, `10`, `15`, `20`.
+    pub async fn get_price_history_daily(
+        &self,
+        symbol: impl Into<model::Symbol>,
+        years: u32,
+    ) -> Result<market_data::GetPriceHistoryRequest, Error> {
+        let symbol = checked_symbol(symbol)?;
+        let access_token = self.tokener.get_access_token().await?;
+
+        market_data::GetPriceHistoryRequest::daily(
+            &self.client,
+            &self.config,
+            access_token,
+            symbol,
+            years,
+        )
+    }
+
+    /// Candles of `interval_minutes` over the last `days`.
+    ///
+    /// `days` must be one of `1`, `2`, `3`, `4`, `5`, `10` and `interval_minutes` one of `1`, `5`, `10`, `15`, `30`.
+    pub async fn get_price_history_minute(
+        &self,
+        symbol: impl Into<model::Symbol>,
+        days: u32,
+        interval_minutes: u32,
+    ) -> Result<market_data::GetPriceHistoryRequest, Error> {
+        let symbol = checked_symbol(symbol)?;
+        let access_token = self.tokener.get_access_token().await?;
+
+        market_data::GetPriceHistoryRequest::minute(
+            &self.client,
+            &self.config,
+            access_token,
+            symbol,
+            days,
+            interval_minutes,
+        )
+    }
+
     /// `symbol`
     ///
     /// Index Symbol
@@ -102,11 +260,16 @@ impl<T: Tokener> Api<T> {
     /// Available values : `$DJI`, `$COMPX`, `$SPX`, `NYSE`, `NASDAQ`, `OTCBB`, `INDEX_ALL`, `EQUITY_ALL`, `OPTION_ALL`, `OPTION_PUT`, `OPTION_CALL`
     ///
     /// Example : `$DJI`
-    pub async fn get_movers(&self, symbol: String) -> Result<market_data::GetMoversRequest, Error> {
+    pub async fn get_movers(
+        &self,
+        symbol: impl Into<model::Symbol>,
+    ) -> Result<market_data::GetMoversRequest, Error> {
+        let symbol = checked_symbol(symbol)?;
         let access_token = self.tokener.get_access_token().await?;
 
         Ok(market_data::GetMoversRequest::new(
             &self.client,
+            &self.config,
             access_token,
             symbol,
         ))
@@ -125,6 +288,7 @@ impl<T: Tokener> Api<T> {
 
         Ok(market_data::GetMarketsRequest::new(
             &self.client,
+            &self.config,
             access_token,
             markets,
         ))
@@ -141,6 +305,7 @@ impl<T: Tokener> Api<T> {
 
         Ok(market_data::GetMarketRequest::new(
             &self.client,
+            &self.config,
             access_token,
             market_id,
         ))
@@ -160,6 +325,7 @@ impl<T: Tokener> Api<T> {
 
         Ok(market_data::GetInstrumentsRequest::new(
             &self.client,
+            &self.config,
             access_token,
             symbol,
             projection,
@@ -177,6 +343,7 @@ impl<T: Tokener> Api<T> {
 
         Ok(market_data::GetInstrumentRequest::new(
             &self.client,
+            &self.config,
             access_token,
             cusip_id,
         ))
@@ -187,6 +354,7 @@ impl<T: Tokener> Api<T> {
 
         Ok(trader::GetAccountNumbersRequest::new(
             &self.client,
+            &self.config,
             access_token,
         ))
     }
@@ -194,22 +362,97 @@ impl<T: Tokener> Api<T> {
     pub async fn get_accounts(&self) -> Result<trader::GetAccountsRequest, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(trader::GetAccountsRequest::new(&self.client, access_token))
+        Ok(trader::GetAccountsRequest::new(
+            &self.client,
+            &self.config,
+            access_token,
+        ))
     }
 
     pub async fn get_account(
         &self,
-        account_number: String,
+        account_number: model::EncryptedAccountNumber,
     ) -> Result<trader::GetAccountRequest, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
         Ok(trader::GetAccountRequest::new(
             &self.client,
+            &self.config,
             access_token,
-            account_number,
+            account_number.into(),
         ))
     }
 
+    /// Poll the positions of an account and the quotes of their instruments every `interval`.
+    ///
+    /// The first poll is immediate. Positions are fetched again every time, so opened and
+    /// closed positions show up. A failed poll is yielded as an error and polling goes on.
+    pub fn monitor_positions(
+        &self,
+        account_number: model::EncryptedAccountNumber,
+        interval: std::time::Duration,
+    ) -> impl Stream<Item = Result<Vec<PositionQuote>, Error>> + '_ {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        futures::stream::unfold(ticker, move |mut ticker| {
+            let account_number = account_number.clone();
+            async move {
+                ticker.tick().await;
+                Some((self.position_quotes(account_number).await, ticker))
+            }
+        })
+    }
+
+    async fn position_quotes(
+        &self,
+        account_number: model::EncryptedAccountNumber,
+    ) -> Result<Vec<PositionQuote>, Error> {
+        let account = self
+            .get_account(account_number)
+            .await?
+            .fields("positions".to_string())
+            .send()
+            .await?;
+        let positions = account.positions();
+        if positions.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let symbols = positions
+            .iter()
+            .map(|x| x.instrument.symbol().to_string())
+            .collect();
+        let quotes = self.get_quotes(symbols).await?.send().await?;
+
+        Ok(positions
+            .iter()
+            .map(|position| PositionQuote {
+                quote: quotes.get(position.instrument.symbol()).cloned(),
+                position: position.clone(),
+            })
+            .collect())
+    }
+
+    /// Get only the balances of an account, without positions
+    ///
+    /// This is the lightest account call, e.g. for polling buying power.
+    pub async fn get_balances(
+        &self,
+        account_number: model::EncryptedAccountNumber,
+    ) -> Result<model::Balances, Error> {
+        let access_token = self.tokener.get_access_token().await?;
+
+        trader::GetAccountRequest::new(
+            &self.client,
+            &self.config,
+            access_token,
+            account_number.into(),
+        )
+        .send_balances()
+        .await
+    }
+
     /// `from_entered_time`
     ///
     /// Specifies that no orders entered before this time should be returned.
@@ -219,21 +462,24 @@ impl<T: Tokener> Api<T> {
     /// `to_entered_time`
     ///
     /// Specifies that no orders entered after this time should be returned.
+    ///
+    /// Fails with [`Error::InvalidTimeRange`] if the range is longer than 60 days.
     pub async fn get_account_orders(
         &self,
-        account_number: String,
+        account_number: model::EncryptedAccountNumber,
         from_entered_time: chrono::DateTime<chrono::Utc>,
         to_entered_time: chrono::DateTime<chrono::Utc>,
     ) -> Result<trader::GetAccountOrdersRequest, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(trader::GetAccountOrdersRequest::new(
+        trader::GetAccountOrdersRequest::new(
             &self.client,
+            &self.config,
             access_token,
-            account_number,
+            account_number.into(),
             from_entered_time,
             to_entered_time,
-        ))
+        )
     }
 
     /// `account_number`
@@ -241,15 +487,16 @@ impl<T: Tokener> Api<T> {
     /// The encrypted ID of the account
     pub async fn post_account_order(
         &self,
-        account_number: String,
+        account_number: model::EncryptedAccountNumber,
         body: model::OrderRequest,
     ) -> Result<trader::PostAccountOrderRequest, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
         Ok(trader::PostAccountOrderRequest::new(
             &self.client,
+            &self.config,
             access_token,
-            account_number,
+            account_number.into(),
             body,
         ))
     }
@@ -263,15 +510,16 @@ impl<T: Tokener> Api<T> {
     /// The ID of the order being retrieved.
     pub async fn get_account_order(
         &self,
-        account_number: String,
+        account_number: model::EncryptedAccountNumber,
         order_id: i64,
     ) -> Result<trader::GetAccountOrderRequest, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
         Ok(trader::GetAccountOrderRequest::new(
             &self.client,
+            &self.config,
             access_token,
-            account_number,
+            account_number.into(),
             order_id,
         ))
     }
@@ -285,15 +533,16 @@ impl<T: Tokener> Api<T> {
     /// The ID of the order being retrieved.
     pub async fn delete_account_order(
         &self,
-        account_number: String,
+        account_number: model::EncryptedAccountNumber,
         order_id: i64,
     ) -> Result<trader::DeleteAccountOrderRequest, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
         Ok(trader::DeleteAccountOrderRequest::new(
             &self.client,
+            &self.config,
             access_token,
-            account_number,
+            account_number.into(),
             order_id,
         ))
     }
@@ -307,7 +556,7 @@ impl<T: Tokener> Api<T> {
     /// The ID of the order being retrieved.
     pub async fn put_account_order(
         &self,
-        account_number: String,
+        account_number: model::EncryptedAccountNumber,
         order_id: i64,
         body: model::OrderRequest,
     ) -> Result<trader::PutAccountOrderRequest, Error> {
@@ -315,13 +564,132 @@ impl<T: Tokener> Api<T> {
 
         Ok(trader::PutAccountOrderRequest::new(
             &self.client,
+            &self.config,
             access_token,
-            account_number,
+            account_number.into(),
             order_id,
             body,
         ))
     }
 
+    /// Fetch a placed order and report the fields Schwab recorded differently from `submitted`,
+    /// e.g. a rounded price.
+    ///
+    /// `account_number`
+    ///
+    /// The encrypted ID of the account
+    ///
+    /// `order_id`
+    ///
+    /// The ID of the order being verified.
+    pub async fn verify_placed_order(
+        &self,
+        account_number: model::EncryptedAccountNumber,
+        order_id: i64,
+        submitted: &model::OrderRequest,
+    ) -> Result<Vec<model::FieldChange>, Error> {
+        let order = self
+            .get_account_order(account_number, order_id)
+            .await?
+            .send()
+            .await?;
+
+        Ok(submitted.diff(&order.into()))
+    }
+
+    /// Fetch an order every `poll_interval` until its status is terminal, e.g. `FILLED` or `REJECTED`.
+    ///
+    /// Fails with [`Error::Timeout`] if the order is still open after `timeout`.
+    pub async fn wait_for_order(
+        &self,
+        account_number: model::EncryptedAccountNumber,
+        order_id: i64,
+        timeout: std::time::Duration,
+        poll_interval: std::time::Duration,
+    ) -> Result<model::Order, Error> {
+        let poll = async {
+            loop {
+                let order = self
+                    .get_account_order(account_number.clone(), order_id)
+                    .await?
+                    .send()
+                    .await?;
+                if order.status.is_terminal() {
+                    return Ok(order);
+                }
+                tokio::time::sleep(poll_interval).await;
+            }
+        };
+
+        tokio::time::timeout(timeout, poll)
+            .await
+            .map_err(|_| Error::Timeout(timeout))?
+    }
+
+    /// Like [`Api::wait_for_order`], but only a filled or rejected order is returned.
+    ///
+    /// Fails with [`Error::OrderNotFilled`] if the order is canceled, replaced or expired instead.
+    pub async fn wait_for_order_fill(
+        &self,
+        account_number: model::EncryptedAccountNumber,
+        order_id: i64,
+        timeout: std::time::Duration,
+        poll_interval: std::time::Duration,
+    ) -> Result<model::Order, Error> {
+        let order = self
+            .wait_for_order(account_number, order_id, timeout, poll_interval)
+            .await?;
+
+        match order.status {
+            model::trader::order::Status::Filled | model::trader::order::Status::Rejected => {
+                Ok(order)
+            }
+            status => Err(Error::OrderNotFilled(status)),
+        }
+    }
+
+    /// Cancel every cancelable order of an account entered between `from_entered_time` and `to_entered_time`,
+    /// see [`model::Order::is_cancelable`], and return the IDs of the canceled ones.
+    ///
+    /// A failed cancellation, e.g. of an order filled in the meantime, does not stop the others.
+    /// The failures are collected rather than dropped, since the caller must know which orders
+    /// may still be open: if any failed, the result is [`Error::PartialCancel`] with the canceled
+    /// IDs and the `(order_id, error)` failures, otherwise `Ok` with the canceled IDs.
+    pub async fn cancel_all_orders(
+        &self,
+        account_number: model::EncryptedAccountNumber,
+        from_entered_time: chrono::DateTime<chrono::Utc>,
+        to_entered_time: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<i64>, Error> {
+        let orders = self
+            .get_account_orders(account_number.clone(), from_entered_time, to_entered_time)
+            .await?
+            .send()
+            .await?;
+
+        let mut canceled = vec![];
+        let mut failed = vec![];
+        for order in orders.iter().filter(|x| x.is_cancelable()) {
+            let result = match self
+                .delete_account_order(account_number.clone(), order.order_id)
+                .await
+            {
+                Ok(req) => req.send().await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) => canceled.push(order.order_id),
+                Err(e) => failed.push((order.order_id, e)),
+            }
+        }
+
+        if failed.is_empty() {
+            Ok(canceled)
+        } else {
+            Err(Error::PartialCancel(canceled, failed))
+        }
+    }
+
     /// `from_entered_time`
     ///
     /// Specifies that no orders entered before this time should be returned.
@@ -331,6 +699,8 @@ impl<T: Tokener> Api<T> {
     /// `to_entered_time`
     ///
     /// Specifies that no orders entered after this time should be returned.
+    ///
+    /// Fails with [`Error::InvalidTimeRange`] if the range is longer than 60 days.
     pub async fn get_accounts_orders(
         &self,
         from_entered_time: chrono::DateTime<chrono::Utc>,
@@ -338,28 +708,34 @@ impl<T: Tokener> Api<T> {
     ) -> Result<trader::GetAccountsOrdersRequest, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
-        Ok(trader::GetAccountsOrdersRequest::new(
+        trader::GetAccountsOrdersRequest::new(
             &self.client,
+            &self.config,
             access_token,
             from_entered_time,
             to_entered_time,
-        ))
+        )
     }
 
     /// `account_number`
     ///
     /// The encrypted ID of the account
+    ///
+    /// `body`
+    ///
+    /// Built from the order to place with `PreviewOrder::from(&order_request)`.
     pub async fn post_accounts_preview_order(
         &self,
-        account_number: String,
+        account_number: model::EncryptedAccountNumber,
         body: model::PreviewOrder,
     ) -> Result<trader::PostAccountPreviewOrderRequest, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
         Ok(trader::PostAccountPreviewOrderRequest::new(
             &self.client,
+            &self.config,
             access_token,
-            account_number,
+            account_number.into(),
             body,
         ))
     }
@@ -380,22 +756,25 @@ impl<T: Tokener> Api<T> {
     ///
     /// `types`
     ///
-    /// Specifies that only transactions of this status should be returned.
+    /// Specifies that only transactions of these types should be returned, sent comma-separated.
     ///
     /// Available values : `TRADE`, `RECEIVE_AND_DELIVER`, `DIVIDEND_OR_INTEREST`, `ACH_RECEIPT`, `ACH_DISBURSEMENT`, `CASH_RECEIPT`, `CASH_DISBURSEMENT`, `ELECTRONIC_FUND`, `WIRE_OUT`, `WIRE_IN`, `JOURNAL`, `MEMORANDUM`, `MARGIN_CALL`, `MONEY_MARKET`, `SMA_ADJUSTMENT`
+    ///
+    /// [`TransactionType::all`] asks for every type.
     pub async fn get_account_transactions(
         &self,
-        account_number: String,
+        account_number: model::EncryptedAccountNumber,
         start_date: chrono::DateTime<chrono::Utc>,
         end_date: chrono::DateTime<chrono::Utc>,
-        types: TransactionType,
+        types: Vec<TransactionType>,
     ) -> Result<trader::GetAccountTransactions, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
         Ok(trader::GetAccountTransactions::new(
             &self.client,
+            &self.config,
             access_token,
-            account_number,
+            account_number.into(),
             start_date,
             end_date,
             types,
@@ -411,15 +790,16 @@ impl<T: Tokener> Api<T> {
     /// The ID of the transaction being retrieved.
     pub async fn get_account_transaction(
         &self,
-        account_number: String,
+        account_number: model::EncryptedAccountNumber,
         transaction_id: i64,
     ) -> Result<trader::GetAccountTransaction, Error> {
         let access_token = self.tokener.get_access_token().await?;
 
         Ok(trader::GetAccountTransaction::new(
             &self.client,
+            &self.config,
             access_token,
-            account_number,
+            account_number.into(),
             transaction_id,
         ))
     }
@@ -429,9 +809,72 @@ impl<T: Tokener> Api<T> {
 
         Ok(trader::GetUserPreferenceRequest::new(
             &self.client,
+            &self.config,
             access_token,
         ))
     }
+
+    /// Connect to the streamer with the `streamerInfo` of [`Api::get_user_preference`] and log in.
+    ///
+    /// The login expires with the access token, see [`crate::streaming::StreamerClient::relogin_with`] to renew it.
+    #[cfg(feature = "streaming")]
+    pub async fn streamer(&self) -> Result<crate::streaming::StreamerClient, Error> {
+        let preferences = self.get_user_preference().await?.send().await?;
+        let info = match preferences {
+            model::UserPreferences::One(x) => x.streamer_info.into_iter().next(),
+            model::UserPreferences::Mutiple(x) => {
+                x.into_iter().flat_map(|x| x.streamer_info).next()
+            }
+        }
+        .ok_or_else(|| Error::Streamer("no streamerInfo in the user preference".to_string()))?;
+        let access_token = self.tokener.get_access_token().await?;
+
+        crate::streaming::StreamerClient::connect(info, &access_token).await
+    }
+}
+
+/// Check a symbol before sending it, see [`model::Symbol`].
+fn checked_symbol(symbol: impl Into<model::Symbol>) -> Result<String, Error> {
+    let symbol: model::Symbol = symbol.into();
+    Ok(symbol.checked()?.into())
+}
+
+/// An [`Api`] sending to the mock `server`, authorized by a [`crate::token::StaticTokener`] with
+/// the `access` token.
+#[cfg(test)]
+pub(crate) async fn mock_api(
+    server: &mut mockito::ServerGuard,
+) -> Api<crate::token::StaticTokener> {
+    mock_api_with_config(server, ApiConfig::default()).await
+}
+
+/// Like [`mock_api`], with `config` sending to the mock `server`.
+#[cfg(test)]
+pub(crate) async fn mock_api_with_config(
+    server: &mut mockito::ServerGuard,
+    config: ApiConfig,
+) -> Api<crate::token::StaticTokener> {
+    let mock_probe = server
+        .mock("GET", "/marketdata/v1/AAPL/quotes")
+        .match_header("authorization", "Bearer access")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body_from_file(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/QuoteResponse_real.json"
+        ))
+        .create_async()
+        .await;
+
+    let api = Api::new_with_config(
+        crate::token::StaticTokener::new("access".to_string()),
+        config.with_base_url(&server.url()),
+    )
+    .await
+    .unwrap();
+    mock_probe.assert_async().await;
+    mock_probe.remove_async().await;
+    api
 }
 
 #[cfg(test)]
@@ -450,6 +893,7 @@ mod tests {
     use crate::token::channel_messenger::stdio_messenger::StdioMessenger;
     use crate::token::channel_messenger::ChannelMessenger;
     use crate::token::TokenChecker;
+    use crate::token::{DynTokener, StaticTokener};
 
     async fn client() -> Api<TokenChecker<impl ChannelMessenger>> {
         #[allow(clippy::option_env_unwrap)]
@@ -493,6 +937,591 @@ mod tests {
         Api::new(token_checker, client).await.unwrap()
     }
 
+    #[test]
+    fn test_api_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<Api<StaticTokener>>();
+        assert_send_sync::<Api<TokenChecker<LocalServerMessenger>>>();
+        assert_send_sync::<Api<Box<dyn DynTokener>>>();
+    }
+
+    #[tokio::test]
+    async fn test_api_with_base_url() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock_account = server
+            .mock("GET", "/trader/v1/accounts/account_number")
+            .match_header("authorization", "Bearer access")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/Trader/Account_real.json"
+            ))
+            .create_async()
+            .await;
+
+        let api = mock_api(&mut server).await;
+        let rsp = api.get_balances("account_number".into()).await;
+        mock_account.assert_async().await;
+        assert!(matches!(rsp.unwrap(), model::Balances::Cash { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_monitor_positions() {
+        use futures::StreamExt;
+
+        let mut server = mockito::Server::new_async().await;
+
+        let quotes: serde_json::Value = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/QuoteResponse_real.json"
+        )))
+        .unwrap();
+        let mut position: serde_json::Value =
+            serde_json::from_str::<serde_json::Value>(include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/Trader/Account.json"
+            )))
+            .unwrap()["securitiesAccount"]["positions"][0]
+                .clone();
+        position["longQuantity"] = 10.into();
+        position["instrument"] = serde_json::json!({
+            "assetType": "EQUITY",
+            "cusip": "037833100",
+            "symbol": "AAPL",
+            "description": "APPLE INC",
+            "instrumentId": 1,
+        });
+        let mut account: serde_json::Value = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Account_real.json"
+        )))
+        .unwrap();
+        account["securitiesAccount"]["positions"] = serde_json::json!([position]);
+
+        let mock_account = server
+            .mock("GET", "/trader/v1/accounts/account_number")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "fields".into(),
+                "positions".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(account.to_string())
+            .expect(2)
+            .create_async()
+            .await;
+        let mock_quotes = server
+            .mock("GET", "/marketdata/v1/quotes")
+            .match_query(mockito::Matcher::UrlEncoded(
+                "symbols".into(),
+                "AAPL".into(),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::json!({ "AAPL": quotes["AAPL"] }).to_string())
+            .expect(2)
+            .create_async()
+            .await;
+
+        let api = mock_api(&mut server).await;
+        let polls: Vec<_> = api
+            .monitor_positions(
+                "account_number".into(),
+                std::time::Duration::from_millis(10),
+            )
+            .take(2)
+            .collect()
+            .await;
+        mock_account.assert_async().await;
+        mock_quotes.assert_async().await;
+
+        for poll in polls {
+            let poll = poll.unwrap();
+            assert_eq!(poll.len(), 1);
+            assert_eq!(poll[0].position.instrument.symbol(), "AAPL");
+            let quote = poll[0].quote.as_ref().unwrap();
+            assert_eq!(quote.symbol(), "AAPL");
+            float_cmp::assert_approx_eq!(f64, 1899.0, poll[0].market_value().unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_order() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mut order: serde_json::Value = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Order_real.json"
+        )))
+        .unwrap();
+
+        order["status"] = "WORKING".into();
+        let working = order.to_string();
+        let mock_working = server
+            .mock("GET", "/trader/v1/accounts/account_number/orders/123")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(&working)
+            .expect(2)
+            .create_async()
+            .await;
+        order["status"] = "FILLED".into();
+        let mock_filled = server
+            .mock("GET", "/trader/v1/accounts/account_number/orders/123")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(order.to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let api = mock_api(&mut server).await;
+        let order = api
+            .wait_for_order(
+                "account_number".into(),
+                123,
+                std::time::Duration::from_secs(5),
+                std::time::Duration::from_millis(10),
+            )
+            .await
+            .unwrap();
+        mock_working.assert_async().await;
+        mock_filled.assert_async().await;
+        assert_eq!(order.status, model::trader::order::Status::Filled);
+
+        // never done
+        let mock_working = server
+            .mock("GET", "/trader/v1/accounts/account_number/orders/456")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(&working)
+            .expect_at_least(1)
+            .create_async()
+            .await;
+        let timeout = std::time::Duration::from_millis(50);
+        let result = api
+            .wait_for_order(
+                "account_number".into(),
+                456,
+                timeout,
+                std::time::Duration::from_millis(10),
+            )
+            .await;
+        mock_working.assert_async().await;
+        assert!(matches!(result, Err(Error::Timeout(x)) if x == timeout));
+
+        // done without a fill
+        let mut order: serde_json::Value = serde_json::from_str(&working).unwrap();
+        order["status"] = "CANCELED".into();
+        let mock_canceled = server
+            .mock("GET", "/trader/v1/accounts/account_number/orders/789")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(order.to_string())
+            .create_async()
+            .await;
+        let result = api
+            .wait_for_order_fill(
+                "account_number".into(),
+                789,
+                std::time::Duration::from_secs(5),
+                std::time::Duration::from_millis(10),
+            )
+            .await;
+        mock_canceled.assert_async().await;
+        assert!(matches!(
+            result,
+            Err(Error::OrderNotFilled(
+                model::trader::order::Status::Canceled
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_placed_order() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mut order: serde_json::Value = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Order_real.json"
+        )))
+        .unwrap();
+        let submitted: model::OrderRequest = serde_json::from_value::<model::Order>(order.clone())
+            .unwrap()
+            .into();
+
+        // an order type this crate does not know
+        order["orderType"] = "SOMETHING_NEW".into();
+        let mock = server
+            .mock("GET", "/trader/v1/accounts/account_number/orders/123")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(order.to_string())
+            .create_async()
+            .await;
+
+        let api = mock_api(&mut server).await;
+        let changes = api
+            .verify_placed_order("account_number".into(), 123, &submitted)
+            .await
+            .unwrap();
+        mock.assert_async().await;
+        assert_eq!(
+            changes,
+            vec![model::FieldChange {
+                field: "orderType".to_string(),
+                submitted: "LIMIT".into(),
+                recorded: serde_json::Value::Null,
+            }]
+        );
+
+        // Schwab fills in the quantityType of the legs
+        let order = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Order.json"
+        ));
+        let mut submitted: model::OrderRequest =
+            serde_json::from_str::<model::Order>(order).unwrap().into();
+        for leg in submitted.order_leg_collection.iter_mut().flatten() {
+            leg.quantity_type = None;
+        }
+        let mock = server
+            .mock("GET", "/trader/v1/accounts/account_number/orders/456")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(order)
+            .create_async()
+            .await;
+        let changes = api
+            .verify_placed_order("account_number".into(), 456, &submitted)
+            .await
+            .unwrap();
+        mock.assert_async().await;
+        assert_eq!(changes, vec![]);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_all_orders() {
+        let mut server = mockito::Server::new_async().await;
+
+        let order: serde_json::Value = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Order_real.json"
+        )))
+        .unwrap();
+        let orders: Vec<_> = [
+            (1, "WORKING", true),
+            (2, "WORKING", true),
+            (3, "FILLED", false),
+        ]
+        .into_iter()
+        .map(|(id, status, cancelable)| {
+            let mut order = order.clone();
+            order["orderId"] = id.into();
+            order["status"] = status.into();
+            order["cancelable"] = cancelable.into();
+            order
+        })
+        .collect();
+
+        let mock_orders = server
+            .mock("GET", "/trader/v1/accounts/account_number/orders")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::Value::from(orders).to_string())
+            .expect(2)
+            .create_async()
+            .await;
+        let mock_delete_1 = server
+            .mock("DELETE", "/trader/v1/accounts/account_number/orders/1")
+            .with_status(200)
+            .expect(2)
+            .create_async()
+            .await;
+        let mock_delete_2 = server
+            .mock("DELETE", "/trader/v1/accounts/account_number/orders/2")
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let api = mock_api(&mut server).await;
+        let to = chrono::Utc::now();
+        let from = to - chrono::TimeDelta::days(1);
+        let canceled = api
+            .cancel_all_orders("account_number".into(), from, to)
+            .await
+            .unwrap();
+        assert_eq!(canceled, vec![1, 2]);
+        mock_delete_2.assert_async().await;
+
+        // the second one fails, the first is still canceled
+        let mock_delete_2 = server
+            .mock("DELETE", "/trader/v1/accounts/account_number/orders/2")
+            .with_status(400)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/Trader/ServiceError_real.json"
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+        let result = api
+            .cancel_all_orders("account_number".into(), from, to)
+            .await;
+        mock_orders.assert_async().await;
+        mock_delete_1.assert_async().await;
+        mock_delete_2.assert_async().await;
+        let Err(Error::PartialCancel(canceled, failed)) = result else {
+            panic!("expected a partial cancel, got {result:?}");
+        };
+        assert_eq!(canceled, vec![1]);
+        assert_eq!(failed.len(), 1);
+        assert!(matches!(failed[0], (2, Error::Service(_))));
+    }
+
+    #[tokio::test]
+    async fn test_symbol() {
+        let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
+
+        // the quote by a literal
+        let mock = server
+            .mock("GET", "/marketdata/v1/AAPL/quotes")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/MarketData/QuoteResponse_real.json"
+            ))
+            .create_async()
+            .await;
+
+        let quote = api.get_quote("AAPL").await.unwrap().send().await.unwrap();
+        assert_eq!(quote.symbol(), "AAPL");
+        mock.assert_async().await;
+
+        // rejected before sending
+        assert!(matches!(
+            api.get_quote(String::new()).await,
+            Err(Error::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            api.get_price_history("A".repeat(21)).await,
+            Err(Error::InvalidParameter(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_account_number() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock_account_numbers = server
+            .mock("GET", "/trader/v1/accounts/accountNumbers")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"accountNumber": "12345678", "hashValue": "ENCRYPTED"}]"#)
+            .create_async()
+            .await;
+        let mock_account = server
+            .mock("GET", "/trader/v1/accounts/ENCRYPTED")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/Trader/Account_real.json"
+            ))
+            .create_async()
+            .await;
+
+        let api = mock_api(&mut server).await;
+        let account_numbers = api
+            .get_account_numbers()
+            .await
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+        let account_number = account_numbers[0].hash_value.clone();
+        assert_eq!(account_number.as_str(), "ENCRYPTED");
+        api.get_account(account_number)
+            .await
+            .unwrap()
+            .send()
+            .await
+            .unwrap();
+        mock_account_numbers.assert_async().await;
+        mock_account.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_api_config() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let mut default_headers = reqwest::header::HeaderMap::new();
+        default_headers.insert("x-api-key", "secret-key".parse().unwrap());
+        let config = ApiConfig {
+            default_headers,
+            default_timeout: Some(std::time::Duration::from_secs(5)),
+            ..ApiConfig::default()
+        };
+        let api = mock_api_with_config(&mut server, config)
+            .await
+            .with_retry(RetryConfig::default())
+            .with_rate_limit(2.0);
+
+        let config = api.config();
+        assert_eq!(config.trader_url, format!("{url}/trader/v1"));
+        assert_eq!(config.market_data_url, format!("{url}/marketdata/v1"));
+        assert_eq!(
+            config.default_timeout,
+            Some(std::time::Duration::from_secs(5))
+        );
+        assert_eq!(config.retry, Some(RetryConfig::default()));
+        float_cmp::assert_approx_eq!(
+            f64,
+            2.0,
+            config.rate_limiter.as_ref().unwrap().requests_per_second()
+        );
+
+        let debug = format!("{config:?}");
+        assert!(debug.contains("x-api-key"), "{debug}");
+        assert!(!debug.contains("secret-key"), "{debug}");
+        assert!(!debug.contains("access"), "{debug}");
+    }
+
+    #[tokio::test]
+    async fn test_api_with_retry() {
+        let mut server = mockito::Server::new_async().await;
+
+        let mock_unavailable = server
+            .mock("GET", "/trader/v1/accounts/account_number")
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+        let mock_account = server
+            .mock("GET", "/trader/v1/accounts/account_number")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/Trader/Account_real.json"
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let api = mock_api(&mut server).await.with_retry(RetryConfig {
+            max_attempts: 2,
+            initial_backoff: std::time::Duration::from_millis(1),
+            max_backoff: std::time::Duration::from_millis(1),
+            ..RetryConfig::default()
+        });
+        let rsp = api.get_balances("account_number".into()).await;
+        mock_unavailable.assert_async().await;
+        mock_account.assert_async().await;
+        assert!(rsp.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_api_with_reauthorization() {
+        /// Hands out `token0`, `token1`, ...
+        #[derive(Debug, Default)]
+        struct CountingTokener(std::sync::atomic::AtomicUsize);
+
+        impl Tokener for CountingTokener {
+            async fn get_access_token(&self) -> Result<String, Error> {
+                let n = self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Ok(format!("token{n}"))
+            }
+
+            async fn redo_authorization(&self) -> Result<(), Error> {
+                Ok(())
+            }
+        }
+
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        let mut mocks = vec![
+            server
+                .mock("GET", "/marketdata/v1/AAPL/quotes")
+                .match_header("authorization", "Bearer token0")
+                .with_status(200)
+                .with_header("content-type", "application/json")
+                .with_body_from_file(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/tests/model/MarketData/QuoteResponse_real.json"
+                ))
+                .create_async()
+                .await,
+        ];
+        for (path, token, body) in [
+            (
+                "/marketdata/v1/quotes",
+                1,
+                "/tests/model/MarketData/QuoteResponse_real.json",
+            ),
+            (
+                "/trader/v1/accounts",
+                3,
+                "/tests/model/Trader/Accounts_real.json",
+            ),
+        ] {
+            mocks.push(
+                server
+                    .mock("GET", path)
+                    .match_query(mockito::Matcher::Any)
+                    .match_header("authorization", format!("Bearer token{token}").as_str())
+                    .with_status(401)
+                    .create_async()
+                    .await,
+            );
+            mocks.push(
+                server
+                    .mock("GET", path)
+                    .match_query(mockito::Matcher::Any)
+                    .match_header(
+                        "authorization",
+                        format!("Bearer token{}", token + 1).as_str(),
+                    )
+                    .with_status(200)
+                    .with_header("content-type", "application/json")
+                    .with_body_from_file(format!("{}{body}", env!("CARGO_MANIFEST_DIR")))
+                    .create_async()
+                    .await,
+            );
+        }
+
+        let api = Api::new_with_config(
+            std::sync::Arc::new(CountingTokener::default()),
+            ApiConfig::default().with_base_url(&url),
+        )
+        .await
+        .unwrap()
+        .with_reauthorization();
+
+        let rsp = api
+            .get_quotes(vec!["AAPL".to_string()])
+            .await
+            .unwrap()
+            .send()
+            .await;
+        assert!(rsp.is_ok());
+        let rsp = api.get_accounts().await.unwrap().send().await;
+        assert!(rsp.is_ok());
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+    }
+
     #[cfg_attr(
         not(feature = "test_online"),
         ignore = r#"Without the "test_online" feature enabled, to activate it, corresponding SCHWAB_API_KEY and SCHWAB_SECRET need to be provided in the environment."#
@@ -575,8 +1604,11 @@ mod tests {
     #[tokio::test]
     async fn test_get_option_chains() {
         let api = client().await;
-        let mut req = api.get_option_chains("AAPL".into()).await.unwrap();
-        req.days_to_expiration(3)
+        let req = api
+            .get_option_chains("AAPL")
+            .await
+            .unwrap()
+            .days_to_expiration(3)
             .exp_month(parameter::Month::All)
             .contract_type(parameter::ContractType::All);
         let rsp = req.send().await.unwrap();
@@ -602,10 +1634,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_option_expiration_chain() {
         let api = client().await;
-        let req = api
-            .get_option_expiration_chain("AAPL".into())
-            .await
-            .unwrap();
+        let req = api.get_option_expiration_chain("AAPL").await.unwrap();
         let rsp = req.send().await.unwrap();
         dbg!(rsp);
     }
@@ -617,7 +1646,23 @@ mod tests {
     #[tokio::test]
     async fn test_get_price_history() {
         let api = client().await;
-        let req = api.get_price_history("AAPL".into()).await.unwrap();
+        let req = api.get_price_history("AAPL").await.unwrap();
+        let rsp = req.send().await.unwrap();
+        dbg!(rsp);
+    }
+
+    #[cfg_attr(
+        not(feature = "test_online"),
+        ignore = r#"Without the "test_online" feature enabled, to activate it, corresponding SCHWAB_API_KEY and SCHWAB_SECRET need to be provided in the environment."#
+    )]
+    #[tokio::test]
+    async fn test_get_price_history_presets() {
+        let api = client().await;
+        let req = api.get_price_history_daily("AAPL", 1).await.unwrap();
+        let rsp = req.send().await.unwrap();
+        dbg!(rsp);
+
+        let req = api.get_price_history_minute("AAPL", 1, 5).await.unwrap();
         let rsp = req.send().await.unwrap();
         dbg!(rsp);
     }
@@ -629,7 +1674,7 @@ mod tests {
     #[tokio::test]
     async fn test_get_movers() {
         let api = client().await;
-        let req = api.get_movers("$DJI".into()).await.unwrap();
+        let req = api.get_movers("$DJI").await.unwrap();
         let rsp = req.send().await.unwrap();
         dbg!(rsp);
     }
@@ -714,7 +1759,7 @@ mod tests {
         dbg!(rsp);
     }
 
-    async fn account_number() -> String {
+    async fn account_number() -> model::EncryptedAccountNumber {
         let api = client().await;
         let req = api.get_account_numbers().await.unwrap();
         let rsp = req.send().await.unwrap();
@@ -745,6 +1790,17 @@ mod tests {
         dbg!(rsp);
     }
 
+    #[cfg_attr(
+        not(feature = "test_online"),
+        ignore = r#"Without the "test_online" feature enabled, to activate it, corresponding SCHWAB_API_KEY and SCHWAB_SECRET need to be provided in the environment."#
+    )]
+    #[tokio::test]
+    async fn test_get_balances() {
+        let api = client().await;
+        let rsp = api.get_balances(account_number().await).await.unwrap();
+        dbg!(rsp);
+    }
+
     #[cfg_attr(
         not(feature = "test_online"),
         ignore = r#"Without the "test_online" feature enabled, to activate it, corresponding SCHWAB_API_KEY and SCHWAB_SECRET need to be provided in the environment."#
@@ -755,7 +1811,7 @@ mod tests {
         let req = api
             .get_account_orders(
                 account_number().await,
-                chrono::NaiveDate::from_ymd_opt(2024, 1, 1)
+                chrono::NaiveDate::from_ymd_opt(2024, 11, 2)
                     .unwrap()
                     .and_hms_milli_opt(0, 0, 1, 444)
                     .unwrap()
@@ -821,7 +1877,7 @@ mod tests {
             .post_account_order(account_number().await, order_post.clone())
             .await
             .unwrap();
-        req.send().await.unwrap();
+        let order_id = req.send().await.unwrap();
 
         // post check
         let req = api
@@ -841,6 +1897,7 @@ mod tests {
         let orders = req.send().await.unwrap();
         dbg!(&orders);
         let order_post_check = orders[0].clone();
+        assert_eq!(order_post_check.order_id, order_id);
         assert_eq!(
             order_post_check.session,
             model::trader::order::Session::Normal
@@ -854,11 +1911,14 @@ mod tests {
             order_post_check.order_type,
             model::trader::order::OrderType::Limit
         );
+        // Schwab records the ETF as a collective investment
         assert_eq!(
             Into::<InstrumentRequest>::into(
                 order_post_check.order_leg_collection[0].instrument.clone()
             ),
-            symbol
+            InstrumentRequest::CollectiveInvestment {
+                symbol: "VEA".to_string(),
+            }
         );
         assert_eq!(
             Into::<Instruction>::into(order_post_check.order_leg_collection[0].instruction),
@@ -871,7 +1931,6 @@ mod tests {
         );
 
         // put
-        let order_id = order_post_check.order_id;
         let mut order_put: model::OrderRequest = order_post_check.into();
         order_put.price = Some(modified_price);
         let req = api
@@ -905,7 +1964,9 @@ mod tests {
             Into::<InstrumentRequest>::into(
                 order_put_check.order_leg_collection[0].instrument.clone()
             ),
-            symbol
+            InstrumentRequest::CollectiveInvestment {
+                symbol: "VEA".to_string(),
+            }
         );
         assert_eq!(
             Into::<Instruction>::into(order_put_check.order_leg_collection[0].instruction),
@@ -961,7 +2022,7 @@ mod tests {
         let api = client().await;
         let req = api
             .get_accounts_orders(
-                chrono::NaiveDate::from_ymd_opt(2024, 6, 1)
+                chrono::NaiveDate::from_ymd_opt(2025, 3, 21)
                     .unwrap()
                     .and_hms_milli_opt(0, 0, 1, 444)
                     .unwrap()
@@ -1019,7 +2080,7 @@ mod tests {
                     .unwrap()
                     .and_local_timezone(chrono::Utc)
                     .unwrap(),
-                TransactionType::Trade,
+                vec![TransactionType::Trade],
             )
             .await
             .unwrap();
@@ -1046,7 +2107,7 @@ mod tests {
                     .unwrap()
                     .and_local_timezone(chrono::Utc)
                     .unwrap(),
-                TransactionType::Trade,
+                vec![TransactionType::Trade],
             )
             .await
             .unwrap();
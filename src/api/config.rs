@@ -0,0 +1,143 @@
+//! Configuration shared by every request of an [`Api`](crate::Api).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use reqwest::header::HeaderMap;
+use reqwest::Client;
+
+use super::endpoints::{SERVER_MARKETDATA, SERVER_TRADER};
+use super::rate_limit::RateLimiter;
+use super::retry::RetryConfig;
+use crate::error::Error;
+
+/// Settings applied to every request sent by an [`Api`](crate::Api).
+///
+/// The `Debug` output only lists the names of [`ApiConfig::default_headers`], so it can be logged.
+#[derive(Clone)]
+pub struct ApiConfig {
+    /// Static headers sent with every request, e.g. the key of an API gateway in front of Schwab.
+    pub default_headers: HeaderMap,
+    /// Base URL of the Trader API, `https://api.schwabapi.com/trader/v1` by default.
+    pub trader_url: String,
+    /// Base URL of the Market Data API, `https://api.schwabapi.com/marketdata/v1` by default.
+    pub market_data_url: String,
+    /// Retry policy for throttled and failed requests, disabled by default.
+    pub retry: Option<RetryConfig>,
+    /// Shared by every request of the [`Api`](crate::Api), no limit by default.
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// The time allowed for a response, including retries and rate limiting.
+    /// Expiry fails with [`Error::Timeout`]. No timeout by default.
+    ///
+    /// The `timeout` setter of every request overrides it for that request alone.
+    pub default_timeout: Option<Duration>,
+    /// Set by [`Api::with_reauthorization`](crate::Api::with_reauthorization).
+    pub(crate) reauthorizer: Option<Reauthorizer>,
+}
+
+/// Fetches the access token again when a request is answered with `401 Unauthorized`.
+#[derive(Clone)]
+pub(crate) struct Reauthorizer(
+    pub(crate) Arc<dyn Fn() -> BoxFuture<'static, Result<String, Error>> + Send + Sync>,
+);
+
+impl std::fmt::Debug for Reauthorizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Reauthorizer")
+    }
+}
+
+/// The part of [`ApiConfig`] used when sending a request.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SendOptions {
+    pub(crate) retry: Option<RetryConfig>,
+    pub(crate) rate_limiter: Option<Arc<RateLimiter>>,
+    pub(crate) reauthorizer: Option<Reauthorizer>,
+    pub(crate) timeout: Option<Duration>,
+    /// Sending the request twice may act twice, e.g. place an order twice, so it is only retried on connect errors.
+    pub(crate) non_idempotent: bool,
+}
+
+impl std::fmt::Debug for ApiConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiConfig")
+            .field(
+                "default_headers",
+                &self.default_headers.keys().collect::<Vec<_>>(),
+            )
+            .field("trader_url", &self.trader_url)
+            .field("market_data_url", &self.market_data_url)
+            .field("retry", &self.retry)
+            .field("rate_limiter", &self.rate_limiter)
+            .field("default_timeout", &self.default_timeout)
+            .field("reauthorizer", &self.reauthorizer)
+            .finish()
+    }
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            default_headers: HeaderMap::default(),
+            trader_url: SERVER_TRADER.to_string(),
+            market_data_url: SERVER_MARKETDATA.to_string(),
+            retry: None,
+            rate_limiter: None,
+            default_timeout: None,
+            reauthorizer: None,
+        }
+    }
+}
+
+impl ApiConfig {
+    /// Point both APIs at another server, e.g. a sandbox or a mock server.
+    ///
+    /// The production paths are kept: `{url}/trader/v1` and `{url}/marketdata/v1`.
+    #[must_use]
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        let url = url.trim_end_matches('/');
+        self.trader_url = format!("{url}/trader/v1");
+        self.market_data_url = format!("{url}/marketdata/v1");
+        self
+    }
+
+    pub(crate) fn send_options(&self) -> SendOptions {
+        SendOptions {
+            retry: self.retry.clone(),
+            rate_limiter: self.rate_limiter.clone(),
+            reauthorizer: self.reauthorizer.clone(),
+            timeout: self.default_timeout,
+            non_idempotent: false,
+        }
+    }
+
+    pub(crate) fn client(&self) -> Result<Client, Error> {
+        Client::builder()
+            .default_headers(self.default_headers.clone())
+            .build()
+            .map_err(std::convert::Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_base_url() {
+        let config = ApiConfig::default();
+        assert_eq!(config.trader_url, "https://api.schwabapi.com/trader/v1");
+        assert_eq!(
+            config.market_data_url,
+            "https://api.schwabapi.com/marketdata/v1"
+        );
+
+        let config = config.with_base_url("http://127.0.0.1:1234/");
+        assert_eq!(config.trader_url, "http://127.0.0.1:1234/trader/v1");
+        assert_eq!(
+            config.market_data_url,
+            "http://127.0.0.1:1234/marketdata/v1"
+        );
+    }
+}
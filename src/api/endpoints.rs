@@ -3,8 +3,8 @@ use urlencoding::encode;
 
 use super::parameter::Market;
 
-const SERVER_TRADER: &str = "https://api.schwabapi.com/trader/v1";
-const SERVER_MARKETDATA: &str = "https://api.schwabapi.com/marketdata/v1";
+pub(crate) const SERVER_TRADER: &str = "https://api.schwabapi.com/trader/v1";
+pub(crate) const SERVER_MARKETDATA: &str = "https://api.schwabapi.com/marketdata/v1";
 
 #[derive(Debug)]
 pub(crate) enum EndpointAccount {
@@ -38,8 +38,8 @@ impl EndpointAccount {
     }
 
     /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_TRADER}{}", self.url_endpoint())
+    pub(crate) fn url(&self, server: &str) -> String {
+        format!("{server}{}", self.url_endpoint())
     }
 }
 
@@ -106,8 +106,8 @@ impl EndpointOrder {
     }
 
     /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_TRADER}{}", self.url_endpoint())
+    pub(crate) fn url(&self, server: &str) -> String {
+        format!("{server}{}", self.url_endpoint())
     }
 }
 
@@ -148,8 +148,8 @@ impl EndpointTransaction {
     }
 
     /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_TRADER}{}", self.url_endpoint())
+    pub(crate) fn url(&self, server: &str) -> String {
+        format!("{server}{}", self.url_endpoint())
     }
 }
 
@@ -170,8 +170,8 @@ impl EndpointUserPreference {
     }
 
     /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_TRADER}{}", self.url_endpoint())
+    pub(crate) fn url(&self, server: &str) -> String {
+        format!("{server}{}", self.url_endpoint())
     }
 }
 
@@ -201,8 +201,8 @@ impl EndpointQuote {
     }
 
     /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_MARKETDATA}{}", self.url_endpoint())
+    pub(crate) fn url(&self, server: &str) -> String {
+        format!("{server}{}", self.url_endpoint())
     }
 }
 
@@ -223,8 +223,8 @@ impl EndpointOptionChain {
     }
 
     /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_MARKETDATA}{}", self.url_endpoint())
+    pub(crate) fn url(&self, server: &str) -> String {
+        format!("{server}{}", self.url_endpoint())
     }
 }
 
@@ -245,8 +245,8 @@ impl EndpointOptionExpirationChain {
     }
 
     /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_MARKETDATA}{}", self.url_endpoint())
+    pub(crate) fn url(&self, server: &str) -> String {
+        format!("{server}{}", self.url_endpoint())
     }
 }
 
@@ -267,8 +267,8 @@ impl EndpointPriceHistory {
     }
 
     /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_MARKETDATA}{}", self.url_endpoint())
+    pub(crate) fn url(&self, server: &str) -> String {
+        format!("{server}{}", self.url_endpoint())
     }
 }
 
@@ -292,8 +292,8 @@ impl EndpointMover {
     }
 
     /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_MARKETDATA}{}", self.url_endpoint())
+    pub(crate) fn url(&self, server: &str) -> String {
+        format!("{server}{}", self.url_endpoint())
     }
 }
 
@@ -325,8 +325,8 @@ impl EndpointMarketHour {
     }
 
     /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_MARKETDATA}{}", self.url_endpoint())
+    pub(crate) fn url(&self, server: &str) -> String {
+        format!("{server}{}", self.url_endpoint())
     }
 }
 
@@ -340,7 +340,7 @@ pub(crate) enum EndpointInstrument {
     // GET
     // /instruments/{cusip_id}
     // Get Instrument by specific cusip
-    Instrutment { cusip_id: String },
+    Instrument { cusip_id: String },
 }
 
 impl EndpointInstrument {
@@ -348,7 +348,7 @@ impl EndpointInstrument {
     pub(crate) fn url_endpoint(&self) -> String {
         match self {
             EndpointInstrument::Instruments => "/instruments".to_string(),
-            EndpointInstrument::Instrutment { ref cusip_id } => {
+            EndpointInstrument::Instrument { ref cusip_id } => {
                 let cusip_id = encode(cusip_id);
                 format!("/instruments/{cusip_id}")
             }
@@ -356,8 +356,8 @@ impl EndpointInstrument {
     }
 
     /// defines the URL include server
-    pub(crate) fn url(&self) -> String {
-        format!("{SERVER_MARKETDATA}{}", self.url_endpoint())
+    pub(crate) fn url(&self, server: &str) -> String {
+        format!("{server}{}", self.url_endpoint())
     }
 }
 
@@ -371,12 +371,12 @@ mod tests {
     fn test_endpoint_account() {
         assert_eq!(
             "https://api.schwabapi.com/trader/v1/accounts/accountNumbers",
-            EndpointAccount::AccountNumbers.url()
+            EndpointAccount::AccountNumbers.url(SERVER_TRADER)
         );
 
         assert_eq!(
             "https://api.schwabapi.com/trader/v1/accounts",
-            EndpointAccount::Accounts.url()
+            EndpointAccount::Accounts.url(SERVER_TRADER)
         );
 
         assert_eq!(
@@ -384,7 +384,7 @@ mod tests {
             EndpointAccount::Account {
                 account_number: "123456".to_string()
             }
-            .url()
+            .url(SERVER_TRADER)
         );
     }
 
@@ -395,7 +395,7 @@ mod tests {
             EndpointOrder::OrdersAccount {
                 account_number: "123456".to_string()
             }
-            .url()
+            .url(SERVER_TRADER)
         );
 
         assert_eq!(
@@ -404,12 +404,12 @@ mod tests {
                 account_number: "123456".to_string(),
                 order_id: 789
             }
-            .url()
+            .url(SERVER_TRADER)
         );
 
         assert_eq!(
             "https://api.schwabapi.com/trader/v1/orders",
-            EndpointOrder::Orders.url()
+            EndpointOrder::Orders.url(SERVER_TRADER)
         );
 
         assert_eq!(
@@ -417,7 +417,7 @@ mod tests {
             EndpointOrder::PreviewOrderAccount {
                 account_number: "123456".to_string()
             }
-            .url()
+            .url(SERVER_TRADER)
         );
     }
 
@@ -428,7 +428,7 @@ mod tests {
             EndpointTransaction::TransactionsAccount {
                 account_number: "123456".to_string()
             }
-            .url()
+            .url(SERVER_TRADER)
         );
 
         assert_eq!(
@@ -437,7 +437,7 @@ mod tests {
                 account_number: "123456".to_string(),
                 transaction_id: 789
             }
-            .url()
+            .url(SERVER_TRADER)
         );
     }
 
@@ -445,7 +445,7 @@ mod tests {
     fn test_endpoint_user_preference() {
         assert_eq!(
             "https://api.schwabapi.com/trader/v1/userPreference",
-            EndpointUserPreference::UserPreference.url()
+            EndpointUserPreference::UserPreference.url(SERVER_TRADER)
         );
     }
 
@@ -453,7 +453,7 @@ mod tests {
     fn test_endpoint_quote() {
         assert_eq!(
             "https://api.schwabapi.com/marketdata/v1/quotes",
-            EndpointQuote::Quotes.url()
+            EndpointQuote::Quotes.url(SERVER_MARKETDATA)
         );
 
         assert_eq!(
@@ -461,7 +461,7 @@ mod tests {
             EndpointQuote::Quote {
                 symbol_id: "ABC".to_string()
             }
-            .url()
+            .url(SERVER_MARKETDATA)
         );
     }
 
@@ -469,7 +469,7 @@ mod tests {
     fn test_endpoint_option_chain() {
         assert_eq!(
             "https://api.schwabapi.com/marketdata/v1/chains",
-            EndpointOptionChain::Chains.url()
+            EndpointOptionChain::Chains.url(SERVER_MARKETDATA)
         );
     }
 
@@ -477,7 +477,7 @@ mod tests {
     fn test_endpoint_option_expiration_chain() {
         assert_eq!(
             "https://api.schwabapi.com/marketdata/v1/expirationchain",
-            EndpointOptionExpirationChain::ExpirationChain.url()
+            EndpointOptionExpirationChain::ExpirationChain.url(SERVER_MARKETDATA)
         );
     }
 
@@ -485,7 +485,7 @@ mod tests {
     fn test_endpoint_price_history() {
         assert_eq!(
             "https://api.schwabapi.com/marketdata/v1/pricehistory",
-            EndpointPriceHistory::PriceHistory.url()
+            EndpointPriceHistory::PriceHistory.url(SERVER_MARKETDATA)
         );
     }
 
@@ -496,7 +496,7 @@ mod tests {
             EndpointMover::Mover {
                 symbol_id: "ABC".to_string()
             }
-            .url()
+            .url(SERVER_MARKETDATA)
         );
     }
 
@@ -504,7 +504,7 @@ mod tests {
     fn test_endpoint_market_hour() {
         assert_eq!(
             "https://api.schwabapi.com/marketdata/v1/markets",
-            EndpointMarketHour::Markets.url()
+            EndpointMarketHour::Markets.url(SERVER_MARKETDATA)
         );
 
         assert_eq!(
@@ -512,7 +512,7 @@ mod tests {
             EndpointMarketHour::Market {
                 market_id: Market::Equity,
             }
-            .url()
+            .url(SERVER_MARKETDATA)
         );
     }
 
@@ -520,15 +520,15 @@ mod tests {
     fn test_endpoint_instrument() {
         assert_eq!(
             "https://api.schwabapi.com/marketdata/v1/instruments",
-            EndpointInstrument::Instruments.url()
+            EndpointInstrument::Instruments.url(SERVER_MARKETDATA)
         );
 
         assert_eq!(
             "https://api.schwabapi.com/marketdata/v1/instruments/123456",
-            EndpointInstrument::Instrutment {
+            EndpointInstrument::Instrument {
                 cusip_id: "123456".to_string()
             }
-            .url()
+            .url(SERVER_MARKETDATA)
         );
     }
 }
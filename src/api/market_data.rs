@@ -6,17 +6,19 @@ use std::collections::HashMap;
 
 use super::parameter::{
     ContractType, Entitlement, FrequencyType, Market, Month, OptionChainStrategy, PeriodType,
-    Projection, QuoteField, SortAttribute,
+    Projection, QuoteField, Range, SortAttribute,
 };
 use crate::api::Error;
 use crate::model;
 
+use super::config::SendOptions;
 use super::endpoints;
+use super::response;
+use super::retry;
+use super::ApiConfig;
 
 async fn process_error(rsp: Response) -> Result<Error, Error> {
-    let json = rsp.text().await?;
-    dbg!(&json);
-    let error_response: model::ErrorResponse = serde_json::from_str(&json)?;
+    let error_response = response::json::<model::ErrorResponse>(rsp).await?;
     Ok(Error::Response(error_response))
 }
 
@@ -24,6 +26,7 @@ async fn process_error(rsp: Response) -> Result<Error, Error> {
 #[derive(Debug)]
 pub struct GetQuotesRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 
     symbols: Vec<String>,
 
@@ -40,24 +43,43 @@ pub struct GetQuotesRequest {
     ///
     /// If ETF symbol ABC is in request and indicative=true API will return quotes for ABC and its corresponding indicative quote for $ABC.IV
     indicative: Option<bool>,
+
+    /// The max number of symbols per call, larger lists are split into concurrent calls.
+    ///
+    /// Default value : `500`
+    chunk_size: usize,
 }
 
 impl GetQuotesRequest {
+    const DEFAULT_CHUNK_SIZE: usize = 500;
+
     fn endpoint() -> endpoints::EndpointQuote {
         endpoints::EndpointQuote::Quotes
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, symbols: Vec<String>) -> Self {
-        let req = client.get(Self::endpoint().url()).bearer_auth(access_token);
-        Self::new_with(req, symbols)
+    pub(crate) fn new(
+        client: &Client,
+        config: &ApiConfig,
+        access_token: String,
+        symbols: Vec<String>,
+    ) -> Self {
+        let req = client
+            .get(Self::endpoint().url(&config.market_data_url))
+            .bearer_auth(access_token);
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req, symbols)
+        }
     }
 
     fn new_with(req: RequestBuilder, symbols: Vec<String>) -> Self {
         Self {
             req,
+            send_options: SendOptions::default(),
             symbols,
             fields: None,
             indicative: None,
+            chunk_size: Self::DEFAULT_CHUNK_SIZE,
         }
     }
 
@@ -68,7 +90,8 @@ impl GetQuotesRequest {
     /// Dont send this attribute for full response.
     ///
     /// Default value : `all`
-    pub fn fields(&mut self, val: Vec<QuoteField>) -> &mut Self {
+    #[must_use]
+    pub fn fields(mut self, val: Vec<QuoteField>) -> Self {
         self.fields = Some(val);
         self
     }
@@ -76,11 +99,28 @@ impl GetQuotesRequest {
     /// Include indicative symbol quotes for all ETF symbols in request.
     ///
     /// If ETF symbol ABC is in request and indicative=true API will return quotes for ABC and its corresponding indicative quote for $ABC.IV
-    pub fn indicative(&mut self, val: bool) -> &mut Self {
+    #[must_use]
+    pub fn indicative(mut self, val: bool) -> Self {
         self.indicative = Some(val);
         self
     }
 
+    /// The max number of symbols per call, larger lists are split into concurrent calls.
+    ///
+    /// Default value : `500`
+    #[must_use]
+    pub fn chunk_size(mut self, val: usize) -> Self {
+        self.chunk_size = val.max(1);
+        self
+    }
+
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req.query(&[("symbols", self.symbols.join(","))]);
         if let Some(x) = self.fields {
@@ -98,9 +138,92 @@ impl GetQuotesRequest {
         req
     }
 
+    /// # Panics
+    ///
+    /// Will panic if the request can not be cloned for chunking
     pub async fn send(self) -> Result<HashMap<String, model::QuoteResponse>, Error> {
+        let quotes = self.send_in_order().await?;
+        Ok(quotes
+            .into_iter()
+            .map(|(symbol, quote)| (symbol.into(), quote))
+            .collect())
+    }
+
+    /// Send with `chunk_size` symbols per call, see [`GetQuotesRequest::chunk_size`].
+    ///
+    /// The quotes keep the order of the requested symbols, duplicates and symbols missing from
+    /// the response left out. Symbols Schwab adds, e.g. the `$ABC.IV` of [`GetQuotesRequest::indicative`],
+    /// follow the requested ones of their call.
+    /// If only some of the calls fail, the quotes of the others are kept in [`Error::PartialQuotes`].
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the request can not be cloned for chunking
+    pub async fn send_chunked(
+        self,
+        chunk_size: usize,
+    ) -> Result<Vec<(model::Symbol, model::QuoteResponse)>, Error> {
+        self.chunk_size(chunk_size).send_in_order().await
+    }
+
+    async fn send_in_order(mut self) -> Result<Vec<(model::Symbol, model::QuoteResponse)>, Error> {
+        let mut seen = std::collections::HashSet::new();
+        self.symbols.retain(|x| seen.insert(x.clone()));
+
+        if self.symbols.len() <= self.chunk_size {
+            return self.send_chunk().await;
+        }
+
+        let reqs = self.symbols.chunks(self.chunk_size).map(|symbols| Self {
+            req: self.req.try_clone().expect("GET request without body"),
+            send_options: self.send_options.clone(),
+            symbols: symbols.to_vec(),
+            fields: self.fields.clone(),
+            indicative: self.indicative,
+            chunk_size: self.chunk_size,
+        });
+        let mut quotes = vec![];
+        let mut error = None;
+        for result in futures::future::join_all(reqs.map(Self::send_chunk)).await {
+            match result {
+                Ok(chunk) => quotes.extend(chunk),
+                Err(e) => {
+                    error.get_or_insert(e);
+                }
+            }
+        }
+
+        match error {
+            None => Ok(quotes),
+            Some(e) if quotes.is_empty() => Err(e),
+            Some(e) => Err(Error::PartialQuotes(quotes, Box::new(e))),
+        }
+    }
+
+    /// Like [`GetQuotesRequest::send`], but listing every requested symbol in order, with `None` when it is missing from the response.
+    /// A repeated symbol is requested once and quoted at each of its positions.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the request can not be cloned for chunking
+    pub async fn send_ordered(self) -> Result<Vec<(String, Option<model::QuoteResponse>)>, Error> {
+        let symbols = self.symbols.clone();
+        let quotes = self.send().await?;
+
+        Ok(symbols
+            .into_iter()
+            .map(|symbol| {
+                let quote = quotes.get(&symbol).cloned();
+                (symbol, quote)
+            })
+            .collect())
+    }
+
+    async fn send_chunk(self) -> Result<Vec<(model::Symbol, model::QuoteResponse)>, Error> {
+        let send_options = self.send_options.clone();
+        let symbols = self.symbols.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -111,17 +234,33 @@ impl GetQuotesRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ErrorResponse>().await?;
+            let error_response = response::json::<model::ErrorResponse>(rsp).await?;
             return Err(Error::Response(error_response));
         }
 
-        let map = rsp.json::<model::QuoteResponseMap>().await?;
+        let map = response::json::<model::QuoteResponseMap>(rsp).await?;
 
         if let Some(e) = map.errors {
             return Err(Error::Quote(e));
         }
 
-        Ok(map.responses)
+        let mut responses = map.responses;
+        let mut quotes: Vec<_> = symbols
+            .into_iter()
+            .filter_map(|symbol| {
+                let quote = responses.remove(&symbol)?;
+                Some((symbol.into(), quote))
+            })
+            .collect();
+        let mut added: Vec<_> = responses.into_iter().collect();
+        added.sort_by(|(a, _), (b, _)| a.cmp(b));
+        quotes.extend(
+            added
+                .into_iter()
+                .map(|(symbol, quote)| (symbol.into(), quote)),
+        );
+
+        Ok(quotes)
     }
 }
 
@@ -129,6 +268,7 @@ impl GetQuotesRequest {
 #[derive(Debug)]
 pub struct GetQuoteRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 
     symbol: String,
 
@@ -147,16 +287,25 @@ impl GetQuoteRequest {
         endpoints::EndpointQuote::Quote { symbol_id }
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, symbol: String) -> Self {
+    pub(crate) fn new(
+        client: &Client,
+        config: &ApiConfig,
+        access_token: String,
+        symbol: String,
+    ) -> Self {
         let req = client
-            .get(Self::endpoint(symbol.clone()).url())
+            .get(Self::endpoint(symbol.clone()).url(&config.market_data_url))
             .bearer_auth(access_token);
-        Self::new_with(req, symbol)
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req, symbol)
+        }
     }
 
     fn new_with(req: RequestBuilder, symbol: String) -> Self {
         Self {
             req,
+            send_options: SendOptions::default(),
             symbol,
             fields: None,
         }
@@ -169,11 +318,19 @@ impl GetQuoteRequest {
     /// Dont send this attribute for full response.
     ///
     /// Default value : `all`
-    pub fn fields(&mut self, val: Vec<QuoteField>) -> &mut Self {
+    #[must_use]
+    pub fn fields(mut self, val: Vec<QuoteField>) -> Self {
         self.fields = Some(val);
         self
     }
 
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req;
         if let Some(x) = self.fields {
@@ -188,13 +345,12 @@ impl GetQuoteRequest {
         req
     }
 
-    /// # Panics
-    ///
-    /// Will panic if no symbol found
+    /// Returns [`Error::SymbolNotFound`] if the symbol is missing in the response
     pub async fn send(self) -> Result<model::QuoteResponse, Error> {
         let symbol = self.symbol.clone();
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         //let json = rsp.text().await.unwrap();
         //dbg!(&json);
@@ -204,18 +360,19 @@ impl GetQuoteRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ErrorResponse>().await?;
+            let error_response = response::json::<model::ErrorResponse>(rsp).await?;
             return Err(Error::Response(error_response));
         }
 
-        let mut map = rsp.json::<model::QuoteResponseMap>().await?;
+        let mut map = response::json::<model::QuoteResponseMap>(rsp).await?;
 
         if let Some(e) = map.errors {
             return Err(Error::Quote(e));
         }
 
-        let val = map.responses.remove(&symbol).expect("must exist");
-        Ok(val)
+        map.responses
+            .remove(&symbol)
+            .ok_or(Error::SymbolNotFound(symbol))
     }
 }
 
@@ -223,6 +380,7 @@ impl GetQuoteRequest {
 #[derive(Debug)]
 pub struct GetOptionChainsRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 
     symbol: String,
 
@@ -253,7 +411,7 @@ pub struct GetOptionChainsRequest {
     strike: Option<f64>,
 
     /// Range(ITM/NTM/OTM etc.)
-    range: Option<String>,
+    range: Option<Range>,
 
     /// From date
     // pattern: yyyy-MM-dd
@@ -302,14 +460,25 @@ impl GetOptionChainsRequest {
         endpoints::EndpointOptionChain::Chains
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, symbol: String) -> Self {
-        let req = client.get(Self::endpoint().url()).bearer_auth(access_token);
-        Self::new_with(req, symbol)
+    pub(crate) fn new(
+        client: &Client,
+        config: &ApiConfig,
+        access_token: String,
+        symbol: String,
+    ) -> Self {
+        let req = client
+            .get(Self::endpoint().url(&config.market_data_url))
+            .bearer_auth(access_token);
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req, symbol)
+        }
     }
 
     fn new_with(req: RequestBuilder, symbol: String) -> Self {
         Self {
             req,
+            send_options: SendOptions::default(),
             symbol,
             contract_type: None,
             strike_count: None,
@@ -332,19 +501,22 @@ impl GetOptionChainsRequest {
 
     /// Contract Type
     /// Available values : CALL, PUT, ALL
-    pub fn contract_type(&mut self, val: ContractType) -> &mut Self {
+    #[must_use]
+    pub fn contract_type(mut self, val: ContractType) -> Self {
         self.contract_type = Some(val);
         self
     }
 
     /// The Number of strikes to return above or below the at-the-money price
-    pub fn strike_count(&mut self, val: i64) -> &mut Self {
+    #[must_use]
+    pub fn strike_count(mut self, val: i64) -> Self {
         self.strike_count = Some(val);
         self
     }
 
     /// Underlying quotes to be included
-    pub fn include_underlying_quote(&mut self, val: bool) -> &mut Self {
+    #[must_use]
+    pub fn include_underlying_quote(mut self, val: bool) -> Self {
         self.include_underlying_quote = Some(val);
         self
     }
@@ -356,39 +528,47 @@ impl GetOptionChainsRequest {
     /// `ANALYTICAL` allows the use of [`Self::volatility`], [`Self::underlying_price`], [`Self::interest_rate`], and [`Self::days_to_expiration`] params to calculate theoretical values.
     ///
     /// Available values : `SINGLE`, `ANALYTICAL`, `COVERED`, `VERTICAL`, `CALENDAR`, `STRANGLE`, `STRADDLE`, `BUTTERFLY`, `CONDOR`, `DIAGONAL`, `COLLAR`, `ROLL`
-    pub fn strategy(&mut self, val: OptionChainStrategy) -> &mut Self {
+    #[must_use]
+    pub fn strategy(mut self, val: OptionChainStrategy) -> Self {
         self.strategy = Some(val);
         self
     }
 
     /// Strike interval for spread strategy chains (see [`Self::strategy`] param)
-    pub fn interval(&mut self, val: f64) -> &mut Self {
+    #[must_use]
+    pub fn interval(mut self, val: f64) -> Self {
         self.interval = Some(val);
         self
     }
 
     /// Strike Price
-    pub fn strike(&mut self, val: f64) -> &mut Self {
+    #[must_use]
+    pub fn strike(mut self, val: f64) -> Self {
         self.strike = Some(val);
         self
     }
 
     /// Range(ITM/NTM/OTM etc.)
-    pub fn range(&mut self, val: String) -> &mut Self {
+    ///
+    /// Available values : `ITM`, `NTM`, `OTM`, `SAK`, `SBK`, `SNK`, `ALL`
+    #[must_use]
+    pub fn range(mut self, val: Range) -> Self {
         self.range = Some(val);
         self
     }
 
     #[allow(clippy::wrong_self_convention)]
     /// From date
-    pub fn from_date(&mut self, val: chrono::NaiveDate) -> &mut Self {
+    #[must_use]
+    pub fn from_date(mut self, val: chrono::NaiveDate) -> Self {
         self.from_date = Some(val);
         self
     }
 
     #[allow(clippy::wrong_self_convention)]
     /// To date
-    pub fn to_date(&mut self, val: chrono::NaiveDate) -> &mut Self {
+    #[must_use]
+    pub fn to_date(mut self, val: chrono::NaiveDate) -> Self {
         self.to_date = Some(val);
         self
     }
@@ -396,7 +576,8 @@ impl GetOptionChainsRequest {
     /// Volatility to use in calculations.
     ///
     /// Applies only to `ANALYTICAL` strategy chains (see [`Self::strategy`] param)
-    pub fn volatility(&mut self, val: f64) -> &mut Self {
+    #[must_use]
+    pub fn volatility(mut self, val: f64) -> Self {
         self.volatility = Some(val);
         self
     }
@@ -404,7 +585,8 @@ impl GetOptionChainsRequest {
     /// Underlying price to use in calculations.
     ///
     /// Applies only to `ANALYTICAL` strategy chains (see [`Self::strategy`] param)
-    pub fn underlying_price(&mut self, val: f64) -> &mut Self {
+    #[must_use]
+    pub fn underlying_price(mut self, val: f64) -> Self {
         self.underlying_price = Some(val);
         self
     }
@@ -412,7 +594,8 @@ impl GetOptionChainsRequest {
     /// Interest rate to use in calculations.
     ///
     /// Applies only to `ANALYTICAL` strategy chains (see [`Self::strategy`] param)
-    pub fn interest_rate(&mut self, val: f64) -> &mut Self {
+    #[must_use]
+    pub fn interest_rate(mut self, val: f64) -> Self {
         self.interest_rate = Some(val);
         self
     }
@@ -420,7 +603,8 @@ impl GetOptionChainsRequest {
     /// Days to expiration to use in calculations.
     ///
     /// Applies only to `ANALYTICAL` strategy chains (see [`Self::strategy`] param)
-    pub fn days_to_expiration(&mut self, val: i64) -> &mut Self {
+    #[must_use]
+    pub fn days_to_expiration(mut self, val: i64) -> Self {
         self.days_to_expiration = Some(val);
         self
     }
@@ -428,13 +612,15 @@ impl GetOptionChainsRequest {
     /// Expiration month
     ///
     /// Available values : `JAN`, `FEB`, `MAR`, `APR`, `MAY`, `JUN`, `JUL`, `AUG`, `SEP`, `OCT`, `NOV`, `DEC`, `ALL`
-    pub fn exp_month(&mut self, val: Month) -> &mut Self {
+    #[must_use]
+    pub fn exp_month(mut self, val: Month) -> Self {
         self.exp_month = Some(val);
         self
     }
 
     /// Option Type
-    pub fn option_type(&mut self, val: String) -> &mut Self {
+    #[must_use]
+    pub fn option_type(mut self, val: String) -> Self {
         self.option_type = Some(val);
         self
     }
@@ -442,11 +628,19 @@ impl GetOptionChainsRequest {
     /// Applicable only if its retail token, entitlement of client PP-PayingPro, NP-NonPro and PN-NonPayingPro
     ///
     /// Available values : `PN`, `NP`, `PP`
-    pub fn entitlement(&mut self, val: Entitlement) -> &mut Self {
+    #[must_use]
+    pub fn entitlement(mut self, val: Entitlement) -> Self {
         self.entitlement = Some(val);
         self
     }
 
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req.query(&[("symbol", self.symbol)]);
         if let Some(x) = self.contract_type {
@@ -468,7 +662,7 @@ impl GetOptionChainsRequest {
             req = req.query(&[("strike", x)]);
         }
         if let Some(x) = self.range {
-            req = req.query(&[("range", x)]);
+            req = req.query(&[("range", String::from(x))]);
         }
         if let Some(x) = self.from_date {
             req = req.query(&[("fromDate", x)]);
@@ -501,9 +695,27 @@ impl GetOptionChainsRequest {
         req
     }
 
+    /// With the `ANALYTICAL` strategy, returns [`Error::AnalyticalParamMismatch`] if the chain doesn't echo
+    /// the requested [`Self::volatility`], [`Self::underlying_price`], [`Self::interest_rate`] or
+    /// [`Self::days_to_expiration`], i.e. Schwab ignored them.
+    #[allow(clippy::cast_precision_loss)]
     pub async fn send(self) -> Result<model::OptionChain, Error> {
+        let analytical: Option<[AnalyticalParam; 4]> =
+            (self.strategy == Some(OptionChainStrategy::Analytical)).then_some([
+                ("volatility", self.volatility, |x| x.volatility),
+                ("underlyingPrice", self.underlying_price, |x| {
+                    x.underlying_price
+                }),
+                ("interestRate", self.interest_rate, |x| x.interest_rate),
+                (
+                    "daysToExpiration",
+                    self.days_to_expiration.map(|x| x as f64),
+                    |x| x.days_to_expiration,
+                ),
+            ]);
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -517,16 +729,44 @@ impl GetOptionChainsRequest {
             return Err(process_error(rsp).await?);
         }
 
-        rsp.json::<model::OptionChain>()
-            .await
-            .map_err(std::convert::Into::into)
+        let chain = response::json::<model::OptionChain>(rsp).await?;
+        if let Some(requested) = analytical {
+            Self::check_analytical_echo(&requested, &chain)?;
+        }
+
+        Ok(chain)
+    }
+
+    fn check_analytical_echo(
+        requested: &[AnalyticalParam],
+        chain: &model::OptionChain,
+    ) -> Result<(), Error> {
+        let mismatches: Vec<String> = requested
+            .iter()
+            .filter_map(|&(name, requested, returned)| {
+                let returned = returned(chain);
+                requested
+                    .filter(|x| (x - returned).abs() > 1e-6)
+                    .map(|x| format!("{name}: requested {x}, returned {returned}"))
+            })
+            .collect();
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::AnalyticalParamMismatch(mismatches.join(", ")))
+        }
     }
 }
 
+/// The name, the requested value and the echoed value of an `ANALYTICAL` parameter.
+type AnalyticalParam = (&'static str, Option<f64>, fn(&model::OptionChain) -> f64);
+
 /// Get option expiration chain for an optionable symbol
 #[derive(Debug)]
 pub struct GetOptionExpirationChainRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 
     symbol: String,
 }
@@ -536,13 +776,34 @@ impl GetOptionExpirationChainRequest {
         endpoints::EndpointOptionExpirationChain::ExpirationChain
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, symbol: String) -> Self {
-        let req: RequestBuilder = client.get(Self::endpoint().url()).bearer_auth(access_token);
-        Self::new_with(req, symbol)
+    pub(crate) fn new(
+        client: &Client,
+        config: &ApiConfig,
+        access_token: String,
+        symbol: String,
+    ) -> Self {
+        let req: RequestBuilder = client
+            .get(Self::endpoint().url(&config.market_data_url))
+            .bearer_auth(access_token);
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req, symbol)
+        }
     }
 
     fn new_with(req: RequestBuilder, symbol: String) -> Self {
-        Self { req, symbol }
+        Self {
+            req,
+            send_options: SendOptions::default(),
+            symbol,
+        }
+    }
+
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
     }
 
     fn build(self) -> RequestBuilder {
@@ -550,8 +811,9 @@ impl GetOptionExpirationChainRequest {
     }
 
     pub async fn send(self) -> Result<model::ExpirationChain, Error> {
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -562,13 +824,20 @@ impl GetOptionExpirationChainRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ErrorResponse>().await?;
+            let error_response = response::json::<model::ErrorResponse>(rsp).await?;
             return Err(Error::Response(error_response));
         }
 
-        rsp.json::<model::ExpirationChain>()
-            .await
-            .map_err(std::convert::Into::into)
+        response::json::<model::ExpirationChain>(rsp).await
+    }
+
+    /// Schwab does not return the underlying quote with the expiration chain,
+    /// so fetch it concurrently with `quote` and bundle both.
+    pub async fn send_with_quote(
+        self,
+        quote: GetQuoteRequest,
+    ) -> Result<(model::ExpirationChain, model::QuoteResponse), Error> {
+        futures::try_join!(self.send(), quote.send())
     }
 }
 
@@ -576,6 +845,7 @@ impl GetOptionExpirationChainRequest {
 #[derive(Debug)]
 pub struct GetPriceHistoryRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 
     symbol: String,
 
@@ -647,14 +917,50 @@ impl GetPriceHistoryRequest {
         endpoints::EndpointPriceHistory::PriceHistory
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, symbol: String) -> Self {
-        let req = client.get(Self::endpoint().url()).bearer_auth(access_token);
-        Self::new_with(req, symbol)
+    pub(crate) fn new(
+        client: &Client,
+        config: &ApiConfig,
+        access_token: String,
+        symbol: String,
+    ) -> Self {
+        let req = client
+            .get(Self::endpoint().url(&config.market_data_url))
+            .bearer_auth(access_token);
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req, symbol)
+        }
+    }
+
+    /// Daily candles over the last `years`, one of `1`, `2`, `3`, `5`, `10`, `15`, `20`.
+    pub(crate) fn daily(
+        client: &Client,
+        config: &ApiConfig,
+        access_token: String,
+        symbol: String,
+        years: u32,
+    ) -> Result<Self, Error> {
+        Self::new(client, config, access_token, symbol).with_daily(years)
+    }
+
+    /// Candles of `interval_minutes` over the last `days`.
+    ///
+    /// `days` is one of `1`, `2`, `3`, `4`, `5`, `10` and `interval_minutes` one of `1`, `5`, `10`, `15`, `30`.
+    pub(crate) fn minute(
+        client: &Client,
+        config: &ApiConfig,
+        access_token: String,
+        symbol: String,
+        days: u32,
+        interval_minutes: u32,
+    ) -> Result<Self, Error> {
+        Self::new(client, config, access_token, symbol).with_minute(days, interval_minutes)
     }
 
     fn new_with(req: RequestBuilder, symbol: String) -> Self {
         Self {
             req,
+            send_options: SendOptions::default(),
             symbol,
             period_type: None,
             period: None,
@@ -667,10 +973,47 @@ impl GetPriceHistoryRequest {
         }
     }
 
+    fn with_daily(self, years: u32) -> Result<Self, Error> {
+        let periods = Self::periods(PeriodType::Year);
+        if !periods.contains(&years.into()) {
+            return Err(Error::InvalidParameter(format!(
+                "years must be one of {periods:?}, got {years}"
+            )));
+        }
+
+        Ok(self
+            .period_type(PeriodType::Year)
+            .period(years.into())
+            .frequency_type(FrequencyType::Daily)
+            .frequency(1))
+    }
+
+    fn with_minute(self, days: u32, interval_minutes: u32) -> Result<Self, Error> {
+        let periods = Self::periods(PeriodType::Day);
+        if !periods.contains(&days.into()) {
+            return Err(Error::InvalidParameter(format!(
+                "days must be one of {periods:?}, got {days}"
+            )));
+        }
+        let frequencies = Self::frequencies(FrequencyType::Minute);
+        if !frequencies.contains(&interval_minutes.into()) {
+            return Err(Error::InvalidParameter(format!(
+                "interval_minutes must be one of {frequencies:?}, got {interval_minutes}"
+            )));
+        }
+
+        Ok(self
+            .period_type(PeriodType::Day)
+            .period(days.into())
+            .frequency_type(FrequencyType::Minute)
+            .frequency(interval_minutes.into()))
+    }
+
     /// The chart period being requested.
     ///
     /// Available values : `day`, `month`, `year`, `ytd`
-    pub fn period_type(&mut self, val: PeriodType) -> &mut Self {
+    #[must_use]
+    pub fn period_type(mut self, val: PeriodType) -> Self {
         self.period_type = Some(val);
         self
     }
@@ -688,7 +1031,8 @@ impl GetPriceHistoryRequest {
     /// * month - default period is 1.
     /// * year - default period is 1.
     /// * ytd - default period is 1.
-    pub fn period(&mut self, val: i64) -> &mut Self {
+    #[must_use]
+    pub fn period(mut self, val: i64) -> Self {
         self.period = Some(val);
         self
     }
@@ -708,7 +1052,8 @@ impl GetPriceHistoryRequest {
     /// * `ytd` - defaulted to `weekly`.
     ///
     /// Available values : `minute`, `daily`, `weekly`, `monthly`
-    pub fn frequency_type(&mut self, val: FrequencyType) -> &mut Self {
+    #[must_use]
+    pub fn frequency_type(mut self, val: FrequencyType) -> Self {
         self.frequency_type = Some(val);
         self
     }
@@ -722,35 +1067,124 @@ impl GetPriceHistoryRequest {
     /// * `monthly` - valid value is `1`
     ///
     /// If [`Self::frequency`] is not specified, default value is `1`
-    pub fn frequency(&mut self, val: i64) -> &mut Self {
+    #[must_use]
+    pub fn frequency(mut self, val: i64) -> Self {
         self.frequency = Some(val);
         self
     }
 
     /// If not specified [`Self::start_date`] will be ([`Self::end_date`] - [`Self::period`]) excluding weekends and holidays.
-    pub fn start_date(&mut self, val: chrono::DateTime<chrono::Utc>) -> &mut Self {
+    #[must_use]
+    pub fn start_date(mut self, val: chrono::DateTime<chrono::Utc>) -> Self {
         self.start_date = Some(val.timestamp_millis());
         self
     }
 
     /// If not specified, the [`Self::end_date`] will default to the market close of previous business day.
-    pub fn end_date(&mut self, val: chrono::DateTime<chrono::Utc>) -> &mut Self {
+    #[must_use]
+    pub fn end_date(mut self, val: chrono::DateTime<chrono::Utc>) -> Self {
         self.end_date = Some(val.timestamp_millis());
         self
     }
 
     /// Need extended hours data
-    pub fn need_extended_hours_data(&mut self, val: bool) -> &mut Self {
+    #[must_use]
+    pub fn need_extended_hours_data(mut self, val: bool) -> Self {
         self.need_extended_hours_data = Some(val);
         self
     }
 
     /// Need previous close price/date
-    pub fn need_previous_close(&mut self, val: bool) -> &mut Self {
+    #[must_use]
+    pub fn need_previous_close(mut self, val: bool) -> Self {
         self.need_previous_close = Some(val);
         self
     }
 
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
+    /// Check `period`, `frequency_type` and `frequency` against the `period_type`, as documented on the setters,
+    /// and that `start_date` is before `end_date`.
+    ///
+    /// Unset values are left to the defaults of Schwab. Also called by [`Self::send`].
+    pub fn validate(&self) -> Result<(), Error> {
+        if let (Some(start), Some(end)) = (self.start_date, self.end_date) {
+            if start >= end {
+                return Err(Error::InvalidParameter(format!(
+                    "start date {start} must be before end date {end}"
+                )));
+            }
+        }
+
+        if let Some(period_type) = self.period_type {
+            let periods = Self::periods(period_type);
+            let frequency_types: &[FrequencyType] = match period_type {
+                PeriodType::Day => &[FrequencyType::Minute],
+                PeriodType::Month | PeriodType::Ytd => {
+                    &[FrequencyType::Daily, FrequencyType::Weekly]
+                }
+                PeriodType::Year => &[
+                    FrequencyType::Daily,
+                    FrequencyType::Weekly,
+                    FrequencyType::Monthly,
+                ],
+            };
+
+            if let Some(period) = self.period.filter(|x| !periods.contains(x)) {
+                return Err(Error::InvalidParameter(format!(
+                    "period {period} is not valid for the period type {period_type:?}, expected one of {periods:?}"
+                )));
+            }
+            if let Some(frequency_type) =
+                self.frequency_type.filter(|x| !frequency_types.contains(x))
+            {
+                return Err(Error::InvalidParameter(format!(
+                    "frequency type {frequency_type:?} is not valid for the period type {period_type:?}, expected one of {frequency_types:?}"
+                )));
+            }
+        }
+
+        if let (Some(frequency_type), Some(frequency)) = (self.frequency_type, self.frequency) {
+            let frequencies = Self::frequencies(frequency_type);
+            if !frequencies.contains(&frequency) {
+                return Err(Error::InvalidParameter(format!(
+                    "frequency {frequency} is not valid for the frequency type {frequency_type:?}, expected one of {frequencies:?}"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The periods Schwab accepts for `period_type`.
+    fn periods(period_type: PeriodType) -> &'static [i64] {
+        match period_type {
+            PeriodType::Day => &[1, 2, 3, 4, 5, 10],
+            PeriodType::Month => &[1, 2, 3, 6],
+            PeriodType::Year => &[1, 2, 3, 5, 10, 15, 20],
+            PeriodType::Ytd => &[1],
+        }
+    }
+
+    /// The frequencies Schwab accepts for `frequency_type`.
+    fn frequencies(frequency_type: FrequencyType) -> &'static [i64] {
+        match frequency_type {
+            FrequencyType::Minute => &[1, 5, 10, 15, 30],
+            FrequencyType::Daily | FrequencyType::Weekly | FrequencyType::Monthly => &[1],
+        }
+    }
+
+    /// The request once every setter is applied, or [`Error::InvalidParameter`], see [`Self::validate`].
+    pub fn build_validated(self) -> Result<Self, Error> {
+        self.validate()?;
+        Ok(self)
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req.query(&[("symbol", self.symbol)]);
         if let Some(x) = self.period_type {
@@ -781,9 +1215,12 @@ impl GetPriceHistoryRequest {
         req
     }
 
+    /// Fails with [`Error::InvalidParameter`] without sending if the parameters do not match, see [`Self::period_type`].
     pub async fn send(self) -> Result<model::CandleList, Error> {
+        self.validate()?;
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -794,13 +1231,11 @@ impl GetPriceHistoryRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ErrorResponse>().await?;
+            let error_response = response::json::<model::ErrorResponse>(rsp).await?;
             return Err(Error::Response(error_response));
         }
 
-        rsp.json::<model::CandleList>()
-            .await
-            .map_err(std::convert::Into::into)
+        response::json::<model::CandleList>(rsp).await
     }
 }
 
@@ -808,6 +1243,7 @@ impl GetPriceHistoryRequest {
 #[derive(Debug)]
 pub struct GetMoversRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 
     /// Index Symbol
     ///
@@ -836,17 +1272,26 @@ impl GetMoversRequest {
         endpoints::EndpointMover::Mover { symbol_id }
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, symbol: String) -> Self {
+    pub(crate) fn new(
+        client: &Client,
+        config: &ApiConfig,
+        access_token: String,
+        symbol: String,
+    ) -> Self {
         let req = client
-            .get(Self::endpoint(symbol.clone()).url())
+            .get(Self::endpoint(symbol.clone()).url(&config.market_data_url))
             .bearer_auth(access_token);
 
-        Self::new_with(req, symbol)
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req, symbol)
+        }
     }
 
     fn new_with(req: RequestBuilder, symbol: String) -> Self {
         Self {
             req,
+            send_options: SendOptions::default(),
             symbol,
             sort: None,
             frequency: None,
@@ -858,7 +1303,8 @@ impl GetMoversRequest {
     /// Available values : `VOLUME`, `TRADES`, `PERCENT_CHANGE_UP`, `PERCENT_CHANGE_DOWN`
     ///
     /// Example : `VOLUME`
-    pub fn sort(&mut self, val: SortAttribute) -> &mut Self {
+    #[must_use]
+    pub fn sort(mut self, val: SortAttribute) -> Self {
         self.sort = Some(val);
         self
     }
@@ -868,11 +1314,19 @@ impl GetMoversRequest {
     /// Available values : `0`, `1`, `5`, `10`, `30`, `60`
     ///
     /// Default value : `0`
-    pub fn frequency(&mut self, val: i64) -> &mut Self {
+    #[must_use]
+    pub fn frequency(mut self, val: i64) -> Self {
         self.frequency = Some(val);
         self
     }
 
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req.query(&[("symbol", self.symbol)]);
         if let Some(x) = self.sort {
@@ -886,8 +1340,9 @@ impl GetMoversRequest {
     }
 
     pub async fn send(self) -> Result<model::Mover, Error> {
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -898,13 +1353,11 @@ impl GetMoversRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ErrorResponse>().await?;
+            let error_response = response::json::<model::ErrorResponse>(rsp).await?;
             return Err(Error::Response(error_response));
         }
 
-        rsp.json::<model::Mover>()
-            .await
-            .map_err(std::convert::Into::into)
+        response::json::<model::Mover>(rsp).await
     }
 }
 
@@ -912,6 +1365,7 @@ impl GetMoversRequest {
 #[derive(Debug)]
 pub struct GetMarketsRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 
     /// List of markets
     ///
@@ -930,15 +1384,26 @@ impl GetMarketsRequest {
         endpoints::EndpointMarketHour::Markets
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, markets: Vec<Market>) -> Self {
-        let req = client.get(Self::endpoint().url()).bearer_auth(access_token);
+    pub(crate) fn new(
+        client: &Client,
+        config: &ApiConfig,
+        access_token: String,
+        markets: Vec<Market>,
+    ) -> Self {
+        let req = client
+            .get(Self::endpoint().url(&config.market_data_url))
+            .bearer_auth(access_token);
 
-        Self::new_with(req, markets)
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req, markets)
+        }
     }
 
     fn new_with(req: RequestBuilder, markets: Vec<Market>) -> Self {
         Self {
             req,
+            send_options: SendOptions::default(),
             markets,
             date: None,
         }
@@ -947,11 +1412,19 @@ impl GetMarketsRequest {
     /// Valid date range is from currentdate to 1 year from today.
     ///
     /// It will default to current day if not entered.
-    pub fn date(&mut self, val: chrono::NaiveDate) -> &mut Self {
+    #[must_use]
+    pub fn date(mut self, val: chrono::NaiveDate) -> Self {
         self.date = Some(val);
         self
     }
 
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let markets: Vec<String> = self
             .markets
@@ -968,8 +1441,9 @@ impl GetMarketsRequest {
     }
 
     pub async fn send(self) -> Result<model::Markets, Error> {
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -980,13 +1454,11 @@ impl GetMarketsRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ErrorResponse>().await?;
+            let error_response = response::json::<model::ErrorResponse>(rsp).await?;
             return Err(Error::Response(error_response));
         }
 
-        rsp.json::<model::Markets>()
-            .await
-            .map_err(std::convert::Into::into)
+        response::json::<model::Markets>(rsp).await
     }
 }
 
@@ -994,9 +1466,7 @@ impl GetMarketsRequest {
 #[derive(Debug)]
 pub struct GetMarketRequest {
     req: RequestBuilder,
-
-    /// Available values : `equity`, `option`, `bond`, `future`, `forex`
-    market_id: Market,
+    send_options: SendOptions,
 
     /// Valid date range is from currentdate to 1 year from today.
     ///
@@ -1010,18 +1480,26 @@ impl GetMarketRequest {
         endpoints::EndpointMarketHour::Market { market_id }
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, market_id: Market) -> Self {
+    pub(crate) fn new(
+        client: &Client,
+        config: &ApiConfig,
+        access_token: String,
+        market_id: Market,
+    ) -> Self {
         let req = client
-            .get(Self::endpoint(market_id).url())
+            .get(Self::endpoint(market_id).url(&config.market_data_url))
             .bearer_auth(access_token);
 
-        Self::new_with(req, market_id)
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req)
+        }
     }
 
-    fn new_with(req: RequestBuilder, market_id: Market) -> Self {
+    fn new_with(req: RequestBuilder) -> Self {
         Self {
             req,
-            market_id,
+            send_options: SendOptions::default(),
             date: None,
         }
     }
@@ -1029,13 +1507,21 @@ impl GetMarketRequest {
     /// Valid date range is from currentdate to 1 year from today.
     ///
     /// It will default to current day if not entered.
-    pub fn date(&mut self, val: chrono::NaiveDate) -> &mut Self {
+    #[must_use]
+    pub fn date(mut self, val: chrono::NaiveDate) -> Self {
         self.date = Some(val);
         self
     }
 
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
     fn build(self) -> RequestBuilder {
-        let mut req = self.req.query(&[("market_id", self.market_id)]);
+        let mut req = self.req;
         if let Some(x) = self.date {
             req = req.query(&[("date", x)]);
         }
@@ -1044,8 +1530,9 @@ impl GetMarketRequest {
     }
 
     pub async fn send(self) -> Result<model::Markets, Error> {
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -1056,13 +1543,11 @@ impl GetMarketRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ErrorResponse>().await?;
+            let error_response = response::json::<model::ErrorResponse>(rsp).await?;
             return Err(Error::Response(error_response));
         }
 
-        rsp.json::<model::Markets>()
-            .await
-            .map_err(std::convert::Into::into)
+        response::json::<model::Markets>(rsp).await
     }
 }
 
@@ -1070,6 +1555,7 @@ impl GetMarketRequest {
 #[derive(Debug)]
 pub struct GetInstrumentsRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 
     symbol: String,
 
@@ -1077,6 +1563,18 @@ pub struct GetInstrumentsRequest {
     ///
     /// Available values : `symbol-search`, `symbol-regex`, `desc-search`, `desc-regex`, `search`, `fundamental`
     projection: Projection,
+
+    /// The max number of instruments to return.
+    ///
+    /// Not supported by Schwab: every matching instrument is still downloaded on each
+    /// request, and only then truncated. It saves no bandwidth or latency.
+    limit: Option<usize>,
+
+    /// The number of instruments to skip, for paging with `limit`.
+    ///
+    /// Not supported by Schwab: every matching instrument is still downloaded on each
+    /// request, and only then skipped, so paging refetches the full result every time.
+    offset: Option<usize>,
 }
 
 impl GetInstrumentsRequest {
@@ -1086,22 +1584,58 @@ impl GetInstrumentsRequest {
 
     pub(crate) fn new(
         client: &Client,
+        config: &ApiConfig,
         access_token: String,
         symbol: String,
         projection: Projection,
     ) -> Self {
-        let req = client.get(Self::endpoint().url()).bearer_auth(access_token);
-        Self::new_with(req, symbol, projection)
+        let req = client
+            .get(Self::endpoint().url(&config.market_data_url))
+            .bearer_auth(access_token);
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req, symbol, projection)
+        }
     }
 
     fn new_with(req: RequestBuilder, symbol: String, projection: Projection) -> Self {
         Self {
             req,
+            send_options: SendOptions::default(),
             symbol,
             projection,
+            limit: None,
+            offset: None,
         }
     }
 
+    /// The max number of instruments to return.
+    ///
+    /// Not supported by Schwab: every matching instrument is still downloaded on each
+    /// request, and only then truncated. It saves no bandwidth or latency.
+    #[must_use]
+    pub fn limit(mut self, val: usize) -> Self {
+        self.limit = Some(val);
+        self
+    }
+
+    /// The number of instruments to skip, for paging with `limit`.
+    ///
+    /// Not supported by Schwab: every matching instrument is still downloaded on each
+    /// request, and only then skipped, so paging refetches the full result every time.
+    #[must_use]
+    pub fn offset(mut self, val: usize) -> Self {
+        self.offset = Some(val);
+        self
+    }
+
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         self.req
             .query(&[("symbol", self.symbol)])
@@ -1109,8 +1643,11 @@ impl GetInstrumentsRequest {
     }
 
     pub async fn send(self) -> Result<model::Instruments, Error> {
+        let limit = self.limit;
+        let offset = self.offset;
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -1121,13 +1658,21 @@ impl GetInstrumentsRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ErrorResponse>().await?;
+            let error_response = response::json::<model::ErrorResponse>(rsp).await?;
             return Err(Error::Response(error_response));
         }
 
-        rsp.json::<model::Instruments>()
-            .await
-            .map_err(std::convert::Into::into)
+        let mut instruments = response::json::<model::Instruments>(rsp).await?;
+        if let Some(offset) = offset {
+            instruments
+                .instruments
+                .drain(..offset.min(instruments.instruments.len()));
+        }
+        if let Some(limit) = limit {
+            instruments.instruments.truncate(limit);
+        }
+
+        Ok(instruments)
     }
 }
 
@@ -1135,6 +1680,7 @@ impl GetInstrumentsRequest {
 #[derive(Debug)]
 pub struct GetInstrumentRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 
     #[allow(dead_code)]
     /// cusip of a security
@@ -1143,18 +1689,37 @@ pub struct GetInstrumentRequest {
 
 impl GetInstrumentRequest {
     fn endpoint(cusip_id: String) -> endpoints::EndpointInstrument {
-        endpoints::EndpointInstrument::Instrutment { cusip_id }
+        endpoints::EndpointInstrument::Instrument { cusip_id }
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, cusip_id: String) -> Self {
+    pub(crate) fn new(
+        client: &Client,
+        config: &ApiConfig,
+        access_token: String,
+        cusip_id: String,
+    ) -> Self {
         let req = client
-            .get(Self::endpoint(cusip_id.clone()).url())
+            .get(Self::endpoint(cusip_id.clone()).url(&config.market_data_url))
             .bearer_auth(access_token);
-        Self::new_with(req, cusip_id)
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req, cusip_id)
+        }
     }
 
     fn new_with(req: RequestBuilder, cusip_id: String) -> Self {
-        Self { req, cusip_id }
+        Self {
+            req,
+            send_options: SendOptions::default(),
+            cusip_id,
+        }
+    }
+
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
     }
 
     fn build(self) -> RequestBuilder {
@@ -1165,8 +1730,9 @@ impl GetInstrumentRequest {
     ///
     /// Will panic if no Instrument
     pub async fn send(self) -> Result<model::InstrumentResponse, Error> {
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -1177,7 +1743,7 @@ impl GetInstrumentRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ErrorResponse>().await?;
+            let error_response = response::json::<model::ErrorResponse>(rsp).await?;
             return Err(Error::Response(error_response));
         }
 
@@ -1194,6 +1760,7 @@ impl GetInstrumentRequest {
 mod tests {
     use super::*;
 
+    use crate::api::{mock_api, ApiConfig};
     use mockito::Matcher;
     use pretty_assertions::assert_eq;
     use reqwest::Client;
@@ -1202,10 +1769,10 @@ mod tests {
     async fn test_get_quotes_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let symbols = vec!["symbol1".to_string(), "symbol2".to_string()];
@@ -1218,7 +1785,7 @@ mod tests {
 
         // Create a mock
         let mock = server
-            .mock("GET", "/quotes")
+            .mock("GET", "/marketdata/v1/quotes")
             .match_query(Matcher::AllOf(vec![
                 Matcher::UrlEncoded("symbols".into(), symbols.join(",")),
                 Matcher::UrlEncoded("fields".into(), "reference,regular,Extra".into()),
@@ -1234,13 +1801,7 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetQuotesRequest::endpoint().url_endpoint()
-        ));
-
-        let mut req = GetQuotesRequest::new_with(req, symbols.clone());
+        let mut req = api.get_quotes(symbols.clone()).await.unwrap();
 
         // check initial value
         assert_eq!(req.symbols, symbols);
@@ -1248,9 +1809,9 @@ mod tests {
         assert_eq!(req.indicative, None);
 
         // check setter
-        req.fields(fields.clone());
+        req = req.fields(fields.clone());
         assert_eq!(req.fields, Some(fields));
-        req.indicative(indicative);
+        req = req.indicative(indicative);
         assert_eq!(req.indicative, Some(indicative));
 
         dbg!(&req);
@@ -1264,10 +1825,10 @@ mod tests {
     async fn test_get_quotes_request_real() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let symbols = vec!["symbol1".to_string(), "symbol2".to_string()];
@@ -1280,7 +1841,7 @@ mod tests {
 
         // Create a mock
         let mock = server
-            .mock("GET", "/quotes")
+            .mock("GET", "/marketdata/v1/quotes")
             .match_query(Matcher::AllOf(vec![
                 Matcher::UrlEncoded("symbols".into(), symbols.join(",")),
                 Matcher::UrlEncoded("fields".into(), "reference,regular,Extra".into()),
@@ -1296,13 +1857,7 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetQuotesRequest::endpoint().url_endpoint()
-        ));
-
-        let mut req = GetQuotesRequest::new_with(req, symbols.clone());
+        let mut req = api.get_quotes(symbols.clone()).await.unwrap();
 
         // check initial value
         assert_eq!(req.symbols, symbols);
@@ -1310,9 +1865,9 @@ mod tests {
         assert_eq!(req.indicative, None);
 
         // check setter
-        req.fields(fields.clone());
+        req = req.fields(fields.clone());
         assert_eq!(req.fields, Some(fields));
-        req.indicative(indicative);
+        req = req.indicative(indicative);
         assert_eq!(req.indicative, Some(indicative));
 
         dbg!(&req);
@@ -1321,15 +1876,254 @@ mod tests {
         result.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_get_quotes_request_send_ordered() {
+        let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
+
+        let symbols = vec![
+            "$SPX".to_string(),
+            "MISSING".to_string(),
+            "AAPL".to_string(),
+        ];
+        let mock = server
+            .mock("GET", "/marketdata/v1/quotes")
+            .match_query(Matcher::UrlEncoded("symbols".into(), symbols.join(",")))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/MarketData/QuoteResponse_real.json"
+            ))
+            .create_async()
+            .await;
+
+        let result = api
+            .get_quotes(symbols.clone())
+            .await
+            .unwrap()
+            .send_ordered()
+            .await;
+        mock.assert_async().await;
+        let result = result.unwrap();
+
+        // in request order, the other quotes of the body left out
+        assert_eq!(
+            result.iter().map(|(x, _)| x.clone()).collect::<Vec<_>>(),
+            symbols
+        );
+        assert_eq!(result[0].1.as_ref().unwrap().symbol(), "$SPX");
+        assert!(result[1].1.is_none());
+        assert_eq!(result[2].1.as_ref().unwrap().symbol(), "AAPL");
+
+        // a repeated symbol is requested once, and quoted at every position
+        let mock = server
+            .mock("GET", "/marketdata/v1/quotes")
+            .match_query(Matcher::UrlEncoded("symbols".into(), "AAPL,$SPX".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/MarketData/QuoteResponse_real.json"
+            ))
+            .create_async()
+            .await;
+        let result = api
+            .get_quotes(vec![
+                "AAPL".to_string(),
+                "$SPX".to_string(),
+                "AAPL".to_string(),
+            ])
+            .await
+            .unwrap()
+            .send_ordered()
+            .await;
+        mock.assert_async().await;
+        let result = result.unwrap();
+        assert_eq!(
+            result.iter().map(|(x, _)| x.as_str()).collect::<Vec<_>>(),
+            vec!["AAPL", "$SPX", "AAPL"]
+        );
+        assert_eq!(result[0].1.as_ref().unwrap().symbol(), "AAPL");
+        assert_eq!(result[1].1.as_ref().unwrap().symbol(), "$SPX");
+        assert_eq!(result[2].1.as_ref().unwrap().symbol(), "AAPL");
+    }
+
+    #[tokio::test]
+    async fn test_get_quotes_request_chunked() {
+        // Request a new server from the pool
+        let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
+
+        // Use one of these addresses to configure your client
+        let _host = server.host_with_port();
+
+        // define parameter
+        let symbols: Vec<String> = (0..600).map(|i| format!("SYM{i}")).collect();
+        let json: serde_json::Value = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/QuoteResponse_real.json"
+        )))
+        .unwrap();
+        let body = |symbols: &[String]| {
+            let map: serde_json::Map<String, serde_json::Value> = symbols
+                .iter()
+                .map(|symbol| {
+                    let mut quote = json["$SPX"].clone();
+                    quote["symbol"] = symbol.clone().into();
+                    (symbol.clone(), quote)
+                })
+                .collect();
+            serde_json::to_string(&map).unwrap()
+        };
+
+        // Create a mock
+        let mock1 = server
+            .mock("GET", "/marketdata/v1/quotes")
+            .match_query(Matcher::UrlEncoded(
+                "symbols".into(),
+                symbols[..500].join(","),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body(&symbols[..500]))
+            .create_async()
+            .await;
+        let mock2 = server
+            .mock("GET", "/marketdata/v1/quotes")
+            .match_query(Matcher::UrlEncoded(
+                "symbols".into(),
+                symbols[500..].join(","),
+            ))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body(&symbols[500..]))
+            .create_async()
+            .await;
+
+        // duplicated symbol should not shift the chunks
+        let mut duplicated = symbols.clone();
+        duplicated.push("SYM0".to_string());
+        let mut req = api.get_quotes(duplicated).await.unwrap();
+
+        // check initial value
+        assert_eq!(req.chunk_size, 500);
+
+        // check setter
+        req = req.chunk_size(0);
+        assert_eq!(req.chunk_size, 1);
+        req = req.chunk_size(500);
+        assert_eq!(req.chunk_size, 500);
+
+        let result = req.send_chunked(500).await;
+        mock1.assert_async().await;
+        mock2.assert_async().await;
+        let result = result.unwrap();
+        // in request order across the chunks
+        assert_eq!(result.len(), 600);
+        for ((symbol, quote), expected) in result.iter().zip(&symbols) {
+            assert_eq!(symbol.as_str(), expected);
+            assert_eq!(quote.symbol(), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_quotes_request_malformed() {
+        // Request a new server from the pool
+        let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
+
+        // Use one of these addresses to configure your client
+        let _host = server.host_with_port();
+
+        // define parameter
+        let body = r#"{"AAPL": {"assetMainType": "EQUITY", "symbol": 42"#;
+
+        // Create a mock
+        let mock = server
+            .mock("GET", "/marketdata/v1/quotes")
+            .match_query(Matcher::UrlEncoded("symbols".into(), "AAPL".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let req = api.get_quotes(vec!["AAPL".to_string()]).await.unwrap();
+
+        let result = req.send().await;
+        mock.assert_async().await;
+        let Err(Error::Deserialization { body: received, .. }) = result else {
+            panic!("expected a deserialization error, got {result:?}");
+        };
+        assert_eq!(received, body);
+    }
+
+    #[tokio::test]
+    async fn test_get_quotes_request_send_chunked_partial() {
+        // Request a new server from the pool
+        let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
+
+        // Use one of these addresses to configure your client
+        let _host = server.host_with_port();
+
+        // define parameter
+        let symbols: Vec<String> = ["AAPL", "$SPX", "BAD"].map(String::from).to_vec();
+        let json: serde_json::Value = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/QuoteResponse_real.json"
+        )))
+        .unwrap();
+        let body = serde_json::json!({ "AAPL": json["AAPL"], "$SPX": json["$SPX"] });
+
+        // Create a mock
+        let mock_ok = server
+            .mock("GET", "/marketdata/v1/quotes")
+            .match_query(Matcher::UrlEncoded("symbols".into(), "AAPL,$SPX".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string())
+            .create_async()
+            .await;
+        let mock_err = server
+            .mock("GET", "/marketdata/v1/quotes")
+            .match_query(Matcher::UrlEncoded("symbols".into(), "BAD".into()))
+            .with_status(400)
+            .with_header("content-type", "application/json")
+            .with_body(include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/MarketData/ErrorResponse.json"
+            )))
+            .create_async()
+            .await;
+
+        let req = api.get_quotes(symbols).await.unwrap();
+
+        let result = req.send_chunked(2).await;
+        mock_ok.assert_async().await;
+        mock_err.assert_async().await;
+        let Err(Error::PartialQuotes(quotes, error)) = result else {
+            panic!("expected partial quotes, got {result:?}");
+        };
+        // in request order
+        assert_eq!(quotes.len(), 2);
+        assert_eq!(quotes[0].0.as_str(), "AAPL");
+        assert_eq!(quotes[0].1.symbol(), "AAPL");
+        assert_eq!(quotes[1].0.as_str(), "$SPX");
+        assert_eq!(quotes[1].1.symbol(), "$SPX");
+        assert!(matches!(*error, Error::Response(_)));
+    }
+
     #[tokio::test]
     #[allow(clippy::too_many_lines)]
     async fn test_get_quote_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let symbol = "AAPL".to_string();
@@ -1337,7 +2131,7 @@ mod tests {
 
         // Create a mock
         let mock = server
-            .mock("GET", "/AAPL/quotes")
+            .mock("GET", "/marketdata/v1/AAPL/quotes")
             .match_query(Matcher::UrlEncoded(
                 "fields".into(),
                 "reference,regular".into(),
@@ -1422,19 +2216,14 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetQuoteRequest::endpoint(symbol.clone()).url_endpoint()
-        ));
-        let mut req = GetQuoteRequest::new_with(req, symbol.clone());
+        let mut req = api.get_quote(symbol.clone()).await.unwrap();
 
         // check initial value
         assert_eq!(req.symbol, symbol);
         assert_eq!(req.fields, None);
 
         // check setter
-        req.fields(fields.clone());
+        req = req.fields(fields.clone());
         assert_eq!(req.fields, Some(fields));
 
         dbg!(&req);
@@ -1451,10 +2240,10 @@ mod tests {
     async fn test_get_quote_request_error() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let symbol = "^IRX".to_string();
@@ -1462,7 +2251,7 @@ mod tests {
 
         // Create a mock
         let mock = server
-            .mock("GET", "/%5EIRX/quotes")
+            .mock("GET", "/marketdata/v1/%5EIRX/quotes")
             .match_query(Matcher::UrlEncoded(
                 "fields".into(),
                 "reference,regular".into(),
@@ -1481,19 +2270,14 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetQuoteRequest::endpoint(symbol.clone()).url_endpoint()
-        ));
-        let mut req = GetQuoteRequest::new_with(req, symbol.clone());
+        let mut req = api.get_quote(symbol.clone()).await.unwrap();
 
         // check initial value
         assert_eq!(req.symbol, symbol);
         assert_eq!(req.fields, None);
 
         // check setter
-        req.fields(fields.clone());
+        req = req.fields(fields.clone());
         assert_eq!(req.fields, Some(fields));
 
         dbg!(&req);
@@ -1509,15 +2293,48 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_get_quote_request_symbol_not_found() {
+        // Request a new server from the pool
+        let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
+
+        // Use one of these addresses to configure your client
+        let _host = server.host_with_port();
+
+        // define parameter
+        let symbol = "AAPL".to_string();
+
+        // Create a mock
+        let mock = server
+            .mock("GET", "/marketdata/v1/AAPL/quotes")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("{}")
+            .create_async()
+            .await;
+
+        let req = api.get_quote(symbol.clone()).await.unwrap();
+
+        dbg!(&req);
+        let result = req.send().await;
+        mock.assert_async().await;
+        let result = result.unwrap_err();
+        match result {
+            Error::SymbolNotFound(s) => assert_eq!(s, symbol),
+            x => panic!("{x:?} is not SymbolNotFound"),
+        }
+    }
+
     #[allow(clippy::too_many_lines)]
     #[tokio::test]
     async fn test_get_options_chains_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let symbol = "string".to_string();
@@ -1527,7 +2344,7 @@ mod tests {
         let strategy = OptionChainStrategy::Single;
         let interval = 1.1;
         let strike = 2.2;
-        let range = "ITM".to_string();
+        let range = Range::Itm;
         let from_date = chrono::NaiveDate::from_ymd_opt(2015, 3, 14).unwrap();
         let to_date = chrono::NaiveDate::from_ymd_opt(2015, 5, 14).unwrap();
         let volatility = 3.3;
@@ -1540,7 +2357,7 @@ mod tests {
 
         // Create a mock
         let mock = server
-            .mock("GET", "/chains")
+            .mock("GET", "/marketdata/v1/chains")
             .match_query(Matcher::AllOf(vec![
                 Matcher::UrlEncoded("symbol".into(), symbol.clone()),
                 Matcher::UrlEncoded("contractType".into(), "CALL".into()),
@@ -1552,7 +2369,7 @@ mod tests {
                 Matcher::UrlEncoded("strategy".into(), "SINGLE".into()),
                 Matcher::UrlEncoded("interval".into(), interval.to_string()),
                 Matcher::UrlEncoded("strike".into(), strike.to_string()),
-                Matcher::UrlEncoded("range".into(), range.clone()),
+                Matcher::UrlEncoded("range".into(), "ITM".into()),
                 Matcher::UrlEncoded("fromDate".into(), from_date.to_string()),
                 Matcher::UrlEncoded("toDate".into(), to_date.to_string()),
                 Matcher::UrlEncoded("volatility".into(), volatility.to_string()),
@@ -1573,12 +2390,7 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetOptionChainsRequest::endpoint().url_endpoint()
-        ));
-        let mut req = GetOptionChainsRequest::new_with(req, symbol.clone());
+        let mut req = api.get_option_chains(symbol.clone()).await.unwrap();
 
         // check initial value
         assert_eq!(req.symbol, symbol);
@@ -1600,37 +2412,38 @@ mod tests {
         assert_eq!(req.entitlement, None);
 
         // check setter
-        req.contract_type(contract_type);
+        req = req.contract_type(contract_type);
         assert_eq!(req.contract_type, Some(contract_type));
-        req.strike_count(strike_count);
+        req = req.strike_count(strike_count);
         assert_eq!(req.strike_count, Some(strike_count));
-        req.include_underlying_quote(include_underlying_quote);
+        req = req.include_underlying_quote(include_underlying_quote);
         assert_eq!(req.include_underlying_quote, Some(include_underlying_quote));
-        req.strategy(strategy);
+        req = req.strategy(strategy);
         assert_eq!(req.strategy, Some(strategy));
-        req.interval(interval);
+        req = req.interval(interval);
         assert_eq!(req.interval, Some(interval));
-        req.strike(strike);
+        req = req.strike(strike);
         assert_eq!(req.strike, Some(strike));
-        req.range(range.clone());
+        req = req.range(range);
         assert_eq!(req.range, Some(range));
-        req.from_date(from_date);
+        assert_eq!(String::from(Range::Sak), "SAK");
+        req = req.from_date(from_date);
         assert_eq!(req.from_date, Some(from_date));
-        req.to_date(to_date);
+        req = req.to_date(to_date);
         assert_eq!(req.to_date, Some(to_date));
-        req.volatility(volatility);
+        req = req.volatility(volatility);
         assert_eq!(req.volatility, Some(volatility));
-        req.underlying_price(underlying_price);
+        req = req.underlying_price(underlying_price);
         assert_eq!(req.underlying_price, Some(underlying_price));
-        req.interest_rate(interest_rate);
+        req = req.interest_rate(interest_rate);
         assert_eq!(req.interest_rate, Some(interest_rate));
-        req.days_to_expiration(days_to_expiration);
+        req = req.days_to_expiration(days_to_expiration);
         assert_eq!(req.days_to_expiration, Some(days_to_expiration));
-        req.exp_month(exp_month);
+        req = req.exp_month(exp_month);
         assert_eq!(req.exp_month, Some(exp_month));
-        req.option_type(option_type.clone());
+        req = req.option_type(option_type.clone());
         assert_eq!(req.option_type, Some(option_type));
-        req.entitlement(entitlement);
+        req = req.entitlement(entitlement);
         assert_eq!(req.entitlement, Some(entitlement));
 
         dbg!(&req);
@@ -1640,21 +2453,70 @@ mod tests {
         assert_eq!(result.status, "SUCCESS");
     }
 
+    #[tokio::test]
+    async fn test_get_option_chains_request_analytical_echo() {
+        // Request a new server from the pool
+        let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
+
+        // Use one of these addresses to configure your client
+        let _host = server.host_with_port();
+
+        // the echoes of the file: volatility 29, underlyingPrice 189.91, interestRate 4.738, daysToExpiration 0
+        let mut body: serde_json::Value = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain_real.json"
+        )))
+        .unwrap();
+        body["strategy"] = "ANALYTICAL".into();
+        let mock = server
+            .mock("GET", "/marketdata/v1/chains")
+            .match_query(Matcher::UrlEncoded("strategy".into(), "ANALYTICAL".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string())
+            .expect(2)
+            .create_async()
+            .await;
+
+        let req = async || {
+            api.get_option_chains("AAPL".to_string())
+                .await
+                .unwrap()
+                .strategy(OptionChainStrategy::Analytical)
+                .volatility(29.0)
+                .underlying_price(189.91)
+                .days_to_expiration(0)
+        };
+
+        // echoed
+        let result = req().await.interest_rate(4.738).send().await;
+        assert!(result.is_ok());
+
+        // ignored
+        let result = req().await.interest_rate(5.0).send().await;
+        mock.assert_async().await;
+        let Err(Error::AnalyticalParamMismatch(msg)) = result else {
+            panic!("expected a mismatch: {result:?}");
+        };
+        assert_eq!(msg, "interestRate: requested 5, returned 4.738");
+    }
+
     #[tokio::test]
     async fn test_get_option_expiration_chain_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let symbol = "string".to_string();
 
         // Create a mock
         let mock = server
-            .mock("GET", "/expirationchain")
+            .mock("GET", "/marketdata/v1/expirationchain")
             .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
                 "symbol".into(),
                 symbol.clone(),
@@ -1669,12 +2531,10 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetOptionExpirationChainRequest::endpoint().url_endpoint()
-        ));
-        let req = GetOptionExpirationChainRequest::new_with(req, symbol.clone());
+        let req = api
+            .get_option_expiration_chain(symbol.clone())
+            .await
+            .unwrap();
 
         // check initial value
         assert_eq!(req.symbol, symbol);
@@ -1689,21 +2549,72 @@ mod tests {
         assert_eq!(result.expiration_list.len(), 21);
     }
 
+    #[tokio::test]
+    async fn test_get_option_expiration_chain_request_with_quote() {
+        // Request a new server from the pool
+        let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
+
+        // Use one of these addresses to configure your client
+        let _host = server.host_with_port();
+
+        // define parameter
+        let symbol = "AAPL".to_string();
+
+        // Create a mock
+        let mock_chain = server
+            .mock("GET", "/marketdata/v1/expirationchain")
+            .match_query(Matcher::UrlEncoded("symbol".into(), symbol.clone()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/MarketData/ExpirationChain_real.json"
+            ))
+            .create_async()
+            .await;
+        let mock_quote = server
+            .mock("GET", "/marketdata/v1/AAPL/quotes")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/MarketData/QuoteResponse_real.json"
+            ))
+            .create_async()
+            .await;
+
+        let req = api
+            .get_option_expiration_chain(symbol.clone())
+            .await
+            .unwrap();
+        let quote_req = api.get_quote(symbol.clone()).await.unwrap();
+
+        dbg!(&req);
+        let result = req.send_with_quote(quote_req).await;
+        mock_chain.assert_async().await;
+        mock_quote.assert_async().await;
+        let (chain, quote) = result.unwrap();
+        assert_eq!(chain.expiration_list.len(), 21);
+        assert_eq!(quote.symbol(), "AAPL");
+    }
+
     #[tokio::test]
     async fn test_get_price_history_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let symbol = "AAPL".to_string();
         let period_type = PeriodType::Day;
         let period = 1;
         let frequency_type = FrequencyType::Minute;
-        let frequency = 2;
+        let frequency = 5;
         let start_date = chrono::NaiveDate::from_ymd_opt(2015, 1, 1)
             .unwrap()
             .and_hms_milli_opt(0, 0, 1, 444)
@@ -1721,7 +2632,7 @@ mod tests {
 
         // Create a mock
         let mock = server
-            .mock("GET", "/pricehistory")
+            .mock("GET", "/marketdata/v1/pricehistory")
             .match_query(Matcher::AllOf(vec![
                 Matcher::UrlEncoded("symbol".into(), symbol.clone()),
                 Matcher::UrlEncoded("periodType".into(), "day".into()),
@@ -1749,12 +2660,7 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetPriceHistoryRequest::endpoint().url_endpoint()
-        ));
-        let mut req = GetPriceHistoryRequest::new_with(req, symbol.clone());
+        let mut req = api.get_price_history(symbol.clone()).await.unwrap();
 
         // check initial value
         assert_eq!(req.symbol, symbol);
@@ -1768,21 +2674,21 @@ mod tests {
         assert_eq!(req.need_previous_close, None);
 
         // check setter
-        req.period_type(period_type);
+        req = req.period_type(period_type);
         assert_eq!(req.period_type, Some(period_type));
-        req.period(period);
+        req = req.period(period);
         assert_eq!(req.period, Some(period));
-        req.frequency_type(frequency_type);
+        req = req.frequency_type(frequency_type);
         assert_eq!(req.frequency_type, Some(frequency_type));
-        req.frequency(frequency);
+        req = req.frequency(frequency);
         assert_eq!(req.frequency, Some(frequency));
-        req.start_date(start_date);
+        req = req.start_date(start_date);
         assert_eq!(req.start_date, Some(start_date.timestamp_millis()));
-        req.end_date(end_date);
+        req = req.end_date(end_date);
         assert_eq!(req.end_date, Some(end_date.timestamp_millis()));
-        req.need_extended_hours_data(need_extended_hours_data);
+        req = req.need_extended_hours_data(need_extended_hours_data);
         assert_eq!(req.need_extended_hours_data, Some(need_extended_hours_data));
-        req.need_previous_close(need_previous_close);
+        req = req.need_previous_close(need_previous_close);
         assert_eq!(req.need_previous_close, Some(need_previous_close));
 
         dbg!(&req);
@@ -1792,14 +2698,114 @@ mod tests {
         assert_eq!(result.symbol, "AAPL");
     }
 
+    #[test]
+    fn test_get_price_history_request_presets() {
+        let client = Client::new();
+        let new =
+            || GetPriceHistoryRequest::new_with(client.get("http://127.0.0.1"), "AAPL".into());
+
+        let req = new().with_daily(5).unwrap();
+        assert_eq!(req.period_type, Some(PeriodType::Year));
+        assert_eq!(req.period, Some(5));
+        assert_eq!(req.frequency_type, Some(FrequencyType::Daily));
+        assert_eq!(req.frequency, Some(1));
+        assert!(new().with_daily(1).is_ok());
+        assert!(new().with_daily(20).is_ok());
+        assert!(matches!(
+            new().with_daily(0),
+            Err(Error::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            new().with_daily(21),
+            Err(Error::InvalidParameter(_))
+        ));
+        // not a period Schwab accepts
+        assert!(matches!(
+            new().with_daily(4),
+            Err(Error::InvalidParameter(_))
+        ));
+
+        let req = new().with_minute(10, 15).unwrap();
+        assert_eq!(req.period_type, Some(PeriodType::Day));
+        assert_eq!(req.period, Some(10));
+        assert_eq!(req.frequency_type, Some(FrequencyType::Minute));
+        assert_eq!(req.frequency, Some(15));
+        assert!(matches!(
+            new().with_minute(6, 5),
+            Err(Error::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            new().with_minute(1, 2),
+            Err(Error::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_get_price_history_request_validate() {
+        let client = Client::new();
+        let new =
+            || GetPriceHistoryRequest::new_with(client.get("http://127.0.0.1"), "AAPL".into());
+
+        assert!(new().validate().is_ok());
+        assert!(new()
+            .period_type(PeriodType::Month)
+            .period(6)
+            .frequency_type(FrequencyType::Weekly)
+            .frequency(1)
+            .validate()
+            .is_ok());
+        let req = new()
+            .start_date(chrono::DateTime::from_timestamp(1_000, 0).unwrap())
+            .end_date(chrono::DateTime::from_timestamp(2_000, 0).unwrap())
+            .build_validated()
+            .unwrap();
+        assert_eq!(req.start_date, Some(1_000_000));
+        assert!(matches!(
+            new()
+                .period_type(PeriodType::Day)
+                .period(20)
+                .build_validated(),
+            Err(Error::InvalidParameter(_))
+        ));
+        // the presets are valid
+        assert!(new().with_daily(20).unwrap().validate().is_ok());
+        assert!(new().with_minute(10, 30).unwrap().validate().is_ok());
+
+        for req in [
+            // day only has minute candles
+            new()
+                .period_type(PeriodType::Day)
+                .frequency_type(FrequencyType::Monthly),
+            new()
+                .period_type(PeriodType::Ytd)
+                .frequency_type(FrequencyType::Monthly),
+            new().period_type(PeriodType::Day).period(6),
+            new().period_type(PeriodType::Month).period(4),
+            new().period_type(PeriodType::Ytd).period(2),
+            new().frequency_type(FrequencyType::Minute).frequency(2),
+            new().frequency_type(FrequencyType::Daily).frequency(5),
+            new()
+                .start_date(chrono::DateTime::from_timestamp(1_000, 0).unwrap())
+                .end_date(chrono::DateTime::from_timestamp(1_000, 0).unwrap()),
+            new()
+                .start_date(chrono::DateTime::from_timestamp(2_000, 0).unwrap())
+                .end_date(chrono::DateTime::from_timestamp(1_000, 0).unwrap()),
+        ] {
+            assert!(
+                matches!(req.validate(), Err(Error::InvalidParameter(_))),
+                "{req:?}"
+            );
+        }
+    }
+
     #[tokio::test]
     async fn test_get_movers_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let symbol = "$DJI".to_string();
@@ -1808,7 +2814,7 @@ mod tests {
 
         // Create a mock
         let mock = server
-            .mock("GET", "/movers/%24DJI")
+            .mock("GET", "/marketdata/v1/movers/%24DJI")
             .match_query(Matcher::AllOf(vec![
                 Matcher::UrlEncoded("sort".into(), "VOLUME".into()),
                 Matcher::UrlEncoded("frequency".into(), frequency.to_string()),
@@ -1823,12 +2829,7 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetMoversRequest::endpoint(symbol.clone()).url_endpoint()
-        ));
-        let mut req = GetMoversRequest::new_with(req, symbol.clone());
+        let mut req = api.get_movers(symbol.clone()).await.unwrap();
 
         // check initial value
         assert_eq!(req.symbol, symbol);
@@ -1836,9 +2837,9 @@ mod tests {
         assert_eq!(req.frequency, None);
 
         // check setter
-        req.sort(sort);
+        req = req.sort(sort);
         assert_eq!(req.sort, Some(sort));
-        req.frequency(frequency);
+        req = req.frequency(frequency);
         assert_eq!(req.frequency, Some(frequency));
 
         dbg!(&req);
@@ -1852,10 +2853,10 @@ mod tests {
     async fn test_get_markets_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let markets = vec![Market::Equity, Market::Option];
@@ -1863,7 +2864,7 @@ mod tests {
 
         // Create a mock
         let mock = server
-            .mock("GET", "/markets")
+            .mock("GET", "/marketdata/v1/markets")
             .match_query(Matcher::AllOf(vec![
                 Matcher::UrlEncoded("markets".into(), "equity,option".into()),
                 Matcher::UrlEncoded("date".into(), date.to_string()),
@@ -1878,19 +2879,14 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetMarketsRequest::endpoint().url_endpoint()
-        ));
-        let mut req = GetMarketsRequest::new_with(req, markets.clone());
+        let mut req = api.get_markets(markets.clone()).await.unwrap();
 
         // check initial value
         assert_eq!(req.markets, markets);
         assert_eq!(req.date, None);
 
         // check setter
-        req.date(date);
+        req = req.date(date);
         assert_eq!(req.date, Some(date));
 
         dbg!(&req);
@@ -1900,14 +2896,54 @@ mod tests {
         assert_eq!(result.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_get_markets_request_default_headers() {
+        // Request a new server from the pool
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        // Create a mock
+        let mock = server
+            .mock("GET", "/markets")
+            .match_header("x-api-key", "gateway-key")
+            .match_header("authorization", "Bearer access_token")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/MarketData/Markets.json"
+            ))
+            .create_async()
+            .await;
+
+        let mut config = ApiConfig::default();
+        config.default_headers.insert(
+            "x-api-key",
+            reqwest::header::HeaderValue::from_static("gateway-key"),
+        );
+        let client = config.client().unwrap();
+        let req = client
+            .get(format!(
+                "{url}{}",
+                GetMarketsRequest::endpoint().url_endpoint()
+            ))
+            .bearer_auth("access_token");
+        let req = GetMarketsRequest::new_with(req, vec![Market::Equity]);
+
+        let result = req.send().await;
+        mock.assert_async().await;
+        result.unwrap();
+    }
+
     #[tokio::test]
     async fn test_get_market_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let market_id = Market::Equity;
@@ -1915,12 +2951,8 @@ mod tests {
 
         // Create a mock
         let mock = server
-            .mock("GET", "/markets/equity")
-            .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
-                "date".into(),
-                date.to_string(),
-            )]))
-            // .match_query(Matcher::Any)
+            .mock("GET", "/marketdata/v1/markets/equity")
+            .match_query(Matcher::Exact(format!("date={date}")))
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body(
@@ -1961,19 +2993,13 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetMarketRequest::endpoint(market_id).url_endpoint()
-        ));
-        let mut req = GetMarketRequest::new_with(req, market_id);
+        let mut req = api.get_market(market_id).await.unwrap();
 
         // check initial value
-        assert_eq!(req.market_id, market_id);
         assert_eq!(req.date, None);
 
         // check setter
-        req.date(date);
+        req = req.date(date);
         assert_eq!(req.date, Some(date));
 
         dbg!(&req);
@@ -1987,10 +3013,10 @@ mod tests {
     async fn test_get_instruments_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let symbol = "AAPL".to_string();
@@ -1998,7 +3024,7 @@ mod tests {
 
         // Create a mock
         let mock = server
-            .mock("GET", "/instruments")
+            .mock("GET", "/marketdata/v1/instruments")
             .match_query(Matcher::AllOf(vec![
                 Matcher::UrlEncoded("symbol".into(), symbol.clone()),
                 Matcher::UrlEncoded("projection".into(), "symbol-search".into()),
@@ -2013,42 +3039,44 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetInstrumentsRequest::endpoint().url_endpoint()
-        ));
-        let req = GetInstrumentsRequest::new_with(req, symbol.clone(), projection);
+        let mut req = api
+            .get_instruments(symbol.clone(), projection)
+            .await
+            .unwrap();
 
         // check initial value
         assert_eq!(req.symbol, symbol);
         assert_eq!(req.projection, projection);
+        assert_eq!(req.limit, None);
+        assert_eq!(req.offset, None);
 
         // check setter
-        // none
+        req = req.limit(5).offset(1);
+        assert_eq!(req.limit, Some(5));
+        assert_eq!(req.offset, Some(1));
 
         dbg!(&req);
         let result = req.send().await;
         mock.assert_async().await;
         let result = result.unwrap();
-        assert_eq!(result.instruments.len(), 2);
+        assert_eq!(result.instruments.len(), 1);
     }
 
     #[tokio::test]
     async fn test_get_instrument_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let cusip_id = "037833100".to_string();
 
         // Create a mock
         let mock = server
-            .mock("GET", "/instruments/037833100")
+            .mock("GET", "/marketdata/v1/instruments/037833100")
             // .match_query(Matcher::Any)
             .with_status(200)
             .with_header("content-type", "application/json")
@@ -2068,12 +3096,7 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetInstrumentRequest::endpoint(cusip_id.clone()).url_endpoint()
-        ));
-        let req = GetInstrumentRequest::new_with(req, cusip_id.clone());
+        let req = api.get_instrument(cusip_id.clone()).await.unwrap();
 
         // check initial value
         assert_eq!(req.cusip_id, cusip_id);
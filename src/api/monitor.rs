@@ -0,0 +1,94 @@
+//! Polling the positions of an account together with their quotes.
+
+use crate::model;
+use crate::model::trader::accounts::Position;
+
+/// A position with the latest quote of its instrument.
+#[derive(Debug, Clone)]
+pub struct PositionQuote {
+    pub position: Position,
+    /// `None` if Schwab returned no quote for the symbol.
+    pub quote: Option<model::QuoteResponse>,
+}
+
+impl PositionQuote {
+    /// The value of the net quantity at the last price, if quoted.
+    ///
+    /// Options and futures are scaled by the contract multiplier of their quote.
+    /// Bonds are not quoted by Schwab, so their value is `None`.
+    #[must_use]
+    pub fn market_value(&self) -> Option<f64> {
+        let quantity = self.position.long_quantity - self.position.short_quantity;
+        let quote = self.quote.as_ref()?;
+        let multiplier = match quote {
+            model::QuoteResponse::Bond(_) => return None,
+            model::QuoteResponse::Option(x) => x.reference.multiplier,
+            model::QuoteResponse::FutureOption(x) => x.reference.multiplier,
+            model::QuoteResponse::Future(x) => x.reference.future_multiplier,
+            _ => 1.0,
+        };
+        quote
+            .last_price()
+            .map(|price| price * quantity * multiplier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use float_cmp::assert_approx_eq;
+    use std::collections::HashMap;
+
+    fn position(long_quantity: f64) -> Position {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Account.json"
+        ));
+        let mut json: serde_json::Value = serde_json::from_str(json).unwrap();
+        let mut position = json["securitiesAccount"]["positions"][0].take();
+        position["longQuantity"] = long_quantity.into();
+        position["instrument"] = serde_json::json!({
+            "assetType": "EQUITY",
+            "cusip": "037833100",
+            "symbol": "AAPL",
+            "description": "APPLE INC",
+            "instrumentId": 1,
+        });
+        serde_json::from_value(position).unwrap()
+    }
+
+    #[test]
+    fn test_market_value() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/QuoteResponse/OptionResponse.json"
+        ));
+        let mut quotes =
+            serde_json::from_str::<HashMap<String, model::QuoteResponse>>(json).unwrap();
+        let quote = quotes.remove("AMZN  220617C03170000").unwrap();
+        let last_price = quote.last_price().unwrap();
+
+        let option = PositionQuote {
+            position: position(2.0),
+            quote: Some(quote),
+        };
+        assert_approx_eq!(
+            f64,
+            last_price * 2.0 * 100.0,
+            option.market_value().unwrap()
+        );
+
+        let bond = PositionQuote {
+            position: position(2.0),
+            quote: Some(model::QuoteResponse::Bond("912797GL5".to_string())),
+        };
+        assert_eq!(None, bond.market_value());
+
+        let unquoted = PositionQuote {
+            position: position(2.0),
+            quote: None,
+        };
+        assert_eq!(None, unquoted.market_value());
+    }
+}
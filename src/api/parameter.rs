@@ -1,10 +1,26 @@
-/// specifies Parameter for Schwab API
+//! specifies Parameter for Schwab API
+//!
+//! Also re-exported as `schwab_api::parameter`.
+//!
+//! ```
+//! use schwab_api::parameter::{ContractType, Market, TransactionType};
+//!
+//! assert_eq!(serde_json::to_value(Market::Equity).unwrap(), "equity");
+//! assert_eq!(serde_json::to_value(ContractType::All).unwrap(), "ALL");
+//! assert_eq!(
+//!     serde_json::to_value(TransactionType::DividendOrInterest).unwrap(),
+//!     "DIVIDEND_OR_INTEREST"
+//! );
+//! ```
+
 use serde::Deserialize;
 use serde::Serialize;
 
 /// Field
 ///
 /// possible root nodes are `quote`, `fundamental`, `extended`, `reference`, `regular`.
+///
+/// Available values : `quote`, `fundamental`, `extended`, `reference`, `regular`, `all`, or any other string through `Extra`
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum QuoteField {
@@ -49,6 +65,37 @@ pub enum OptionChainStrategy {
     Roll,
 }
 
+/// Option Chain range, relative to the price of the underlying
+///
+/// Available values : `ITM`, `NTM`, `OTM`, `SAK`, `SBK`, `SNK`, `ALL`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Range {
+    /// In-the-money
+    Itm,
+    /// Near-the-money
+    Ntm,
+    /// Out-of-the-money
+    Otm,
+    /// Strikes above market
+    Sak,
+    /// Strikes below market
+    Sbk,
+    /// Strikes near market
+    Snk,
+    All,
+}
+
+impl From<Range> for String {
+    fn from(value: Range) -> Self {
+        serde_json::to_value(value)
+            .expect("value")
+            .as_str()
+            .expect("value is a str")
+            .to_string()
+    }
+}
+
 /// Expiration month
 ///
 /// Available values : `JAN`, `FEB`, `MAR`, `APR`, `MAY`, `JUN`, `JUL`, `AUG`, `SEP`, `OCT`, `NOV`, `DEC`, `ALL`
@@ -195,3 +242,27 @@ pub enum TransactionType {
     MoneyMarket,
     SmaAdjustment,
 }
+
+impl TransactionType {
+    /// Every type, to fetch all transactions in one request
+    #[must_use]
+    pub fn all() -> Vec<TransactionType> {
+        vec![
+            TransactionType::Trade,
+            TransactionType::ReceiveAndDeliver,
+            TransactionType::DividendOrInterest,
+            TransactionType::AchReceipt,
+            TransactionType::AchDisbursement,
+            TransactionType::CashReceipt,
+            TransactionType::CashDisbursement,
+            TransactionType::ElectronicFund,
+            TransactionType::WireOut,
+            TransactionType::WireIn,
+            TransactionType::Journal,
+            TransactionType::Memorandum,
+            TransactionType::MarginCall,
+            TransactionType::MoneyMarket,
+            TransactionType::SmaAdjustment,
+        ]
+    }
+}
@@ -0,0 +1,104 @@
+//! Keeping the request rate of an [`Api`](crate::Api) under Schwab's limits.
+
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use tokio::sync::Semaphore;
+
+/// Lets at most `requests_per_second` requests through per second.
+///
+/// Up to one second worth of requests may be sent at once; afterwards a permit is added every
+/// `1 / requests_per_second` seconds by a background task, which ends with the limiter.
+#[derive(Debug)]
+pub struct RateLimiter {
+    requests_per_second: f64,
+    permits: Arc<Semaphore>,
+}
+
+impl RateLimiter {
+    /// # Panics
+    ///
+    /// Will panic if `requests_per_second` is not positive, or if called outside a Tokio runtime.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn new(requests_per_second: f64) -> Self {
+        assert!(
+            requests_per_second.is_finite() && requests_per_second > 0.0,
+            "requests_per_second must be positive: {requests_per_second}"
+        );
+
+        let capacity = requests_per_second.ceil().max(1.0) as usize;
+        let permits = Arc::new(Semaphore::new(capacity));
+        tokio::spawn(Self::replenish(
+            Arc::downgrade(&permits),
+            capacity,
+            Duration::from_secs_f64(1.0 / requests_per_second),
+        ));
+
+        Self {
+            requests_per_second,
+            permits,
+        }
+    }
+
+    /// The rate this limiter was created with.
+    #[must_use]
+    pub fn requests_per_second(&self) -> f64 {
+        self.requests_per_second
+    }
+
+    async fn replenish(permits: Weak<Semaphore>, capacity: usize, period: Duration) {
+        let mut interval = tokio::time::interval(period);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let Some(permits) = permits.upgrade() else {
+                return;
+            };
+            if permits.available_permits() < capacity {
+                permits.add_permits(1);
+            }
+        }
+    }
+
+    /// Wait until a request may be sent.
+    pub(crate) async fn acquire(&self) {
+        self.permits
+            .acquire()
+            .await
+            .expect("the semaphore is never closed")
+            .forget();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn test_rate_limiter() {
+        let limiter = RateLimiter::new(10.0);
+
+        // burst
+        let start = Instant::now();
+        for _ in 0..10 {
+            limiter.acquire().await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        // then one every 100ms
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert!(start.elapsed() >= Duration::from_millis(250));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "requests_per_second must be positive")]
+    async fn test_rate_limiter_invalid() {
+        let _ = RateLimiter::new(0.0);
+    }
+}
@@ -0,0 +1,12 @@
+//! Decoding response bodies while keeping them around for diagnostics.
+
+use reqwest::Response;
+use serde::de::DeserializeOwned;
+
+use crate::error::Error;
+
+/// Read the body as text and deserialize it, attaching the raw body to [`Error::Deserialization`] on failure.
+pub(crate) async fn json<T: DeserializeOwned>(rsp: Response) -> Result<T, Error> {
+    let body = rsp.text().await?;
+    serde_json::from_str(&body).map_err(|source| Error::Deserialization { source, body })
+}
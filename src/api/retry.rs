@@ -0,0 +1,435 @@
+//! Retrying throttled and failed requests with exponential backoff, and unauthorized ones with a new token.
+
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use reqwest::header::{HeaderValue, AUTHORIZATION, RETRY_AFTER};
+use reqwest::{RequestBuilder, Response, StatusCode};
+
+use super::config::SendOptions;
+use crate::error::Error;
+
+/// How requests answered with a status of `retry_on`, or failing to connect, are retried.
+///
+/// The backoff doubles after every attempt, up to `max_backoff`, and is jittered by up to half of it.
+/// A `Retry-After` header of a `429` response overrides the computed backoff.
+/// Other statuses, e.g. `400`, `401`, `403` or `404`, are returned immediately.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Total number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// The wait before the first retry.
+    pub initial_backoff: Duration,
+    /// The upper bound of the wait between two attempts.
+    pub max_backoff: Duration,
+    /// The statuses worth another attempt, `429`, `500`, `502`, `503` and `504` by default.
+    pub retry_on: Vec<StatusCode>,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            retry_on: vec![
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+        }
+    }
+}
+
+impl RetryConfig {
+    fn is_retryable(&self, status: StatusCode) -> bool {
+        self.retry_on.contains(&status)
+    }
+
+    fn retry_after(rsp: &Response) -> Option<Duration> {
+        if rsp.status() != StatusCode::TOO_MANY_REQUESTS {
+            return None;
+        }
+
+        rsp.headers()
+            .get(RETRY_AFTER)?
+            .to_str()
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+            .map(Duration::from_secs)
+    }
+
+    /// Somewhere between half of `backoff` and `backoff`, so that clients throttled together spread out.
+    fn jitter(backoff: Duration) -> Duration {
+        let random = std::collections::hash_map::RandomState::new()
+            .build_hasher()
+            .finish();
+        let half = backoff / 2;
+        let nanos = u64::try_from(half.as_nanos()).unwrap_or(u64::MAX);
+        half + Duration::from_nanos(random % nanos.saturating_add(1))
+    }
+}
+
+/// Send `req`, waiting for the rate limiter before each attempt and retrying according to `options`.
+///
+/// With a reauthorizer, a `401 Unauthorized` response is replayed once with the access token fetched again.
+/// Requests whose body can not be cloned are sent only once,
+/// non-idempotent ones are only retried on connect errors.
+/// The timeout covers all of it and fails with [`Error::Timeout`].
+/// A `429 Too Many Requests` left after retrying fails with [`Error::RateLimited`],
+/// a `401 Unauthorized` with [`Error::Unauthorized`].
+pub(crate) async fn send(req: RequestBuilder, options: &SendOptions) -> Result<Response, Error> {
+    let rsp = match options.timeout {
+        Some(timeout) => tokio::time::timeout(timeout, send_reauthorizing(req, options))
+            .await
+            .map_err(|_| Error::Timeout(timeout))?,
+        None => send_reauthorizing(req, options).await,
+    }?;
+
+    if rsp.status() == StatusCode::TOO_MANY_REQUESTS {
+        return Err(Error::RateLimited {
+            retry_after: RetryConfig::retry_after(&rsp),
+        });
+    }
+    if rsp.status() == StatusCode::UNAUTHORIZED {
+        return Err(Error::Unauthorized);
+    }
+
+    Ok(rsp)
+}
+
+async fn send_reauthorizing(req: RequestBuilder, options: &SendOptions) -> Result<Response, Error> {
+    let Some((reauthorizer, replay)) = options
+        .reauthorizer
+        .as_ref()
+        .and_then(|x| Some((x, req.try_clone()?)))
+    else {
+        return send_retrying(req, options).await;
+    };
+
+    let rsp = send_retrying(req, options).await?;
+    if rsp.status() != StatusCode::UNAUTHORIZED {
+        return Ok(rsp);
+    }
+
+    let access_token = (reauthorizer.0)().await?;
+    let (client, request) = replay.build_split();
+    let mut request = request?;
+    let mut bearer = HeaderValue::from_str(&format!("Bearer {access_token}"))
+        .map_err(|e| Error::Token(e.to_string()))?;
+    bearer.set_sensitive(true);
+    request.headers_mut().insert(AUTHORIZATION, bearer);
+
+    send_retrying(RequestBuilder::from_parts(client, request), options).await
+}
+
+async fn send_retrying(req: RequestBuilder, options: &SendOptions) -> Result<Response, Error> {
+    let acquire = || async {
+        if let Some(limiter) = &options.rate_limiter {
+            limiter.acquire().await;
+        }
+    };
+
+    let Some(retry) = &options.retry else {
+        acquire().await;
+        return req.send().await.map_err(std::convert::Into::into);
+    };
+
+    let mut backoff = retry.initial_backoff;
+    let mut attempt = 1;
+    loop {
+        acquire().await;
+        let Some(next) = req.try_clone().filter(|_| attempt < retry.max_attempts) else {
+            return req.send().await.map_err(std::convert::Into::into);
+        };
+
+        // a request that could not connect never reached Schwab, so even orders are safe to resend,
+        // but a 5xx may come after an order was accepted
+        let wait = match next.send().await {
+            Ok(rsp) if options.non_idempotent || !retry.is_retryable(rsp.status()) => {
+                return Ok(rsp)
+            }
+            Ok(rsp) => RetryConfig::retry_after(&rsp),
+            Err(e) if e.is_connect() => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        tokio::time::sleep(wait.unwrap_or_else(|| RetryConfig::jitter(backoff))).await;
+        backoff = (backoff * 2).min(retry.max_backoff);
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use reqwest::Client;
+
+    fn retry() -> SendOptions {
+        SendOptions {
+            retry: Some(RetryConfig {
+                max_attempts: 3,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(2),
+                ..RetryConfig::default()
+            }),
+            rate_limiter: None,
+            reauthorizer: None,
+            timeout: None,
+            non_idempotent: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_retry() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+        let client = Client::new();
+
+        // throttled, then served
+        let mock_throttled = server
+            .mock("GET", "/throttled")
+            .with_status(429)
+            .with_header("retry-after", "0")
+            .expect(1)
+            .create_async()
+            .await;
+        let mock_ok = server
+            .mock("GET", "/throttled")
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+        let rsp = send(client.get(format!("{url}/throttled")), &retry())
+            .await
+            .unwrap();
+        mock_throttled.assert_async().await;
+        mock_ok.assert_async().await;
+        assert_eq!(rsp.status(), StatusCode::OK);
+
+        // failing twice, then served
+        let mock_failing = server
+            .mock("GET", "/flaky")
+            .with_status(503)
+            .expect(2)
+            .create_async()
+            .await;
+        let mock_ok = server
+            .mock("GET", "/flaky")
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+        let rsp = send(client.get(format!("{url}/flaky")), &retry())
+            .await
+            .unwrap();
+        mock_failing.assert_async().await;
+        mock_ok.assert_async().await;
+        assert_eq!(rsp.status(), StatusCode::OK);
+
+        // the whole budget is used on 5xx
+        let mock_unavailable = server
+            .mock("GET", "/unavailable")
+            .with_status(503)
+            .expect(3)
+            .create_async()
+            .await;
+        let rsp = send(client.get(format!("{url}/unavailable")), &retry())
+            .await
+            .unwrap();
+        mock_unavailable.assert_async().await;
+        assert_eq!(rsp.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        // not in retry_on
+        let mock_not_implemented = server
+            .mock("GET", "/not_implemented")
+            .with_status(501)
+            .expect(1)
+            .create_async()
+            .await;
+        let rsp = send(client.get(format!("{url}/not_implemented")), &retry())
+            .await
+            .unwrap();
+        mock_not_implemented.assert_async().await;
+        assert_eq!(rsp.status(), StatusCode::NOT_IMPLEMENTED);
+
+        // not retryable
+        let mock_not_found = server
+            .mock("GET", "/not_found")
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+        let rsp = send(client.get(format!("{url}/not_found")), &retry())
+            .await
+            .unwrap();
+        mock_not_found.assert_async().await;
+        assert_eq!(rsp.status(), StatusCode::NOT_FOUND);
+
+        // disabled
+        let mock_disabled = server
+            .mock("GET", "/disabled")
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+        let rsp = send(
+            client.get(format!("{url}/disabled")),
+            &SendOptions::default(),
+        )
+        .await
+        .unwrap();
+        mock_disabled.assert_async().await;
+        assert_eq!(rsp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_send_non_idempotent() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+        let options = SendOptions {
+            non_idempotent: true,
+            ..retry()
+        };
+
+        // the order may have been placed before the 503
+        let mock = server
+            .mock("POST", "/orders")
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+        let rsp = send(
+            Client::new().post(format!("{url}/orders")).body("{}"),
+            &options,
+        )
+        .await
+        .unwrap();
+        mock.assert_async().await;
+        assert_eq!(rsp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_send_rate_limited() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+        let client = Client::new();
+
+        let mock = server
+            .mock("GET", "/throttled")
+            .with_status(429)
+            .with_header("retry-after", "3")
+            .with_body("not json")
+            .expect(1)
+            .create_async()
+            .await;
+        let result = send(
+            client.get(format!("{url}/throttled")),
+            &SendOptions::default(),
+        )
+        .await;
+        mock.assert_async().await;
+        assert!(matches!(
+            result,
+            Err(Error::RateLimited { retry_after: Some(x) }) if x == Duration::from_secs(3)
+        ));
+
+        // still throttled once the retries are used up, without a Retry-After
+        let mock = server
+            .mock("GET", "/always")
+            .with_status(429)
+            .expect(3)
+            .create_async()
+            .await;
+        let result = send(client.get(format!("{url}/always")), &retry()).await;
+        mock.assert_async().await;
+        assert!(matches!(
+            result,
+            Err(Error::RateLimited { retry_after: None })
+        ));
+        assert!(result.unwrap_err().is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_send_unauthorized() {
+        let mut server = mockito::Server::new_async().await;
+        let url = server.url();
+
+        // not retried
+        let mock = server
+            .mock("GET", "/unauthorized")
+            .with_status(401)
+            .expect(1)
+            .create_async()
+            .await;
+        let result = send(Client::new().get(format!("{url}/unauthorized")), &retry()).await;
+        mock.assert_async().await;
+        let error = result.unwrap_err();
+        assert!(matches!(error, Error::Unauthorized));
+        assert!(error.is_auth_error());
+        assert!(!error.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_send_retry_connect() {
+        // nothing listens on the port once the listener is dropped
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let options = SendOptions {
+            retry: Some(RetryConfig {
+                max_attempts: 3,
+                initial_backoff: Duration::from_millis(40),
+                max_backoff: Duration::from_millis(40),
+                ..RetryConfig::default()
+            }),
+            ..SendOptions::default()
+        };
+
+        let start = std::time::Instant::now();
+        let result = send(Client::new().get(format!("http://{addr}/")), &options).await;
+        assert!(matches!(result, Err(Error::Reqwest(e)) if e.is_connect()));
+        // two jittered waits of at least 20ms each
+        assert!(start.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[test]
+    fn test_jitter() {
+        let backoff = Duration::from_millis(100);
+        for _ in 0..100 {
+            let wait = RetryConfig::jitter(backoff);
+            assert!(wait >= backoff / 2 && wait <= backoff, "{wait:?}");
+        }
+        assert_eq!(RetryConfig::jitter(Duration::ZERO), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_send_timeout() {
+        // accepts connections but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let mut sockets = vec![];
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                sockets.push(socket);
+            }
+        });
+
+        let timeout = Duration::from_millis(50);
+        let options = SendOptions {
+            timeout: Some(timeout),
+            ..retry()
+        };
+        let result = send(Client::new().get(format!("http://{addr}/")), &options).await;
+        assert!(matches!(result, Err(Error::Timeout(x)) if x == timeout));
+
+        server.abort();
+    }
+}
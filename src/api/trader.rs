@@ -3,15 +3,41 @@
 
 use reqwest::{Client, RequestBuilder, StatusCode};
 
+use super::config::SendOptions;
 use super::endpoints;
 use super::parameter::{Status, TransactionType};
+use super::response;
+use super::retry;
+use super::ApiConfig;
 use crate::api::Error;
 use crate::model;
+use crate::model::trader::accounts::AccountBalances;
+
+/// The longest range Schwab accepts between `from_entered_time` and `to_entered_time`.
+const MAX_ENTERED_TIME_RANGE_DAYS: i64 = 60;
+
+fn check_entered_time_range(
+    from_entered_time: chrono::DateTime<chrono::Utc>,
+    to_entered_time: chrono::DateTime<chrono::Utc>,
+) -> Result<(), Error> {
+    let range = to_entered_time - from_entered_time;
+    if range > chrono::TimeDelta::days(MAX_ENTERED_TIME_RANGE_DAYS) {
+        // count a started day as a whole one, 60 days and 1 second is 61 days
+        let days = (range - chrono::TimeDelta::nanoseconds(1)).num_days() + 1;
+        return Err(Error::InvalidTimeRange {
+            days,
+            max: MAX_ENTERED_TIME_RANGE_DAYS,
+        });
+    }
+
+    Ok(())
+}
 
 /// Get list of account numbers and their encrypted values
 #[derive(Debug)]
 pub struct GetAccountNumbersRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 }
 
 impl GetAccountNumbersRequest {
@@ -19,13 +45,28 @@ impl GetAccountNumbersRequest {
         endpoints::EndpointAccount::AccountNumbers
     }
 
-    pub(crate) fn new(client: &Client, access_token: String) -> Self {
-        let req = client.get(Self::endpoint().url()).bearer_auth(access_token);
-        Self::new_with(req)
+    pub(crate) fn new(client: &Client, config: &ApiConfig, access_token: String) -> Self {
+        let req = client
+            .get(Self::endpoint().url(&config.trader_url))
+            .bearer_auth(access_token);
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req)
+        }
     }
 
     fn new_with(req: RequestBuilder) -> Self {
-        Self { req }
+        Self {
+            req,
+            send_options: SendOptions::default(),
+        }
+    }
+
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
     }
 
     fn build(self) -> RequestBuilder {
@@ -33,18 +74,17 @@ impl GetAccountNumbersRequest {
     }
 
     pub async fn send(self) -> Result<model::AccountNumbers, Error> {
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
+            let error_response = response::json::<model::ServiceError>(rsp).await?;
             return Err(Error::Service(error_response));
         }
 
-        rsp.json::<model::AccountNumbers>()
-            .await
-            .map_err(std::convert::Into::into)
+        response::json::<model::AccountNumbers>(rsp).await
     }
 }
 
@@ -52,6 +92,7 @@ impl GetAccountNumbersRequest {
 #[derive(Debug)]
 pub struct GetAccountsRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 
     /// This allows one to determine which fields they want returned.
     ///
@@ -68,13 +109,22 @@ impl GetAccountsRequest {
         endpoints::EndpointAccount::Accounts
     }
 
-    pub(crate) fn new(client: &Client, access_token: String) -> Self {
-        let req = client.get(Self::endpoint().url()).bearer_auth(access_token);
-        Self::new_with(req)
+    pub(crate) fn new(client: &Client, config: &ApiConfig, access_token: String) -> Self {
+        let req = client
+            .get(Self::endpoint().url(&config.trader_url))
+            .bearer_auth(access_token);
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req)
+        }
     }
 
     fn new_with(req: RequestBuilder) -> Self {
-        Self { req, fields: None }
+        Self {
+            req,
+            send_options: SendOptions::default(),
+            fields: None,
+        }
     }
 
     /// This allows one to determine which fields they want returned.
@@ -84,11 +134,19 @@ impl GetAccountsRequest {
     /// Example:
     ///
     /// fields=`positions`
-    pub fn fields(&mut self, val: String) -> &mut Self {
+    #[must_use]
+    pub fn fields(mut self, val: String) -> Self {
         self.fields = Some(val);
         self
     }
 
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req;
         if let Some(x) = self.fields {
@@ -99,18 +157,17 @@ impl GetAccountsRequest {
     }
 
     pub async fn send(self) -> Result<model::Accounts, Error> {
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
+            let error_response = response::json::<model::ServiceError>(rsp).await?;
             return Err(Error::Service(error_response));
         }
 
-        rsp.json::<model::Accounts>()
-            .await
-            .map_err(std::convert::Into::into)
+        response::json::<model::Accounts>(rsp).await
     }
 }
 
@@ -118,6 +175,7 @@ impl GetAccountsRequest {
 #[derive(Debug)]
 pub struct GetAccountRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 
     #[allow(dead_code)]
     /// The encrypted ID of the account
@@ -138,16 +196,25 @@ impl GetAccountRequest {
         endpoints::EndpointAccount::Account { account_number }
     }
 
-    pub(crate) fn new(client: &Client, access_token: String, account_number: String) -> Self {
+    pub(crate) fn new(
+        client: &Client,
+        config: &ApiConfig,
+        access_token: String,
+        account_number: String,
+    ) -> Self {
         let req = client
-            .get(Self::endpoint(account_number.clone()).url())
+            .get(Self::endpoint(account_number.clone()).url(&config.trader_url))
             .bearer_auth(access_token);
-        Self::new_with(req, account_number)
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req, account_number)
+        }
     }
 
     fn new_with(req: RequestBuilder, account_number: String) -> Self {
         Self {
             req,
+            send_options: SendOptions::default(),
             account_number,
             fields: None,
         }
@@ -160,11 +227,19 @@ impl GetAccountRequest {
     /// Example:
     ///
     /// fields=`positions`
-    pub fn fields(&mut self, val: String) -> &mut Self {
+    #[must_use]
+    pub fn fields(mut self, val: String) -> Self {
         self.fields = Some(val);
         self
     }
 
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req;
         if let Some(x) = self.fields {
@@ -175,18 +250,37 @@ impl GetAccountRequest {
     }
 
     pub async fn send(self) -> Result<model::Account, Error> {
+        let send_options = self.send_options.clone();
+        let req = self.build();
+        let rsp = retry::send(req, &send_options).await?;
+
+        let status = rsp.status();
+        if status != StatusCode::OK {
+            let error_response = response::json::<model::ServiceError>(rsp).await?;
+            return Err(Error::Service(error_response));
+        }
+
+        response::json::<model::Account>(rsp).await
+    }
+
+    /// Fetch only the balances of the account.
+    ///
+    /// `fields` is dropped so that positions are not requested.
+    pub async fn send_balances(mut self) -> Result<model::Balances, Error> {
+        self.fields = None;
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
+            let error_response = response::json::<model::ServiceError>(rsp).await?;
             return Err(Error::Service(error_response));
         }
 
-        rsp.json::<model::Account>()
+        response::json::<AccountBalances>(rsp)
             .await
-            .map_err(std::convert::Into::into)
+            .map(|x| x.securities_account)
     }
 }
 
@@ -194,6 +288,7 @@ impl GetAccountRequest {
 #[derive(Debug)]
 pub struct GetAccountOrdersRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 
     #[allow(dead_code)]
     /// The encrypted ID of the account
@@ -220,7 +315,12 @@ pub struct GetAccountOrdersRequest {
     /// Specifies that only orders of this status should be returned.
     ///
     /// Available values : `AWAITING_PARENT_ORDER`, `AWAITING_CONDITION`, `AWAITING_STOP_CONDITION`, `AWAITING_MANUAL_REVIEW`, `ACCEPTED`, `AWAITING_UR_OUT`, `PENDING_ACTIVATION`, `QUEUED`, `WORKING`, `REJECTED`, `PENDING_CANCEL`, `CANCELED`, `PENDING_REPLACE`, `REPLACED`, `FILLED`, `EXPIRED`, `NEW`, `AWAITING_RELEASE_TIME`, `PENDING_ACKNOWLEDGEMENT`, `PENDING_RECALL`, `UNKNOWN`
-    status: Option<Status>,
+    status: Option<Vec<Status>>,
+
+    /// Specifies that only orders with a leg of this symbol should be returned.
+    ///
+    /// Not supported by Schwab, the orders are filtered once received.
+    symbol: Option<String>,
 }
 
 impl GetAccountOrdersRequest {
@@ -230,15 +330,21 @@ impl GetAccountOrdersRequest {
 
     pub(crate) fn new(
         client: &Client,
+        config: &ApiConfig,
         access_token: String,
         account_number: String,
         from_entered_time: chrono::DateTime<chrono::Utc>,
         to_entered_time: chrono::DateTime<chrono::Utc>,
-    ) -> Self {
+    ) -> Result<Self, Error> {
+        check_entered_time_range(from_entered_time, to_entered_time)?;
+
         let req = client
-            .get(Self::endpoint(account_number.clone()).url())
+            .get(Self::endpoint(account_number.clone()).url(&config.trader_url))
             .bearer_auth(access_token);
-        Self::new_with(req, account_number, from_entered_time, to_entered_time)
+        Ok(Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req, account_number, from_entered_time, to_entered_time)
+        })
     }
 
     fn new_with(
@@ -249,17 +355,20 @@ impl GetAccountOrdersRequest {
     ) -> Self {
         Self {
             req,
+            send_options: SendOptions::default(),
             account_number,
             max_results: None,
             from_entered_time,
             to_entered_time,
             status: None,
+            symbol: None,
         }
     }
 
     /// The max number of orders to retrieve.
     /// Default is `3000`.
-    pub fn max_results(&mut self, val: i64) -> &mut Self {
+    #[must_use]
+    pub fn max_results(mut self, val: i64) -> Self {
         self.max_results = Some(val);
         self
     }
@@ -267,11 +376,35 @@ impl GetAccountOrdersRequest {
     /// Specifies that only orders of this status should be returned.
     ///
     /// Available values : `AWAITING_PARENT_ORDER`, `AWAITING_CONDITION`, `AWAITING_STOP_CONDITION`, `AWAITING_MANUAL_REVIEW`, `ACCEPTED`, `AWAITING_UR_OUT`, `PENDING_ACTIVATION`, `QUEUED`, `WORKING`, `REJECTED`, `PENDING_CANCEL`, `CANCELED`, `PENDING_REPLACE`, `REPLACED`, `FILLED`, `EXPIRED`, `NEW`, `AWAITING_RELEASE_TIME`, `PENDING_ACKNOWLEDGEMENT`, `PENDING_RECALL`, `UNKNOWN`
-    pub fn status(&mut self, val: Status) -> &mut Self {
+    #[must_use]
+    pub fn status(mut self, val: Status) -> Self {
+        self.status = Some(vec![val]);
+        self
+    }
+
+    /// Specifies that only orders of any of these statuses should be returned, e.g. `WORKING`, `PENDING_ACTIVATION` and `QUEUED`.
+    #[must_use]
+    pub fn statuses(mut self, val: Vec<Status>) -> Self {
         self.status = Some(val);
         self
     }
 
+    /// Specifies that only orders with a leg of this symbol should be returned.
+    ///
+    /// Not supported by Schwab, the orders are filtered once received.
+    #[must_use]
+    pub fn symbol(mut self, val: String) -> Self {
+        self.symbol = Some(val);
+        self
+    }
+
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req.query(&[
             (
@@ -287,15 +420,25 @@ impl GetAccountOrdersRequest {
             req = req.query(&[("maxResults", x)]);
         }
         if let Some(x) = self.status {
-            req = req.query(&[("status", x)]);
+            let x: Vec<String> = x
+                .into_iter()
+                .map(|s| serde_json::to_value(s).expect("value"))
+                .map(|v| v.as_str().expect("value is a str").to_string())
+                .collect();
+            req = req.query(&[("status", x.join(","))]);
         }
 
         req
     }
 
+    /// Schwab occasionally returns the same order twice when filtering by several statuses,
+    /// so with [`Self::statuses`] only the most recently entered entry of each `order_id` is kept.
     pub async fn send(self) -> Result<Vec<model::Order>, Error> {
+        let multi_status = self.status.as_ref().is_some_and(|x| x.len() > 1);
+        let symbol = self.symbol.clone();
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -306,13 +449,19 @@ impl GetAccountOrdersRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
+            let error_response = response::json::<model::ServiceError>(rsp).await?;
             return Err(Error::Service(error_response));
         }
 
-        rsp.json::<Vec<model::Order>>()
-            .await
-            .map_err(std::convert::Into::into)
+        let mut orders = response::json::<Vec<model::Order>>(rsp).await?;
+        if let Some(symbol) = symbol {
+            orders.retain(|x| has_symbol(x, &symbol));
+        }
+        if multi_status {
+            return Ok(dedup_orders(orders));
+        }
+
+        Ok(orders)
     }
 }
 
@@ -320,12 +469,15 @@ impl GetAccountOrdersRequest {
 #[derive(Debug)]
 pub struct PostAccountOrderRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 
-    #[allow(dead_code)]
     /// The encrypted ID of the account
     account_number: String,
 
     body: model::OrderRequest,
+
+    /// Whether the account is a margin account, checked against [`model::OrderRequest::requires_margin`].
+    margin: Option<bool>,
 }
 
 impl PostAccountOrderRequest {
@@ -335,46 +487,103 @@ impl PostAccountOrderRequest {
 
     pub(crate) fn new(
         client: &Client,
+        config: &ApiConfig,
         access_token: String,
         account_number: String,
         body: model::OrderRequest,
     ) -> Self {
         let req = client
-            .post(Self::endpoint(account_number.clone()).url())
+            .post(Self::endpoint(account_number.clone()).url(&config.trader_url))
             .bearer_auth(access_token);
-        Self::new_with(req, account_number, body)
+        Self {
+            send_options: SendOptions {
+                non_idempotent: true,
+                ..config.send_options()
+            },
+            ..Self::new_with(req, account_number, body)
+        }
     }
 
     fn new_with(req: RequestBuilder, account_number: String, body: model::OrderRequest) -> Self {
         Self {
             req,
+            // a 5xx may come after the order was placed
+            send_options: SendOptions {
+                non_idempotent: true,
+                ..SendOptions::default()
+            },
             account_number,
             body,
+            margin: None,
         }
     }
 
+    /// Fail with [`Error::MarginAccountRequired`] instead of sending an order that sells short
+    /// when `val` is a cash account.
+    #[must_use]
+    pub fn check_margin(mut self, val: &model::Account) -> Self {
+        self.margin = Some(val.is_margin());
+        self
+    }
+
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         self.req.json(&self.body)
     }
 
-    pub async fn send(self) -> Result<(), Error> {
+    /// Place the order and return its ID, taken from the `Location` header of the response.
+    pub async fn send(self) -> Result<i64, Error> {
+        if self.margin == Some(false) && self.body.requires_margin() {
+            return Err(Error::MarginAccountRequired(format!(
+                "account {} is a cash account",
+                self.account_number
+            )));
+        }
+
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         let status = rsp.status();
         if status != StatusCode::CREATED {
-            let error_response = rsp.json::<model::ServiceError>().await?;
+            let error_response = response::json::<model::ServiceError>(rsp).await?;
             return Err(Error::Service(error_response));
         }
 
-        Ok(())
+        order_id(&rsp)
     }
 }
 
+/// The ID at the end of the `Location` header, e.g. `.../accounts/{accountNumber}/orders/{orderId}`.
+fn order_id(rsp: &reqwest::Response) -> Result<i64, Error> {
+    let location = rsp
+        .headers()
+        .get(reqwest::header::LOCATION)
+        .ok_or(Error::MissingLocationHeader(
+            "no Location header".to_string(),
+        ))?
+        .to_str()
+        .map_err(|e| Error::MissingLocationHeader(e.to_string()))?;
+
+    location
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .and_then(|x| x.parse().ok())
+        .ok_or_else(|| Error::MissingLocationHeader(location.to_string()))
+}
+
 /// Get a specific order by its ID, for a specific account
 #[derive(Debug)]
 pub struct GetAccountOrderRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 
     #[allow(dead_code)]
     /// The encrypted ID of the account
@@ -394,31 +603,44 @@ impl GetAccountOrderRequest {
     }
     pub(crate) fn new(
         client: &Client,
+        config: &ApiConfig,
         access_token: String,
         account_number: String,
         order_id: i64,
     ) -> Self {
         let req = client
-            .get(Self::endpoint(account_number.clone(), order_id).url())
+            .get(Self::endpoint(account_number.clone(), order_id).url(&config.trader_url))
             .bearer_auth(access_token);
-        Self::new_with(req, account_number, order_id)
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req, account_number, order_id)
+        }
     }
 
     fn new_with(req: RequestBuilder, account_number: String, order_id: i64) -> Self {
         Self {
             req,
+            send_options: SendOptions::default(),
             account_number,
             order_id,
         }
     }
 
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         self.req
     }
 
     pub async fn send(self) -> Result<model::Order, Error> {
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -429,13 +651,11 @@ impl GetAccountOrderRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
+            let error_response = response::json::<model::ServiceError>(rsp).await?;
             return Err(Error::Service(error_response));
         }
 
-        rsp.json::<model::Order>()
-            .await
-            .map_err(std::convert::Into::into)
+        response::json::<model::Order>(rsp).await
     }
 }
 
@@ -443,6 +663,7 @@ impl GetAccountOrderRequest {
 #[derive(Debug)]
 pub struct DeleteAccountOrderRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 
     #[allow(dead_code)]
     /// The encrypted ID of the account
@@ -463,35 +684,48 @@ impl DeleteAccountOrderRequest {
 
     pub(crate) fn new(
         client: &Client,
+        config: &ApiConfig,
         access_token: String,
         account_number: String,
         order_id: i64,
     ) -> Self {
         let req = client
-            .delete(Self::endpoint(account_number.clone(), order_id).url())
+            .delete(Self::endpoint(account_number.clone(), order_id).url(&config.trader_url))
             .bearer_auth(access_token);
-        Self::new_with(req, account_number, order_id)
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req, account_number, order_id)
+        }
     }
 
     fn new_with(req: RequestBuilder, account_number: String, order_id: i64) -> Self {
         Self {
             req,
+            send_options: SendOptions::default(),
             account_number,
             order_id,
         }
     }
 
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         self.req
     }
 
     pub async fn send(self) -> Result<(), Error> {
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
+            let error_response = response::json::<model::ServiceError>(rsp).await?;
             return Err(Error::Service(error_response));
         }
 
@@ -503,8 +737,8 @@ impl DeleteAccountOrderRequest {
 #[derive(Debug)]
 pub struct PutAccountOrderRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 
-    #[allow(dead_code)]
     /// The encrypted ID of the account
     account_number: String,
 
@@ -513,6 +747,9 @@ pub struct PutAccountOrderRequest {
     order_id: i64,
 
     body: model::OrderRequest,
+
+    /// Whether the account is a margin account, checked against [`model::OrderRequest::requires_margin`].
+    margin: Option<bool>,
 }
 
 impl PutAccountOrderRequest {
@@ -525,15 +762,22 @@ impl PutAccountOrderRequest {
 
     pub(crate) fn new(
         client: &Client,
+        config: &ApiConfig,
         access_token: String,
         account_number: String,
         order_id: i64,
         body: model::OrderRequest,
     ) -> Self {
         let req = client
-            .put(Self::endpoint(account_number.clone(), order_id).url())
+            .put(Self::endpoint(account_number.clone(), order_id).url(&config.trader_url))
             .bearer_auth(access_token);
-        Self::new_with(req, account_number, order_id, body)
+        Self {
+            send_options: SendOptions {
+                non_idempotent: true,
+                ..config.send_options()
+            },
+            ..Self::new_with(req, account_number, order_id, body)
+        }
     }
 
     fn new_with(
@@ -544,23 +788,52 @@ impl PutAccountOrderRequest {
     ) -> Self {
         Self {
             req,
+            // a 5xx may come after the order was replaced
+            send_options: SendOptions {
+                non_idempotent: true,
+                ..SendOptions::default()
+            },
             account_number,
             order_id,
             body,
+            margin: None,
         }
     }
 
+    /// Fail with [`Error::MarginAccountRequired`] instead of sending an order that sells short
+    /// when `val` is a cash account.
+    #[must_use]
+    pub fn check_margin(mut self, val: &model::Account) -> Self {
+        self.margin = Some(val.is_margin());
+        self
+    }
+
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         self.req.json(&self.body)
     }
 
     pub async fn send(self) -> Result<(), Error> {
+        if self.margin == Some(false) && self.body.requires_margin() {
+            return Err(Error::MarginAccountRequired(format!(
+                "account {} is a cash account",
+                self.account_number
+            )));
+        }
+
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         let status = rsp.status();
         if status != StatusCode::CREATED {
-            let error_response = rsp.json::<model::ServiceError>().await?;
+            let error_response = response::json::<model::ServiceError>(rsp).await?;
             return Err(Error::Service(error_response));
         }
 
@@ -572,6 +845,7 @@ impl PutAccountOrderRequest {
 #[derive(Debug)]
 pub struct GetAccountsOrdersRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 
     /// The max number of orders to retrieve.
     ///
@@ -595,7 +869,7 @@ pub struct GetAccountsOrdersRequest {
     /// Specifies that only orders of this status should be returned.
     ///
     /// Available values : `AWAITING_PARENT_ORDER`, `AWAITING_CONDITION`, `AWAITING_STOP_CONDITION`, `AWAITING_MANUAL_REVIEW`, `ACCEPTED`, `AWAITING_UR_OUT`, `PENDING_ACTIVATION`, `QUEUED`, `WORKING`, `REJECTED`, `PENDING_CANCEL`, `CANCELED`, `PENDING_REPLACE`, `REPLACED`, `FILLED`, `EXPIRED`, `NEW`, `AWAITING_RELEASE_TIME`, `PENDING_ACKNOWLEDGEMENT`, `PENDING_RECALL`, `UNKNOWN`
-    status: Option<Status>,
+    status: Option<Vec<Status>>,
 }
 
 impl GetAccountsOrdersRequest {
@@ -605,12 +879,20 @@ impl GetAccountsOrdersRequest {
 
     pub(crate) fn new(
         client: &Client,
+        config: &ApiConfig,
         access_token: String,
         from_entered_time: chrono::DateTime<chrono::Utc>,
         to_entered_time: chrono::DateTime<chrono::Utc>,
-    ) -> Self {
-        let req = client.get(Self::endpoint().url()).bearer_auth(access_token);
-        Self::new_with(req, from_entered_time, to_entered_time)
+    ) -> Result<Self, Error> {
+        check_entered_time_range(from_entered_time, to_entered_time)?;
+
+        let req = client
+            .get(Self::endpoint().url(&config.trader_url))
+            .bearer_auth(access_token);
+        Ok(Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req, from_entered_time, to_entered_time)
+        })
     }
 
     fn new_with(
@@ -620,6 +902,7 @@ impl GetAccountsOrdersRequest {
     ) -> Self {
         Self {
             req,
+            send_options: SendOptions::default(),
             max_results: None,
             from_entered_time,
             to_entered_time,
@@ -630,7 +913,8 @@ impl GetAccountsOrdersRequest {
     /// The max number of orders to retrieve.
     ///
     /// Default is `3000`.
-    pub fn max_results(&mut self, val: i64) -> &mut Self {
+    #[must_use]
+    pub fn max_results(mut self, val: i64) -> Self {
         self.max_results = Some(val);
         self
     }
@@ -638,11 +922,26 @@ impl GetAccountsOrdersRequest {
     /// Specifies that only orders of this status should be returned.
     ///
     /// Available values : `AWAITING_PARENT_ORDER`, `AWAITING_CONDITION`, `AWAITING_STOP_CONDITION`, `AWAITING_MANUAL_REVIEW`, `ACCEPTED`, `AWAITING_UR_OUT`, `PENDING_ACTIVATION`, `QUEUED`, `WORKING`, `REJECTED`, `PENDING_CANCEL`, `CANCELED`, `PENDING_REPLACE`, `REPLACED`, `FILLED`, `EXPIRED`, `NEW`, `AWAITING_RELEASE_TIME`, `PENDING_ACKNOWLEDGEMENT`, `PENDING_RECALL`, `UNKNOWN`
-    pub fn status(&mut self, val: Status) -> &mut Self {
+    #[must_use]
+    pub fn status(mut self, val: Status) -> Self {
+        self.status = Some(vec![val]);
+        self
+    }
+
+    /// Specifies that only orders of any of these statuses should be returned, e.g. `WORKING`, `PENDING_ACTIVATION` and `QUEUED`.
+    #[must_use]
+    pub fn statuses(mut self, val: Vec<Status>) -> Self {
         self.status = Some(val);
         self
     }
 
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req.query(&[
             (
@@ -658,25 +957,37 @@ impl GetAccountsOrdersRequest {
             req = req.query(&[("maxResults", x)]);
         }
         if let Some(x) = self.status {
-            req = req.query(&[("status", x)]);
+            let x: Vec<String> = x
+                .into_iter()
+                .map(|s| serde_json::to_value(s).expect("value"))
+                .map(|v| v.as_str().expect("value is a str").to_string())
+                .collect();
+            req = req.query(&[("status", x.join(","))]);
         }
 
         req
     }
 
+    /// Schwab occasionally returns the same order twice when filtering by several statuses,
+    /// so with [`Self::statuses`] only the most recently entered entry of each `order_id` is kept.
     pub async fn send(self) -> Result<Vec<model::Order>, Error> {
+        let multi_status = self.status.as_ref().is_some_and(|x| x.len() > 1);
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
+            let error_response = response::json::<model::ServiceError>(rsp).await?;
             return Err(Error::Service(error_response));
         }
 
-        rsp.json::<Vec<model::Order>>()
-            .await
-            .map_err(std::convert::Into::into)
+        let orders = response::json::<Vec<model::Order>>(rsp).await?;
+        if multi_status {
+            return Ok(dedup_orders(orders));
+        }
+
+        Ok(orders)
     }
 }
 
@@ -684,6 +995,7 @@ impl GetAccountsOrdersRequest {
 #[derive(Debug)]
 pub struct PostAccountPreviewOrderRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 
     #[allow(dead_code)]
     /// The encrypted ID of the account
@@ -699,41 +1011,52 @@ impl PostAccountPreviewOrderRequest {
 
     pub(crate) fn new(
         client: &Client,
+        config: &ApiConfig,
         access_token: String,
         account_number: String,
         body: model::PreviewOrder,
     ) -> Self {
         let req = client
-            .post(Self::endpoint(account_number.clone()).url())
+            .post(Self::endpoint(account_number.clone()).url(&config.trader_url))
             .bearer_auth(access_token);
-        Self::new_with(req, account_number, body)
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req, account_number, body)
+        }
     }
 
     fn new_with(req: RequestBuilder, account_number: String, body: model::PreviewOrder) -> Self {
         Self {
             req,
+            send_options: SendOptions::default(),
             account_number,
             body,
         }
     }
 
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         self.req.json(&self.body)
     }
 
     pub async fn send(self) -> Result<model::PreviewOrder, Error> {
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
+            let error_response = response::json::<model::ServiceError>(rsp).await?;
             return Err(Error::Service(error_response));
         }
 
-        rsp.json::<model::PreviewOrder>()
-            .await
-            .map_err(std::convert::Into::into)
+        response::json::<model::PreviewOrder>(rsp).await
     }
 }
 
@@ -741,6 +1064,7 @@ impl PostAccountPreviewOrderRequest {
 #[derive(Debug)]
 pub struct GetAccountTransactions {
     req: RequestBuilder,
+    send_options: SendOptions,
 
     #[allow(dead_code)]
     /// The encrypted ID of the account
@@ -767,7 +1091,7 @@ pub struct GetAccountTransactions {
     /// Specifies that only transactions of this status should be returned.
     ///
     /// Available values : `TRADE`, `RECEIVE_AND_DELIVER`, `DIVIDEND_OR_INTEREST`, `ACH_RECEIPT`, `ACH_DISBURSEMENT`, `CASH_RECEIPT`, `CASH_DISBURSEMENT`, `ELECTRONIC_FUND`, `WIRE_OUT`, `WIRE_IN`, `JOURNAL`, `MEMORANDUM`, `MARGIN_CALL`, `MONEY_MARKET`, `SMA_ADJUSTMENT`
-    types: TransactionType,
+    types: Vec<TransactionType>,
 }
 
 impl GetAccountTransactions {
@@ -777,16 +1101,20 @@ impl GetAccountTransactions {
 
     pub(crate) fn new(
         client: &Client,
+        config: &ApiConfig,
         access_token: String,
         account_number: String,
         start_date: chrono::DateTime<chrono::Utc>,
         end_date: chrono::DateTime<chrono::Utc>,
-        types: TransactionType,
+        types: Vec<TransactionType>,
     ) -> Self {
         let req = client
-            .get(Self::endpoint(account_number.clone()).url())
+            .get(Self::endpoint(account_number.clone()).url(&config.trader_url))
             .bearer_auth(access_token);
-        Self::new_with(req, account_number, start_date, end_date, types)
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req, account_number, start_date, end_date, types)
+        }
     }
 
     fn new_with(
@@ -794,10 +1122,11 @@ impl GetAccountTransactions {
         account_number: String,
         start_date: chrono::DateTime<chrono::Utc>,
         end_date: chrono::DateTime<chrono::Utc>,
-        types: TransactionType,
+        types: Vec<TransactionType>,
     ) -> Self {
         Self {
             req,
+            send_options: SendOptions::default(),
             account_number,
             start_date,
             end_date,
@@ -807,17 +1136,38 @@ impl GetAccountTransactions {
     }
 
     /// It filters all the transaction activities based on the symbol specified.
-    pub fn symbol(&mut self, val: String) -> &mut Self {
+    #[must_use]
+    pub fn symbol(mut self, val: String) -> Self {
         self.symbol = Some(val);
         self
     }
 
+    /// Specifies that only transactions of these types should be returned, replacing those given at creation.
+    #[must_use]
+    pub fn types(mut self, val: Vec<TransactionType>) -> Self {
+        self.types = val;
+        self
+    }
+
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         let mut req = self.req.query(&[
             ("startDate", self.start_date.format("%+").to_string()),
             ("endDate", self.end_date.format("%+").to_string()),
         ]);
-        req = req.query(&[("types", self.types)]);
+        let types: Vec<String> = self
+            .types
+            .into_iter()
+            .map(|t| serde_json::to_value(t).expect("value"))
+            .map(|v| v.as_str().expect("value is a str").to_string())
+            .collect();
+        req = req.query(&[("types", types.join(","))]);
         if let Some(x) = self.symbol {
             req = req.query(&[("symbol", x)]);
         }
@@ -826,8 +1176,9 @@ impl GetAccountTransactions {
     }
 
     pub async fn send(self) -> Result<Vec<model::Transaction>, Error> {
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -837,11 +1188,11 @@ impl GetAccountTransactions {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
+            let error_response = response::json::<model::ServiceError>(rsp).await?;
             return Err(Error::Service(error_response));
         }
 
-        rsp.json().await.map_err(std::convert::Into::into)
+        response::json(rsp).await
     }
 }
 
@@ -849,6 +1200,7 @@ impl GetAccountTransactions {
 #[derive(Debug)]
 pub struct GetAccountTransaction {
     req: RequestBuilder,
+    send_options: SendOptions,
 
     #[allow(dead_code)]
     /// The encrypted ID of the account
@@ -869,24 +1221,36 @@ impl GetAccountTransaction {
 
     pub(crate) fn new(
         client: &Client,
+        config: &ApiConfig,
         access_token: String,
         account_number: String,
         transaction_id: i64,
     ) -> Self {
         let req = client
-            .get(Self::endpoint(account_number.clone(), transaction_id).url())
+            .get(Self::endpoint(account_number.clone(), transaction_id).url(&config.trader_url))
             .bearer_auth(access_token);
-        Self::new_with(req, account_number, transaction_id)
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req, account_number, transaction_id)
+        }
     }
 
     fn new_with(req: RequestBuilder, account_number: String, transaction_id: i64) -> Self {
         Self {
             req,
+            send_options: SendOptions::default(),
             account_number,
             transaction_id,
         }
     }
 
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
+    }
+
     fn build(self) -> RequestBuilder {
         self.req
     }
@@ -895,8 +1259,9 @@ impl GetAccountTransaction {
     ///
     /// Will panic if no transaction found
     pub async fn send(self) -> Result<model::Transaction, Error> {
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -907,11 +1272,11 @@ impl GetAccountTransaction {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
+            let error_response = response::json::<model::ServiceError>(rsp).await?;
             return Err(Error::Service(error_response));
         }
 
-        rsp.json().await.map_err(std::convert::Into::into)
+        response::json(rsp).await
     }
 }
 
@@ -919,19 +1284,35 @@ impl GetAccountTransaction {
 #[derive(Debug)]
 pub struct GetUserPreferenceRequest {
     req: RequestBuilder,
+    send_options: SendOptions,
 }
 
 impl GetUserPreferenceRequest {
     fn endpoint() -> endpoints::EndpointUserPreference {
         endpoints::EndpointUserPreference::UserPreference
     }
-    pub(crate) fn new(client: &Client, access_token: String) -> Self {
-        let req = client.get(Self::endpoint().url()).bearer_auth(access_token);
-        Self::new_with(req)
+    pub(crate) fn new(client: &Client, config: &ApiConfig, access_token: String) -> Self {
+        let req = client
+            .get(Self::endpoint().url(&config.trader_url))
+            .bearer_auth(access_token);
+        Self {
+            send_options: config.send_options(),
+            ..Self::new_with(req)
+        }
     }
 
     fn new_with(req: RequestBuilder) -> Self {
-        Self { req }
+        Self {
+            req,
+            send_options: SendOptions::default(),
+        }
+    }
+
+    /// See [`ApiConfig::default_timeout`], overridden for this request.
+    #[must_use]
+    pub fn timeout(mut self, val: std::time::Duration) -> Self {
+        self.send_options.timeout = Some(val);
+        self
     }
 
     fn build(self) -> RequestBuilder {
@@ -939,8 +1320,9 @@ impl GetUserPreferenceRequest {
     }
 
     pub async fn send(self) -> Result<model::UserPreferences, Error> {
+        let send_options = self.send_options.clone();
         let req = self.build();
-        let rsp = req.send().await?;
+        let rsp = retry::send(req, &send_options).await?;
 
         // let json = rsp.text().await.unwrap();
         // dbg!(&json);
@@ -951,20 +1333,50 @@ impl GetUserPreferenceRequest {
 
         let status = rsp.status();
         if status != StatusCode::OK {
-            let error_response = rsp.json::<model::ServiceError>().await?;
+            let error_response = response::json::<model::ServiceError>(rsp).await?;
             return Err(Error::Service(error_response));
         }
 
-        rsp.json::<model::UserPreferences>()
-            .await
-            .map_err(std::convert::Into::into)
+        response::json::<model::UserPreferences>(rsp).await
+    }
+}
+
+/// Whether a leg of `order`, or of one of its child orders, trades `symbol`.
+fn has_symbol(order: &model::Order, symbol: &str) -> bool {
+    order
+        .order_leg_collection
+        .iter()
+        .any(|leg| leg.instrument.symbol() == symbol)
+        || order
+            .child_order_strategies
+            .iter()
+            .flatten()
+            .any(|x| has_symbol(x, symbol))
+}
+
+/// Keep the most recently entered order of each `order_id`, in the order of first appearance.
+fn dedup_orders(orders: Vec<model::Order>) -> Vec<model::Order> {
+    let mut index: std::collections::HashMap<i64, usize> = std::collections::HashMap::new();
+    let mut deduped: Vec<model::Order> = Vec::with_capacity(orders.len());
+    for order in orders {
+        if let Some(&i) = index.get(&order.order_id) {
+            if order.entered_time > deduped[i].entered_time {
+                deduped[i] = order;
+            }
+        } else {
+            index.insert(order.order_id, deduped.len());
+            deduped.push(order);
+        }
     }
+
+    deduped
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use crate::api::mock_api;
     use crate::model::trader::accounts::SecuritiesAccount;
 
     use mockito::Matcher;
@@ -975,17 +1387,17 @@ mod tests {
     async fn test_get_account_numbers_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         // none
 
         // Create a mock
         let mock = server
-            .mock("GET", "/accounts/accountNumbers")
+            .mock("GET", "/trader/v1/accounts/accountNumbers")
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body_from_file(concat!(
@@ -995,19 +1407,17 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetAccountNumbersRequest::endpoint().url_endpoint()
-        ));
-
-        let req = GetAccountNumbersRequest::new_with(req);
+        let mut req = api.get_account_numbers().await.unwrap();
 
         // check initial value
-        // none
+        assert_eq!(req.send_options.timeout, None);
 
         // check setter
-        // none
+        req = req.timeout(std::time::Duration::from_secs(5));
+        assert_eq!(
+            req.send_options.timeout,
+            Some(std::time::Duration::from_secs(5))
+        );
 
         dbg!(&req);
         let result = req.send().await;
@@ -1020,20 +1430,20 @@ mod tests {
     async fn test_get_accounts_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let fields = "positions".to_string();
 
         // Create a mock
         let mock = server
-            .mock("GET", "/accounts")
+            .mock("GET", "/trader/v1/accounts")
             .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
                 "fields".into(),
-                fields.to_string(),
+                fields.clone(),
             )]))
             .with_status(200)
             .with_header("content-type", "application/json")
@@ -1044,19 +1454,13 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetAccountsRequest::endpoint().url_endpoint()
-        ));
-
-        let mut req = GetAccountsRequest::new_with(req);
+        let mut req = api.get_accounts().await.unwrap();
 
         // check initial value
         assert_eq!(req.fields, None);
 
         // check setter
-        req.fields(fields.clone());
+        req = req.fields(fields.clone());
         assert_eq!(req.fields, Some(fields));
 
         dbg!(&req);
@@ -1073,10 +1477,10 @@ mod tests {
     async fn test_get_account_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let account_number = "account_number".to_string();
@@ -1084,10 +1488,10 @@ mod tests {
 
         // Create a mock
         let mock = server
-            .mock("GET", "/accounts/account_number")
+            .mock("GET", "/trader/v1/accounts/account_number")
             .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
                 "fields".into(),
-                fields.to_string(),
+                fields.clone(),
             )]))
             .with_status(200)
             .with_header("content-type", "application/json")
@@ -1098,20 +1502,17 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetAccountRequest::endpoint(account_number.clone()).url_endpoint()
-        ));
-
-        let mut req = GetAccountRequest::new_with(req, account_number.clone());
+        let mut req = api
+            .get_account(account_number.clone().into())
+            .await
+            .unwrap();
 
         // check initial value
         assert_eq!(req.account_number, account_number);
         assert_eq!(req.fields, None);
 
         // check setter
-        req.fields(fields.clone());
+        req = req.fields(fields.clone());
         assert_eq!(req.fields, Some(fields));
 
         dbg!(&req);
@@ -1124,14 +1525,56 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn test_get_account_request_balances() {
+        // Request a new server from the pool
+        let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
+
+        // Use one of these addresses to configure your client
+        let _host = server.host_with_port();
+
+        // define parameter
+        let account_number = "account_number".to_string();
+
+        // Create a mock
+        let mock = server
+            .mock("GET", "/trader/v1/accounts/account_number")
+            .match_query(Matcher::Missing)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/Trader/Account_real.json"
+            ))
+            .create_async()
+            .await;
+
+        let req = api
+            .get_account(account_number.into())
+            .await
+            .unwrap()
+            .fields("positions".to_string());
+        let result = req.send_balances().await;
+        mock.assert_async().await;
+        let result = result.unwrap();
+        assert!(matches!(
+            result,
+            model::Balances::Cash {
+                current_balances: Some(_),
+                ..
+            }
+        ));
+    }
+
     #[tokio::test]
     async fn test_get_account_orders_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let account_number = "account_number".to_string();
@@ -1152,7 +1595,7 @@ mod tests {
 
         // Create a mock
         let mock = server
-            .mock("GET", "/accounts/account_number/orders")
+            .mock("GET", "/trader/v1/accounts/account_number/orders")
             .match_query(Matcher::AllOf(vec![
                 Matcher::UrlEncoded("maxResults".into(), max_results.to_string()),
                 Matcher::UrlEncoded(
@@ -1174,18 +1617,14 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetAccountOrdersRequest::endpoint(account_number.clone()).url_endpoint()
-        ));
-
-        let mut req = GetAccountOrdersRequest::new_with(
-            req,
-            account_number.clone(),
-            from_entered_time,
-            to_entered_time,
-        );
+        let mut req = api
+            .get_account_orders(
+                account_number.clone().into(),
+                from_entered_time,
+                to_entered_time,
+            )
+            .await
+            .unwrap();
 
         // check initial value
         assert_eq!(req.account_number, account_number);
@@ -1193,12 +1632,13 @@ mod tests {
         assert_eq!(req.from_entered_time, from_entered_time);
         assert_eq!(req.to_entered_time, to_entered_time);
         assert_eq!(req.status, None);
+        assert_eq!(req.symbol, None);
 
         // check setter
-        req.max_results(max_results);
+        req = req.max_results(max_results);
         assert_eq!(req.max_results, Some(max_results));
-        req.status(status);
-        assert_eq!(req.status, Some(status));
+        req = req.status(status);
+        assert_eq!(req.status, Some(vec![status]));
 
         dbg!(&req);
         let result = req.send().await;
@@ -1208,82 +1648,313 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_post_account_order_request() {
-        // Request a new server from the pool
+    async fn test_get_account_orders_request_symbol() {
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
-        // Use one of these addresses to configure your client
-        let _host = server.host_with_port();
-        let url = server.url();
-
-        // define parameter
         let account_number = "account_number".to_string();
-        let body = model::OrderRequest::default();
+        let entered_time = chrono::DateTime::from_timestamp(1_420_070_400, 0).unwrap();
 
-        // Create a mock
+        // the symbol is not sent
         let mock = server
-            .mock("POST", "/accounts/account_number/orders")
-            .with_status(201)
+            .mock("GET", "/trader/v1/accounts/account_number/orders")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded(
+                    "fromEnteredTime".into(),
+                    entered_time.format("%+").to_string(),
+                ),
+                Matcher::UrlEncoded(
+                    "toEnteredTime".into(),
+                    entered_time.format("%+").to_string(),
+                ),
+            ]))
+            .with_status(200)
             .with_header("content-type", "application/json")
-            .match_body(mockito::Matcher::Json(
-                serde_json::to_value(body.clone()).unwrap(),
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/Trader/Orders_real.json"
             ))
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.post(format!(
-            "{url}{}",
-            PostAccountOrderRequest::endpoint(account_number.clone()).url_endpoint()
-        ));
-
-        let req = PostAccountOrderRequest::new_with(req, account_number.clone(), body.clone());
-
-        // check initial value
-        assert_eq!(req.account_number, account_number);
-        assert_eq!(req.body, body);
+        let mut req = api
+            .get_account_orders(account_number.into(), entered_time, entered_time)
+            .await
+            .unwrap();
 
         // check setter
-        // none
+        req = req.symbol("BNDX".to_string());
+        assert_eq!(req.symbol, Some("BNDX".to_string()));
 
-        dbg!(&req);
         let result = req.send().await;
         mock.assert_async().await;
-        assert!(result.is_ok());
+        let result = result.unwrap();
+        assert_eq!(result.len(), 2);
+        assert!(result
+            .iter()
+            .all(|x| x.order_leg_collection[0].instrument.symbol() == "BNDX"));
     }
 
     #[tokio::test]
-    async fn test_get_account_order_request() {
+    async fn test_get_orders_request_statuses() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let account_number = "account_number".to_string();
-        let order_id = 123;
-
+        let from_entered_time = chrono::NaiveDate::from_ymd_opt(2015, 1, 1)
+            .unwrap()
+            .and_hms_milli_opt(0, 0, 1, 444)
+            .unwrap()
+            .and_local_timezone(chrono::Utc)
+            .unwrap();
+        let to_entered_time = from_entered_time;
+        let statuses = vec![Status::Working, Status::PendingActivation, Status::Queued];
+
         // Create a mock
-        let mock = server
-            .mock("GET", "/accounts/account_number/orders/123")
+        let query =
+            Matcher::UrlEncoded("status".into(), "WORKING,PENDING_ACTIVATION,QUEUED".into());
+        let mock_account = server
+            .mock("GET", "/trader/v1/accounts/account_number/orders")
+            .match_query(query.clone())
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body_from_file(concat!(
                 env!("CARGO_MANIFEST_DIR"),
-                "/tests/model/Trader/Order_real.json"
+                "/tests/model/Trader/Orders_real.json"
+            ))
+            .create_async()
+            .await;
+        let mock_accounts = server
+            .mock("GET", "/trader/v1/orders")
+            .match_query(query)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/Trader/Orders_real.json"
             ))
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetAccountOrderRequest::endpoint(account_number.clone(), order_id).url_endpoint()
+        let req = api
+            .get_account_orders(account_number.into(), from_entered_time, to_entered_time)
+            .await
+            .unwrap()
+            .statuses(statuses.clone());
+        assert_eq!(req.status, Some(statuses.clone()));
+        let result = req.send().await;
+        mock_account.assert_async().await;
+        // the 15 orders of the file share 3 ids
+        let result = result.unwrap();
+        assert_eq!(result.len(), 3);
+        assert_eq!(
+            result[0].entered_time.to_rfc3339(),
+            "2024-06-06T13:46:03+00:00"
+        );
+
+        let req = api
+            .get_accounts_orders(from_entered_time, to_entered_time)
+            .await
+            .unwrap()
+            .statuses(statuses.clone());
+        assert_eq!(req.status, Some(statuses));
+        let result = req.send().await;
+        mock_accounts.assert_async().await;
+        assert_eq!(result.unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_dedup_orders() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Orders_real.json"
         ));
+        let mut orders: Vec<model::Order> = serde_json::from_str(json).unwrap();
+        // the ids are anonymized in the file
+        for (i, order) in (0..).zip(orders.iter_mut()) {
+            order.order_id = i;
+        }
+        let len = orders.len();
+
+        let mut newer = orders[0].clone();
+        newer.entered_time += chrono::TimeDelta::seconds(1);
+        let mut older = orders[1].clone();
+        older.entered_time -= chrono::TimeDelta::seconds(1);
+
+        let mut duplicated = orders.clone();
+        duplicated.push(newer.clone());
+        duplicated.insert(0, older);
+
+        let deduped = dedup_orders(duplicated);
+        assert_eq!(deduped.len(), len);
+        assert_eq!(deduped[0].order_id, orders[1].order_id);
+        assert_eq!(deduped[0].entered_time, orders[1].entered_time);
+        assert_eq!(deduped[1], newer);
+    }
+
+    #[tokio::test]
+    async fn test_post_account_order_request() {
+        // Request a new server from the pool
+        let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
+
+        // Use one of these addresses to configure your client
+        let _host = server.host_with_port();
+
+        // define parameter
+        let account_number = "account_number".to_string();
+        let body = model::OrderRequest::default();
+
+        // Create a mock
+        let mock = server
+            .mock("POST", "/trader/v1/accounts/account_number/orders")
+            .with_status(201)
+            .with_header("content-type", "application/json")
+            .with_header(
+                "location",
+                "https://api.schwabapi.com/trader/v1/accounts/x/orders/123456",
+            )
+            .match_body(mockito::Matcher::Json(
+                serde_json::to_value(body.clone()).unwrap(),
+            ))
+            .create_async()
+            .await;
+
+        let req = api
+            .post_account_order(account_number.clone().into(), body.clone())
+            .await
+            .unwrap();
+
+        // check initial value
+        assert_eq!(req.account_number, account_number);
+        assert_eq!(req.body, body);
+
+        // check setter
+        // none
+
+        dbg!(&req);
+        let result = req.send().await;
+        mock.assert_async().await;
+        assert_eq!(result.unwrap(), 123_456);
+
+        // created without a usable Location
+        let mock = server
+            .mock("POST", "/trader/v1/accounts/account_number/orders")
+            .with_status(201)
+            .create_async()
+            .await;
+        let req = api
+            .post_account_order(account_number.clone().into(), body.clone())
+            .await
+            .unwrap();
+        let result = req.send().await;
+        mock.assert_async().await;
+        assert!(matches!(result, Err(Error::MissingLocationHeader(_))));
 
-        let req = GetAccountOrderRequest::new_with(req, account_number.clone(), order_id);
+        // the Location does not end with an ID
+        let mock = server
+            .mock("POST", "/trader/v1/accounts/account_number/orders")
+            .with_status(201)
+            .with_header(
+                "location",
+                "https://api.schwabapi.com/trader/v1/accounts/x/orders",
+            )
+            .create_async()
+            .await;
+        let req = api
+            .post_account_order(account_number.into(), body)
+            .await
+            .unwrap();
+        let result = req.send().await;
+        mock.assert_async().await;
+        assert!(matches!(result, Err(Error::MissingLocationHeader(_))));
+    }
+
+    #[tokio::test]
+    async fn test_post_account_order_request_check_margin() {
+        // Request a new server from the pool
+        let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
+
+        // Use one of these addresses to configure your client
+        let _host = server.host_with_port();
+
+        // define parameter
+        let account_number = "account_number".to_string();
+        let body = model::OrderRequest::market(
+            model::InstrumentRequest::Equity {
+                symbol: "XYZ".to_string(),
+            },
+            model::Instruction::SellShort,
+            10.0,
+        )
+        .unwrap();
+        let account: model::Account = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Account_real.json"
+        )))
+        .unwrap();
+        assert!(!account.is_margin());
+
+        // Create a mock
+        let mock = server
+            .mock("POST", "/trader/v1/accounts/account_number/orders")
+            .with_status(201)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let mut req = api
+            .post_account_order(account_number.into(), body)
+            .await
+            .unwrap();
+
+        // check initial value
+        assert_eq!(req.margin, None);
+
+        // check setter
+        req = req.check_margin(&account);
+        assert_eq!(req.margin, Some(false));
+
+        let result = req.send().await;
+        mock.assert_async().await;
+        assert!(matches!(result, Err(Error::MarginAccountRequired(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_account_order_request() {
+        // Request a new server from the pool
+        let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
+
+        // Use one of these addresses to configure your client
+        let _host = server.host_with_port();
+
+        // define parameter
+        let account_number = "account_number".to_string();
+        let order_id = 123;
+
+        // Create a mock
+        let mock = server
+            .mock("GET", "/trader/v1/accounts/account_number/orders/123")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/Trader/Order_real.json"
+            ))
+            .create_async()
+            .await;
+
+        let req = api
+            .get_account_order(account_number.clone().into(), order_id)
+            .await
+            .unwrap();
 
         // check initial value
         assert_eq!(req.account_number, account_number);
@@ -1299,14 +1970,65 @@ mod tests {
         assert_eq!(result.session, model::trader::order::Session::Normal);
     }
 
+    #[tokio::test]
+    async fn test_get_account_order_request_diff() {
+        let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
+
+        // define parameter
+        let account_number = "account_number".to_string();
+        let order_id = 1_234_567_890_123;
+        let submitted = model::OrderRequest::limit(
+            model::InstrumentRequest::Equity {
+                symbol: "VTI".to_string(),
+            },
+            model::Instruction::Buy,
+            1.0,
+            29.999,
+        )
+        .unwrap();
+
+        // Schwab rounds the price to 30.0, and records VTI as COLLECTIVE_INVESTMENT
+        let mock = server
+            .mock(
+                "GET",
+                "/trader/v1/accounts/account_number/orders/1234567890123",
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/Trader/Order_real.json"
+            ))
+            .create_async()
+            .await;
+
+        let req = api
+            .get_account_order(account_number.into(), order_id)
+            .await
+            .unwrap();
+
+        let result = req.send().await;
+        mock.assert_async().await;
+        let changes = submitted.diff(&result.unwrap().into());
+        assert_eq!(
+            changes,
+            vec![model::FieldChange {
+                field: "price".to_string(),
+                submitted: serde_json::json!(29.999),
+                recorded: serde_json::json!(30.0),
+            }]
+        );
+    }
+
     #[tokio::test]
     async fn test_delete_account_order_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let account_number = "account_number".to_string();
@@ -1314,19 +2036,16 @@ mod tests {
 
         // Create a mock
         let mock = server
-            .mock("DELETE", "/accounts/account_number/orders/123")
+            .mock("DELETE", "/trader/v1/accounts/account_number/orders/123")
             .with_status(200)
             .with_header("content-type", "application/json")
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.delete(format!(
-            "{url}{}",
-            DeleteAccountOrderRequest::endpoint(account_number.clone(), order_id).url_endpoint()
-        ));
-
-        let req = DeleteAccountOrderRequest::new_with(req, account_number.clone(), order_id);
+        let req = api
+            .delete_account_order(account_number.clone().into(), order_id)
+            .await
+            .unwrap();
 
         // check initial value
         assert_eq!(req.account_number, account_number);
@@ -1341,14 +2060,67 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_order_requests_not_retried() {
+        let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await.with_retry(retry::RetryConfig {
+            initial_backoff: std::time::Duration::from_millis(1),
+            max_backoff: std::time::Duration::from_millis(1),
+            ..retry::RetryConfig::default()
+        });
+
+        // define parameter
+        let account_number = "account_number".to_string();
+        let body = model::OrderRequest::default();
+
+        // the order may have been placed before the 503
+        let mock = server
+            .mock("POST", "/trader/v1/accounts/account_number/orders")
+            .with_status(503)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/Trader/ServiceError.json"
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+        let req = api
+            .post_account_order(account_number.clone().into(), body.clone())
+            .await
+            .unwrap();
+        let result = req.send().await;
+        mock.assert_async().await;
+        assert!(matches!(result, Err(Error::Service(_))));
+
+        let mock = server
+            .mock("PUT", "/trader/v1/accounts/account_number/orders/1234")
+            .with_status(503)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/Trader/ServiceError.json"
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+        let req = api
+            .put_account_order(account_number.into(), 1234, body)
+            .await
+            .unwrap();
+        let result = req.send().await;
+        mock.assert_async().await;
+        assert!(matches!(result, Err(Error::Service(_))));
+    }
+
     #[tokio::test]
     async fn test_put_account_order_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let account_number = "account_number".to_string();
@@ -1357,21 +2129,17 @@ mod tests {
 
         // Create a mock
         let mock = server
-            .mock("PUT", "/accounts/account_number/orders/123")
+            .mock("PUT", "/trader/v1/accounts/account_number/orders/123")
             .with_status(201)
             .with_header("content-type", "application/json")
             .match_body(Matcher::Json(serde_json::to_value(body.clone()).unwrap()))
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.put(format!(
-            "{url}{}",
-            PutAccountOrderRequest::endpoint(account_number.clone(), order_id).url_endpoint()
-        ));
-
-        let req =
-            PutAccountOrderRequest::new_with(req, account_number.clone(), order_id, body.clone());
+        let req = api
+            .put_account_order(account_number.clone().into(), order_id, body.clone())
+            .await
+            .unwrap();
 
         // check initial value
         assert_eq!(req.account_number, account_number);
@@ -1387,14 +2155,212 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    #[allow(clippy::too_many_lines)]
+    async fn test_order_lifecycle() {
+        // place -> list -> replace -> wait for fill -> cancel, like `test_post_put_delete_account_order` but offline
+        let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
+        let url = server.url();
+
+        // define parameter
+        let account_number = "account_number".to_string();
+        let placed_order_id = 1_234_567_890_123;
+        let body = model::OrderRequest::default();
+        let from_entered_time = chrono::Utc::now() - chrono::Duration::days(1);
+        let to_entered_time = chrono::Utc::now();
+
+        // Create mocks
+        let mock_post = server
+            .mock("POST", "/trader/v1/accounts/account_number/orders")
+            .match_body(Matcher::Json(serde_json::to_value(body.clone()).unwrap()))
+            .with_status(201)
+            .with_header(
+                "location",
+                &format!("{url}/accounts/account_number/orders/{placed_order_id}"),
+            )
+            .create_async()
+            .await;
+        let mock_get_orders = server
+            .mock("GET", "/trader/v1/accounts/account_number/orders")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded(
+                    "fromEnteredTime".into(),
+                    from_entered_time.format("%+").to_string(),
+                ),
+                Matcher::UrlEncoded(
+                    "toEnteredTime".into(),
+                    to_entered_time.format("%+").to_string(),
+                ),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/Trader/Orders_real.json"
+            ))
+            .create_async()
+            .await;
+        let mock_put = server
+            .mock(
+                "PUT",
+                "/trader/v1/accounts/account_number/orders/1234567890123",
+            )
+            .match_body(Matcher::Json(serde_json::to_value(body.clone()).unwrap()))
+            .with_status(201)
+            .create_async()
+            .await;
+        let mut order: serde_json::Value = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Order_real.json"
+        )))
+        .unwrap();
+        order["status"] = "WORKING".into();
+        let mock_get_order_working = server
+            .mock(
+                "GET",
+                "/trader/v1/accounts/account_number/orders/1234567890123",
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(order.to_string())
+            .expect(1)
+            .create_async()
+            .await;
+        order["status"] = "FILLED".into();
+        let mock_get_order_filled = server
+            .mock(
+                "GET",
+                "/trader/v1/accounts/account_number/orders/1234567890123",
+            )
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(order.to_string())
+            .expect(1)
+            .create_async()
+            .await;
+        let mock_delete = server
+            .mock(
+                "DELETE",
+                "/trader/v1/accounts/account_number/orders/1234567890123",
+            )
+            .with_status(200)
+            .create_async()
+            .await;
+
+        // place
+        let req = api
+            .post_account_order(account_number.clone().into(), body.clone())
+            .await
+            .unwrap();
+        let order_id = req.send().await.unwrap();
+        mock_post.assert_async().await;
+        assert_eq!(order_id, placed_order_id);
+
+        // list
+        let req = api
+            .get_account_orders(
+                account_number.clone().into(),
+                from_entered_time,
+                to_entered_time,
+            )
+            .await
+            .unwrap();
+        let orders = req.send().await.unwrap();
+        mock_get_orders.assert_async().await;
+        assert!(orders.iter().any(|x| x.order_id == order_id));
+
+        // replace
+        let req = api
+            .put_account_order(account_number.clone().into(), order_id, body.clone())
+            .await
+            .unwrap();
+        req.send().await.unwrap();
+        mock_put.assert_async().await;
+
+        // wait for fill
+        let order = api
+            .wait_for_order_fill(
+                account_number.clone().into(),
+                order_id,
+                std::time::Duration::from_secs(5),
+                std::time::Duration::from_millis(10),
+            )
+            .await
+            .unwrap();
+        mock_get_order_working.assert_async().await;
+        mock_get_order_filled.assert_async().await;
+        assert_eq!(order.order_id, order_id);
+        assert_eq!(order.status, model::trader::order::Status::Filled);
+
+        // cancel
+        let req = api
+            .delete_account_order(account_number.clone().into(), order_id)
+            .await
+            .unwrap();
+        req.send().await.unwrap();
+        mock_delete.assert_async().await;
+    }
+
+    #[test]
+    fn test_orders_request_time_range() {
+        let client = Client::new();
+        let config = ApiConfig::default();
+        let from_entered_time = chrono::DateTime::from_timestamp(1_714_521_600, 0).unwrap();
+
+        // exactly 60 days
+        let to_entered_time = from_entered_time + chrono::TimeDelta::days(60);
+        assert!(GetAccountOrdersRequest::new(
+            &client,
+            &config,
+            "access".to_string(),
+            "account_number".to_string(),
+            from_entered_time,
+            to_entered_time,
+        )
+        .is_ok());
+        assert!(GetAccountsOrdersRequest::new(
+            &client,
+            &config,
+            "access".to_string(),
+            from_entered_time,
+            to_entered_time,
+        )
+        .is_ok());
+
+        // 60 days and 1 second
+        let to_entered_time = to_entered_time + chrono::TimeDelta::seconds(1);
+        assert!(matches!(
+            GetAccountOrdersRequest::new(
+                &client,
+                &config,
+                "access".to_string(),
+                "account_number".to_string(),
+                from_entered_time,
+                to_entered_time,
+            ),
+            Err(Error::InvalidTimeRange { days: 61, max: 60 })
+        ));
+        assert!(matches!(
+            GetAccountsOrdersRequest::new(
+                &client,
+                &config,
+                "access".to_string(),
+                from_entered_time,
+                to_entered_time,
+            ),
+            Err(Error::InvalidTimeRange { days: 61, max: 60 })
+        ));
+    }
+
     #[tokio::test]
     async fn test_get_accounts_orders_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let max_results = 10;
@@ -1414,7 +2380,7 @@ mod tests {
 
         // Create a mock
         let mock = server
-            .mock("GET", "/orders")
+            .mock("GET", "/trader/v1/orders")
             .match_query(Matcher::AllOf(vec![
                 Matcher::UrlEncoded("maxResults".into(), max_results.to_string()),
                 Matcher::UrlEncoded(
@@ -1436,13 +2402,10 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetAccountsOrdersRequest::endpoint().url_endpoint()
-        ));
-
-        let mut req = GetAccountsOrdersRequest::new_with(req, from_entered_time, to_entered_time);
+        let mut req = api
+            .get_accounts_orders(from_entered_time, to_entered_time)
+            .await
+            .unwrap();
 
         // check initial value
         assert_eq!(req.max_results, None);
@@ -1451,10 +2414,10 @@ mod tests {
         assert_eq!(req.status, None);
 
         // check setter
-        req.max_results(max_results);
+        req = req.max_results(max_results);
         assert_eq!(req.max_results, Some(max_results));
-        req.status(status);
-        assert_eq!(req.status, Some(status));
+        req = req.status(status);
+        assert_eq!(req.status, Some(vec![status]));
 
         dbg!(&req);
         let result = req.send().await;
@@ -1467,10 +2430,10 @@ mod tests {
     async fn test_post_account_preview_order_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let account_number = "account_number".to_string();
@@ -1478,7 +2441,7 @@ mod tests {
 
         // Create a mock
         let mock = server
-            .mock("POST", "/accounts/account_number/previewOrder")
+            .mock("POST", "/trader/v1/accounts/account_number/previewOrder")
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body_from_file(concat!(
@@ -1488,14 +2451,10 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.post(format!(
-            "{url}{}",
-            PostAccountPreviewOrderRequest::endpoint(account_number.clone()).url_endpoint()
-        ));
-
-        let req =
-            PostAccountPreviewOrderRequest::new_with(req, account_number.clone(), body.clone());
+        let req = api
+            .post_accounts_preview_order(account_number.clone().into(), body.clone())
+            .await
+            .unwrap();
 
         // check initial value
         assert_eq!(req.account_number, account_number);
@@ -1511,14 +2470,35 @@ mod tests {
         assert_eq!(result.order_id, 0);
     }
 
+    #[test]
+    fn test_get_account_transactions_request_all_types() {
+        let date = chrono::DateTime::from_timestamp(1_420_070_400, 0).unwrap();
+        let req = GetAccountTransactions::new_with(
+            Client::new().get("http://localhost/accounts/account_number/transactions"),
+            "account_number".to_string(),
+            date,
+            date,
+            vec![TransactionType::Trade],
+        )
+        .types(TransactionType::all());
+        assert_eq!(req.types, TransactionType::all());
+
+        let req = req.build().build().unwrap();
+        let (_, types) = req.url().query_pairs().find(|(k, _)| k == "types").unwrap();
+        assert_eq!(
+            types,
+            "TRADE,RECEIVE_AND_DELIVER,DIVIDEND_OR_INTEREST,ACH_RECEIPT,ACH_DISBURSEMENT,CASH_RECEIPT,CASH_DISBURSEMENT,ELECTRONIC_FUND,WIRE_OUT,WIRE_IN,JOURNAL,MEMORANDUM,MARGIN_CALL,MONEY_MARKET,SMA_ADJUSTMENT"
+        );
+    }
+
     #[tokio::test]
     async fn test_get_account_transactions_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let account_number = "account_number".to_string();
@@ -1535,15 +2515,15 @@ mod tests {
             .and_local_timezone(chrono::Utc)
             .unwrap();
         let symbol = "VTI".to_string();
-        let types = TransactionType::ReceiveAndDeliver;
+        let types = vec![TransactionType::ReceiveAndDeliver];
 
         // Create a mock
         let mock = server
-            .mock("GET", "/accounts/account_number/transactions")
+            .mock("GET", "/trader/v1/accounts/account_number/transactions")
             .match_query(Matcher::AllOf(vec![
                 Matcher::UrlEncoded("startDate".into(), start_date.format("%+").to_string()),
                 Matcher::UrlEncoded("endDate".into(), end_date.format("%+").to_string()),
-                Matcher::UrlEncoded("symbol".into(), symbol.to_string()),
+                Matcher::UrlEncoded("symbol".into(), symbol.clone()),
                 Matcher::UrlEncoded("types".into(), "RECEIVE_AND_DELIVER".into()),
             ]))
             .with_status(200)
@@ -1555,19 +2535,15 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetAccountTransactions::endpoint(account_number.clone()).url_endpoint()
-        ));
-
-        let mut req = GetAccountTransactions::new_with(
-            req,
-            account_number.clone(),
-            start_date,
-            end_date,
-            types,
-        );
+        let mut req = api
+            .get_account_transactions(
+                account_number.clone().into(),
+                start_date,
+                end_date,
+                types.clone(),
+            )
+            .await
+            .unwrap();
 
         // check initial value
         assert_eq!(req.account_number, account_number);
@@ -1577,7 +2553,7 @@ mod tests {
         assert_eq!(req.types, types);
 
         // check setter
-        req.symbol(symbol.clone());
+        req = req.symbol(symbol.clone());
         assert_eq!(req.symbol, Some(symbol));
 
         dbg!(&req);
@@ -1591,10 +2567,10 @@ mod tests {
     async fn test_get_account_transaction_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         let account_number = "account_number".to_string();
@@ -1602,7 +2578,7 @@ mod tests {
 
         // Create a mock
         let mock = server
-            .mock("GET", "/accounts/account_number/transactions/123")
+            .mock("GET", "/trader/v1/accounts/account_number/transactions/123")
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body_from_file(concat!(
@@ -1612,13 +2588,10 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetAccountTransaction::endpoint(account_number.clone(), transaction_id).url_endpoint()
-        ));
-
-        let req = GetAccountTransaction::new_with(req, account_number.clone(), transaction_id);
+        let req = api
+            .get_account_transaction(account_number.clone().into(), transaction_id)
+            .await
+            .unwrap();
 
         // check initial value
         assert_eq!(req.account_number, account_number);
@@ -1638,17 +2611,17 @@ mod tests {
     async fn test_get_user_preference_request() {
         // Request a new server from the pool
         let mut server = mockito::Server::new_async().await;
+        let api = mock_api(&mut server).await;
 
         // Use one of these addresses to configure your client
         let _host = server.host_with_port();
-        let url = server.url();
 
         // define parameter
         // none
 
         // Create a mock
         let mock = server
-            .mock("GET", "/userPreference")
+            .mock("GET", "/trader/v1/userPreference")
             .with_status(200)
             .with_header("content-type", "application/json")
             .with_body_from_file(concat!(
@@ -1658,13 +2631,7 @@ mod tests {
             .create_async()
             .await;
 
-        let client = Client::new();
-        let req = client.get(format!(
-            "{url}{}",
-            GetUserPreferenceRequest::endpoint().url_endpoint()
-        ));
-
-        let req = GetUserPreferenceRequest::new_with(req);
+        let req = api.get_user_preference().await.unwrap();
 
         // check initial value
         // none
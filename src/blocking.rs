@@ -0,0 +1,147 @@
+//! A synchronous facade over [`crate::Api`], enabled with the `blocking` feature.
+//!
+//! Every call drives the async request to completion on a runtime owned by [`Api`],
+//! so no tokio runtime is needed by the caller. The methods must not be called from within
+//! an async context, where blocking the thread would panic.
+//!
+//! ```no_run
+//! use schwab_api::blocking::Api;
+//! use schwab_api::token::StaticTokener;
+//!
+//! let api = Api::new(StaticTokener::new("access_token".to_string()), reqwest::Client::new()).unwrap();
+//! let quote = api.get_quote("VTI".to_string()).unwrap();
+//! println!("{quote:?}");
+//!
+//! // any other request, with its setters, through block_on
+//! let candles = api
+//!     .block_on(async {
+//!         api.inner()
+//!             .get_price_history("VTI".to_string())
+//!             .await?
+//!             .need_extended_hours_data(false)
+//!             .send()
+//!             .await
+//!     })
+//!     .unwrap();
+//! ```
+
+use std::future::Future;
+
+use reqwest::Client;
+use tokio::runtime::Runtime;
+
+use crate::api::ApiConfig;
+use crate::error::Error;
+use crate::model;
+use crate::token::Tokener;
+
+/// Interacting with the Schwab API without async.
+#[derive(Debug)]
+pub struct Api<T: Tokener> {
+    inner: crate::Api<T>,
+    runtime: Runtime,
+}
+
+impl<T: Tokener> Api<T> {
+    /// Create API Struct, see [`crate::Api::new`]
+    pub fn new(tokener: T, client: Client) -> Result<Self, Error> {
+        let runtime = Self::runtime()?;
+        let inner = runtime.block_on(crate::Api::new(tokener, client))?;
+        Ok(Self { inner, runtime })
+    }
+
+    /// Create API Struct sending every request through a client built from `config`, see [`crate::Api::new_with_config`]
+    pub fn new_with_config(tokener: T, config: ApiConfig) -> Result<Self, Error> {
+        let runtime = Self::runtime()?;
+        let inner = runtime.block_on(crate::Api::new_with_config(tokener, config))?;
+        Ok(Self { inner, runtime })
+    }
+
+    fn runtime() -> Result<Runtime, Error> {
+        // a worker thread keeps background tasks, e.g. of the tokener, running between calls
+        Ok(tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()?)
+    }
+
+    /// The async API, for requests not wrapped here, to be sent with [`Api::block_on`]
+    #[must_use]
+    pub fn inner(&self) -> &crate::Api<T> {
+        &self.inner
+    }
+
+    /// Run `future` to completion on the runtime of this API
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// Get Quote by a single symbol
+    pub fn get_quote(
+        &self,
+        symbol: impl Into<model::Symbol>,
+    ) -> Result<model::QuoteResponse, Error> {
+        self.block_on(async { self.inner.get_quote(symbol).await?.send().await })
+    }
+
+    /// Get `PriceHistory` for a single symbol with the default period and frequency
+    pub fn get_price_history(
+        &self,
+        symbol: impl Into<model::Symbol>,
+    ) -> Result<model::CandleList, Error> {
+        self.block_on(async { self.inner.get_price_history(symbol).await?.send().await })
+    }
+
+    /// Get linked account(s) balances and positions for the logged in user
+    pub fn get_accounts(&self) -> Result<model::Accounts, Error> {
+        self.block_on(async { self.inner.get_accounts().await?.send().await })
+    }
+
+    /// Place an order and return its ID, see [`crate::api::trader::PostAccountOrderRequest::send`]
+    pub fn post_account_order(
+        &self,
+        account_number: model::EncryptedAccountNumber,
+        body: model::OrderRequest,
+    ) -> Result<i64, Error> {
+        self.block_on(async {
+            self.inner
+                .post_account_order(account_number, body)
+                .await?
+                .send()
+                .await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::token::StaticTokener;
+
+    #[test]
+    fn test_get_quote() {
+        let mut server = mockito::Server::new();
+        let url = server.url();
+
+        let mock = server
+            .mock("GET", "/marketdata/v1/AAPL/quotes")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body_from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/model/MarketData/QuoteResponse_real.json"
+            ))
+            .expect(2)
+            .create();
+
+        let api = Api::new_with_config(
+            StaticTokener::new("access".to_string()),
+            ApiConfig::default().with_base_url(&url),
+        )
+        .unwrap();
+        let quote = api.get_quote("AAPL".to_string()).unwrap();
+        mock.assert();
+        assert_eq!(quote.symbol(), "AAPL");
+    }
+}
@@ -10,14 +10,96 @@ pub enum Error {
     Reqwest(#[from] reqwest::Error),
     #[error("OrderRequestBuild error: {0}")]
     OrderRequestBuild(crate::model::trader::order_request::OrderRequestBuilderError),
+    #[error("InvalidActivationPrice error: {0}")]
+    InvalidActivationPrice(String),
+    #[error("InvalidParameter error: {0}")]
+    InvalidParameter(String),
+    #[error("MarginAccountRequired error: {0}")]
+    MarginAccountRequired(String),
     #[error("QuoteError: {0:?}")]
     Quote(crate::model::QuoteError),
+    #[error("Symbol not found: {0}")]
+    SymbolNotFound(String),
+    #[error("InvalidOptionSymbol error: {0}")]
+    InvalidOptionSymbol(String),
+    /// A created order whose `Location` header does not end with its ID.
+    #[error("MissingLocationHeader error: {0}")]
+    MissingLocationHeader(String),
+    /// An order that ended without filling, e.g. canceled or replaced.
+    #[error("OrderNotFilled error: {0:?}")]
+    OrderNotFilled(crate::model::trader::order::Status),
     #[error("ErrorResponse: {0:?}")]
     Response(crate::model::ErrorResponse),
     #[error("ServiceError: {0:?}")]
     Service(crate::model::ServiceError),
     #[error("Json error: {0}")]
     Json(#[from] serde_json::Error),
+    /// A response body that did not match the expected model, kept as received.
+    #[error("Deserialization error: {source}, body: {body}")]
+    Deserialization {
+        source: serde_json::Error,
+        body: String,
+    },
+    #[cfg(feature = "csv")]
+    #[error("Csv error: {0}")]
+    Csv(#[from] csv::Error),
+    #[cfg(feature = "streaming")]
+    #[error("WebSocket error: {0}")]
+    WebSocket(#[from] Box<tokio_tungstenite::tungstenite::Error>),
+    /// The streamer answered a request with a non-zero code, or closed before answering.
+    #[cfg(feature = "streaming")]
+    #[error("Streamer error: {0}")]
+    Streamer(String),
     #[error("ChannelMessenger error: {0}")]
     ChannelMessenger(String),
+    #[error("AnalyticalParamMismatch error: {0}")]
+    AnalyticalParamMismatch(String),
+    #[error("Timeout error: no response within {0:?}")]
+    Timeout(std::time::Duration),
+    /// Answered with `429 Too Many Requests`, `retry_after` is read from the `Retry-After` header.
+    #[error("RateLimited error: retry after {retry_after:?}")]
+    RateLimited {
+        retry_after: Option<std::time::Duration>,
+    },
+    /// The range between `from_entered_time` and `to_entered_time` of an orders request is longer than Schwab accepts,
+    /// `days` counts a started day as a whole one.
+    #[error("InvalidTimeRange error: {days} days between from_entered_time and to_entered_time, at most {max} days are accepted")]
+    InvalidTimeRange { days: i64, max: i64 },
+    /// Answered with `401 Unauthorized`, even after the reauthorization if enabled.
+    #[error("Unauthorized error: the access token was rejected")]
+    Unauthorized,
+    /// Some orders of [`crate::Api::cancel_all_orders`] could not be canceled: the IDs of the canceled ones,
+    /// then each failed ID with its error.
+    #[error("PartialCancel error: {} orders not canceled", .1.len())]
+    PartialCancel(Vec<i64>, Vec<(i64, Error)>),
+    /// Some chunks of a quotes request failed: the quotes of the other chunks, in request order,
+    /// and the first error.
+    #[error("PartialQuotes error: {1}")]
+    PartialQuotes(
+        Vec<(crate::model::Symbol, crate::model::QuoteResponse)>,
+        Box<Error>,
+    ),
+}
+
+impl Error {
+    /// Whether sending the same request again later may succeed, e.g. throttled or timed out.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::RateLimited { .. } | Error::Timeout(_) => true,
+            Error::Reqwest(e) => e.is_connect() || e.is_timeout(),
+            Error::PartialQuotes(_, e) => e.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// Whether the token has to be renewed, see [`crate::token::Tokener::redo_authorization`].
+    #[must_use]
+    pub fn is_auth_error(&self) -> bool {
+        match self {
+            Error::Unauthorized | Error::Token(_) => true,
+            Error::PartialQuotes(_, e) => e.is_auth_error(),
+            _ => false,
+        }
+    }
 }
@@ -42,6 +42,12 @@
 //! }
 //! ```
 //!
+//! ## Features
+//! * `blocking`: `blocking::Api`, a synchronous facade driving the async calls on its own runtime.
+//! * `csv`: export `CandleList` as CSV.
+//! * `calculations`: analytics such as `OptionChain::max_pain`.
+//! * `streaming`: `streaming::StreamerClient`, live level one equity quotes over Schwab's WebSocket streamer.
+//!
 //! **Disclaimer:** *This is an unofficial API wrapper for Schwab. It is not endorsed by or affiliated with Schwab or any associated organization. Before using this package, make sure to read and understand the terms of service of the underlying API. The authors of this package accept no responsibility for any damage that might stem from its use. Refer to the LICENSE file for more details.*
 
 #![forbid(unsafe_code)]
@@ -62,9 +68,14 @@ unused_allocation
 )]
 
 pub mod api;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod error;
 pub mod model;
+#[cfg(feature = "streaming")]
+pub mod streaming;
 pub mod token;
 
+pub use api::parameter;
 pub use api::Api;
 pub use error::Error;
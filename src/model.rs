@@ -1,6 +1,8 @@
 //! Structs and utilities for handling API response data.
 
+pub(crate) mod datetime;
 pub mod market_data;
+pub mod symbol;
 pub mod trader;
 
 pub use market_data::candle_list::CandleList;
@@ -9,20 +11,28 @@ pub use market_data::expiration_chain::ExpirationChain;
 pub use market_data::instrument::InstrumentResponse;
 pub use market_data::instrument::Instruments;
 pub use market_data::market::Markets;
+pub use market_data::market::MarketsExt;
 pub use market_data::mover::Mover;
 pub use market_data::option_chain::OptionChain;
+pub use market_data::option_symbol::OptionSymbol;
 pub use market_data::quote_response::quote_error::QuoteError;
+pub use market_data::quote_response::QuoteData;
 pub use market_data::quote_response::QuoteResponse;
 pub(crate) use market_data::quote_response::QuoteResponseMap;
+pub use symbol::Symbol;
 
 pub use trader::account_number::AccountNumbers;
+pub use trader::account_number::EncryptedAccountNumber;
 pub use trader::accounts::Account;
 pub use trader::accounts::Accounts;
+pub use trader::accounts::Balances;
 pub use trader::order::Order;
+pub use trader::order_request::FieldChange;
 pub use trader::order_request::InstrumentRequest;
 pub use trader::order_request::OrderRequest;
 pub use trader::preview_order::Instruction;
 pub use trader::preview_order::PreviewOrder;
+pub use trader::realized_pnl::{realized_pnl, RealizedLot};
 pub use trader::service_error::ServiceError;
 pub use trader::transactions::Transaction;
 pub use trader::user_preference::UserPreferences;
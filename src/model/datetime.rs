@@ -0,0 +1,229 @@
+//! Serde helpers for the timestamp formats returned by Schwab.
+//!
+//! * [`millis_datetime`]: epoch milliseconds, e.g. `1715990395834`.
+//!   Used by quotes (`quoteTime`, `tradeTime`, ...), candles (`datetime`), option contracts and movers.
+//! * [`iso8601_datetime`]: ISO-8601 strings, e.g. `2024-05-17T14:39:29+0000`, `2024-05-20T09:30:00-04:00`
+//!   or `2024-05-10T04:00:00Z`. Used by orders, transactions, accounts, market hours, fundamentals
+//!   and expiration dates. Offsets with or without a colon, a missing offset (taken as UTC) and
+//!   plain dates (taken as midnight UTC) are accepted; serialization is unchanged.
+//!
+//! Both have an `option` submodule for `Option<DateTime<Utc>>` fields, used with `#[serde(default)]`.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+
+pub(crate) mod millis_datetime {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Millis {
+        Int(i64),
+        Float(f64),
+        Str(String),
+    }
+
+    fn to_datetime<E: serde::de::Error>(millis: i64) -> Result<DateTime<Utc>, E> {
+        DateTime::from_timestamp_millis(millis)
+            .ok_or_else(|| E::custom(format!("timestamp out of range: {millis}")))
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn parse<'de, D: Deserializer<'de>>(millis: Millis) -> Result<DateTime<Utc>, D::Error> {
+        let millis = match millis {
+            Millis::Int(x) => x,
+            Millis::Float(x) => x.round() as i64,
+            Millis::Str(x) => x.trim().parse().map_err(serde::de::Error::custom)?,
+        };
+        to_datetime(millis)
+    }
+
+    pub(crate) fn serialize<S: Serializer>(
+        date: &DateTime<Utc>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(date.timestamp_millis())
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<Utc>, D::Error> {
+        parse::<D>(Millis::deserialize(deserializer)?)
+    }
+
+    pub(crate) mod option {
+        use super::{parse, Millis};
+        use chrono::{DateTime, Utc};
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        #[allow(clippy::ref_option)]
+        pub(crate) fn serialize<S: Serializer>(
+            date: &Option<DateTime<Utc>>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match date {
+                Some(date) => super::serialize(date, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<DateTime<Utc>>, D::Error> {
+            Option::<Millis>::deserialize(deserializer)?
+                .map(parse::<D>)
+                .transpose()
+        }
+    }
+}
+
+pub(crate) mod iso8601_datetime {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(
+        date: &DateTime<Utc>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        date.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<Utc>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        super::parse_iso8601(&s).map_err(serde::de::Error::custom)
+    }
+
+    pub(crate) mod option {
+        use chrono::{DateTime, Utc};
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        #[allow(clippy::ref_option)]
+        pub(crate) fn serialize<S: Serializer>(
+            date: &Option<DateTime<Utc>>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match date {
+                Some(date) => super::serialize(date, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<DateTime<Utc>>, D::Error> {
+            Option::<String>::deserialize(deserializer)?
+                .map(|s| super::super::parse_iso8601(&s).map_err(serde::de::Error::custom))
+                .transpose()
+        }
+    }
+}
+
+fn parse_iso8601(s: &str) -> Result<DateTime<Utc>, String> {
+    let s = s.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(s) {
+        return Ok(date.to_utc());
+    }
+    // offset without colon, e.g. `+0000`
+    if let Ok(date) = DateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f%z") {
+        return Ok(date.to_utc());
+    }
+    if let Ok(date) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f") {
+        return Ok(date.and_utc());
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).expect("midnight").and_utc());
+    }
+
+    Err(format!("invalid ISO-8601 timestamp: {s}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Millis {
+        #[serde(with = "millis_datetime")]
+        time: DateTime<Utc>,
+        #[serde(default, with = "millis_datetime::option")]
+        optional: Option<DateTime<Utc>>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Iso8601 {
+        #[serde(with = "iso8601_datetime")]
+        time: DateTime<Utc>,
+        #[serde(default, with = "iso8601_datetime::option")]
+        optional: Option<DateTime<Utc>>,
+    }
+
+    #[test]
+    fn test_millis_datetime() {
+        let expected = DateTime::from_timestamp_millis(1_715_990_395_834).unwrap();
+
+        for json in [
+            r#"{"time": 1715990395834}"#,
+            r#"{"time": 1715990395834.0}"#,
+            r#"{"time": "1715990395834"}"#,
+        ] {
+            let val: Millis = serde_json::from_str(json).unwrap();
+            assert_eq!(val.time, expected);
+            assert_eq!(val.optional, None);
+        }
+
+        let val: Millis =
+            serde_json::from_str(r#"{"time": 1715990395834, "optional": 1715990395834}"#).unwrap();
+        assert_eq!(val.optional, Some(expected));
+        assert_eq!(
+            serde_json::to_value(&val).unwrap(),
+            serde_json::json!({"time": 1_715_990_395_834_i64, "optional": 1_715_990_395_834_i64})
+        );
+
+        assert!(serde_json::from_str::<Millis>(r#"{"time": "now"}"#).is_err());
+    }
+
+    #[test]
+    fn test_iso8601_datetime() {
+        let expected = DateTime::parse_from_rfc3339("2024-05-17T14:39:29Z")
+            .unwrap()
+            .to_utc();
+
+        for s in [
+            // orders and transactions
+            "2024-05-17T14:39:29+0000",
+            // fundamentals
+            "2024-05-17T14:39:29Z",
+            "2024-05-17T14:39:29.000Z",
+            // market hours
+            "2024-05-17T10:39:29-04:00",
+            "2024-05-17T14:39:29",
+        ] {
+            let val: Iso8601 = serde_json::from_value(serde_json::json!({ "time": s })).unwrap();
+            assert_eq!(val.time, expected, "{s}");
+            assert_eq!(val.optional, None);
+        }
+
+        let val: Iso8601 =
+            serde_json::from_str(r#"{"time": "2024-05-17", "optional": "2024-05-17T14:39:29Z"}"#)
+                .unwrap();
+        assert_eq!(
+            val.time,
+            NaiveDate::from_ymd_opt(2024, 5, 17)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc()
+        );
+        assert_eq!(val.optional, Some(expected));
+        assert_eq!(
+            serde_json::to_value(&val).unwrap(),
+            serde_json::json!({"time": "2024-05-17T00:00:00Z", "optional": "2024-05-17T14:39:29Z"})
+        );
+
+        assert!(serde_json::from_str::<Iso8601>(r#"{"time": "tomorrow"}"#).is_err());
+    }
+}
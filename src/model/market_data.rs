@@ -7,4 +7,5 @@ pub mod instrument;
 pub mod market;
 pub mod mover;
 pub mod option_chain;
+pub mod option_symbol;
 pub mod quote_response;
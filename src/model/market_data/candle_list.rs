@@ -1,8 +1,8 @@
 use serde::Deserialize;
 use serde::Serialize;
-use serde_with::{serde_as, TimestampMilliSeconds};
 
-#[serde_as]
+use crate::model::datetime::{iso8601_datetime, millis_datetime};
+
 #[serde_with::apply(
     Option => #[serde(skip_serializing_if = "Option::is_none")],
 )]
@@ -12,21 +12,22 @@ pub struct CandleList {
     pub candles: Vec<Candle>,
     pub empty: Option<bool>,
     pub previous_close: Option<f64>,
-    #[serde_as(as = "Option<TimestampMilliSeconds<i64>>")]
+    #[serde(default, with = "millis_datetime::option")]
     pub previous_close_date: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(rename = "previousCloseDateISO8601")]
+    #[serde(default, with = "iso8601_datetime::option")]
     pub previous_close_date_iso8601: Option<chrono::DateTime<chrono::Utc>>,
     pub symbol: String,
 }
 
-#[serde_as]
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Candle {
     pub close: f64,
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub datetime: chrono::DateTime<chrono::Utc>,
     #[serde(rename = "dateTimeISO8601", skip_serializing_if = "Option::is_none")]
+    #[serde(default, with = "iso8601_datetime::option")]
     pub datetime_iso8601: Option<chrono::DateTime<chrono::Utc>>,
     pub high: f64,
     pub low: f64,
@@ -34,11 +35,175 @@ pub struct Candle {
     pub volume: u64,
 }
 
+impl CandleList {
+    /// Aggregate candles into coarser bars, e.g. 1-minute to 5-minute.
+    ///
+    /// Buckets are aligned to wall-clock boundaries (multiples of `interval` since the Unix epoch)
+    /// and each bar is stamped with its bucket start.
+    /// `open` is the first, `high` the max, `low` the min, `close` the last and `volume` the sum.
+    /// Candles are expected in chronological order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `interval` is not positive.
+    #[must_use]
+    pub fn resample(&self, interval: chrono::Duration) -> CandleList {
+        let step = interval.num_milliseconds();
+        assert!(step > 0, "resample interval must be positive");
+
+        let mut candles: Vec<Candle> = Vec::new();
+        for candle in &self.candles {
+            let millis = candle.datetime.timestamp_millis();
+            let start = chrono::DateTime::from_timestamp_millis(millis - millis.rem_euclid(step))
+                .expect("bucket start within range");
+
+            match candles.last_mut() {
+                Some(bar) if bar.datetime == start => {
+                    bar.high = bar.high.max(candle.high);
+                    bar.low = bar.low.min(candle.low);
+                    bar.close = candle.close;
+                    bar.volume += candle.volume;
+                }
+                _ => candles.push(Candle {
+                    datetime: start,
+                    datetime_iso8601: candle.datetime_iso8601.map(|_| start),
+                    ..*candle
+                }),
+            }
+        }
+
+        CandleList {
+            candles,
+            ..self.clone()
+        }
+    }
+
+    /// Volume-weighted average price: the sum of `close * volume` over the sum of `volume`.
+    ///
+    /// `NaN` when there is no candle or no volume.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn vwap(&self) -> f64 {
+        let (value, volume) = self
+            .candles
+            .iter()
+            .fold((0.0, 0.0), |(value, volume), candle| {
+                let v = candle.volume as f64;
+                (value + candle.close * v, volume + v)
+            });
+        if volume == 0.0 {
+            return f64::NAN;
+        }
+
+        value / volume
+    }
+
+    /// Simple moving average of `close` over `period` candles.
+    ///
+    /// Aligned with [`CandleList::candles`]: the first `period - 1` values are `NaN`.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn sma(&self, period: usize) -> Vec<f64> {
+        let mut sma = vec![f64::NAN; self.candles.len()];
+        if period == 0 {
+            return sma;
+        }
+
+        let mut sum = 0.0;
+        for (i, candle) in self.candles.iter().enumerate() {
+            sum += candle.close;
+            if i >= period {
+                sum -= self.candles[i - period].close;
+            }
+            if i + 1 >= period {
+                sma[i] = sum / period as f64;
+            }
+        }
+
+        sma
+    }
+
+    /// Exponential moving average of `close` with a smoothing factor of `2 / (period + 1)`.
+    ///
+    /// Seeded with the simple moving average of the first `period` candles,
+    /// so the first `period - 1` values are `NaN` as with [`CandleList::sma`].
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn ema(&self, period: usize) -> Vec<f64> {
+        let mut ema = self.sma(period);
+        if period == 0 {
+            return ema;
+        }
+
+        let alpha = 2.0 / (period as f64 + 1.0);
+        for i in period..self.candles.len() {
+            ema[i] = alpha * self.candles[i].close + (1.0 - alpha) * ema[i - 1];
+        }
+
+        ema
+    }
+
+    /// Write the candles as CSV rows of `datetime,open,high,low,close,volume`.
+    ///
+    /// `datetime` is in ISO-8601, e.g. `2021-12-10T12:00:00Z`.
+    #[cfg(feature = "csv")]
+    pub fn to_csv(
+        &self,
+        writer: &mut impl std::io::Write,
+        include_header: bool,
+    ) -> Result<(), crate::Error> {
+        self.write_csv(writer, include_header, |x| {
+            x.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+        })
+    }
+
+    /// Same as [`CandleList::to_csv`], with `datetime` converted to `tz`, e.g. `2021-12-10T07:00:00-05:00`.
+    #[cfg(feature = "csv")]
+    pub fn to_csv_with_tz(
+        &self,
+        writer: &mut impl std::io::Write,
+        tz: &chrono_tz::Tz,
+        include_header: bool,
+    ) -> Result<(), crate::Error> {
+        self.write_csv(writer, include_header, |x| {
+            x.with_timezone(tz)
+                .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+        })
+    }
+
+    #[cfg(feature = "csv")]
+    fn write_csv(
+        &self,
+        writer: &mut impl std::io::Write,
+        include_header: bool,
+        datetime: impl Fn(&chrono::DateTime<chrono::Utc>) -> String,
+    ) -> Result<(), crate::Error> {
+        let mut writer = csv::Writer::from_writer(writer);
+        if include_header {
+            writer.write_record(["datetime", "open", "high", "low", "close", "volume"])?;
+        }
+        for candle in &self.candles {
+            writer.write_record([
+                datetime(&candle.datetime),
+                candle.open.to_string(),
+                candle.high.to_string(),
+                candle.low.to_string(),
+                candle.close.to_string(),
+                candle.volume.to_string(),
+            ])?;
+        }
+        writer.flush()?;
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use assert_json_diff::{assert_json_matches, CompareMode, Config, NumericMode};
+    use float_cmp::assert_approx_eq;
 
     #[test]
     fn test_de() {
@@ -52,6 +217,29 @@ mod tests {
         assert!(val.is_ok());
     }
 
+    #[test]
+    fn test_de_datetime() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/CandleList.json"
+        ));
+
+        let val = serde_json::from_str::<CandleList>(json).unwrap();
+        // 1639137600000
+        assert_eq!(
+            val.candles[0].datetime,
+            chrono::NaiveDate::from_ymd_opt(2021, 12, 10)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+                .and_utc()
+        );
+        assert_eq!(
+            val.previous_close_date.unwrap().to_rfc3339(),
+            "2021-12-09T06:00:00+00:00"
+        );
+    }
+
     #[test]
     fn test_serde_real() {
         let json = include_str!(concat!(
@@ -87,4 +275,160 @@ mod tests {
             Config::new(CompareMode::Strict).numeric_mode(NumericMode::AssumeFloat)
         );
     }
+
+    #[test]
+    fn test_resample() {
+        let start = chrono::DateTime::from_timestamp(1_700_000_100, 0).unwrap();
+        let minute = |i: i64, open: f64, high: f64, low: f64, close: f64| Candle {
+            close,
+            datetime: start + chrono::Duration::minutes(i),
+            datetime_iso8601: None,
+            high,
+            low,
+            open,
+            volume: 100,
+        };
+        let list = CandleList {
+            candles: vec![
+                minute(0, 10.0, 11.0, 9.5, 10.5),
+                minute(1, 10.5, 12.0, 10.0, 11.5),
+                minute(2, 11.5, 11.8, 9.0, 9.2),
+                minute(3, 9.2, 10.0, 9.1, 9.9),
+                minute(4, 9.9, 10.2, 9.8, 10.1),
+                minute(5, 10.1, 10.4, 10.0, 10.3),
+                minute(6, 10.3, 13.0, 10.2, 12.5),
+            ],
+            empty: Some(false),
+            previous_close: None,
+            previous_close_date: None,
+            previous_close_date_iso8601: None,
+            symbol: "AAPL".to_string(),
+        };
+
+        let resampled = list.resample(chrono::Duration::minutes(5));
+        assert_eq!(resampled.symbol, "AAPL");
+        assert_eq!(
+            resampled.candles,
+            vec![
+                // 1_700_000_100 is 22:15 UTC, so the first bucket is full
+                Candle {
+                    close: 10.1,
+                    datetime: start,
+                    datetime_iso8601: None,
+                    high: 12.0,
+                    low: 9.0,
+                    open: 10.0,
+                    volume: 500,
+                },
+                Candle {
+                    close: 12.5,
+                    datetime: start + chrono::Duration::minutes(5),
+                    datetime_iso8601: None,
+                    high: 13.0,
+                    low: 10.0,
+                    open: 10.1,
+                    volume: 200,
+                },
+            ]
+        );
+
+        // wall-clock buckets, not anchored to the first candle
+        let resampled = list.resample(chrono::Duration::minutes(2));
+        let volumes: Vec<u64> = resampled.candles.iter().map(|c| c.volume).collect();
+        assert_eq!(volumes, vec![100, 200, 200, 200]);
+        assert_eq!(
+            resampled.candles[0].datetime,
+            start - chrono::Duration::minutes(1)
+        );
+    }
+
+    #[test]
+    fn test_indicators() {
+        let start = chrono::DateTime::from_timestamp(1_700_000_100, 0).unwrap();
+        let list = |closes: &[(f64, u64)]| CandleList {
+            candles: closes
+                .iter()
+                .enumerate()
+                .map(|(i, &(close, volume))| Candle {
+                    close,
+                    datetime: start + chrono::Duration::minutes(i64::try_from(i).unwrap()),
+                    datetime_iso8601: None,
+                    high: close,
+                    low: close,
+                    open: close,
+                    volume,
+                })
+                .collect(),
+            empty: Some(closes.is_empty()),
+            previous_close: None,
+            previous_close_date: None,
+            previous_close_date_iso8601: None,
+            symbol: "AAPL".to_string(),
+        };
+
+        let val = list(&[
+            (10.0, 100),
+            (11.0, 300),
+            (12.0, 100),
+            (13.0, 0),
+            (14.0, 500),
+        ]);
+        // (1000 + 3300 + 1200 + 7000) / 1000
+        assert_approx_eq!(f64, 12.5, val.vwap());
+
+        let sma = val.sma(3);
+        assert_eq!(sma.len(), 5);
+        assert!(sma[0].is_nan() && sma[1].is_nan());
+        assert_approx_eq!(f64, 11.0, sma[2]);
+        assert_approx_eq!(f64, 12.0, sma[3]);
+        assert_approx_eq!(f64, 13.0, sma[4]);
+        assert!(val.sma(0).iter().all(|x| x.is_nan()));
+        assert!(val.sma(6).iter().all(|x| x.is_nan()));
+        assert!(val.ema(0).iter().all(|x| x.is_nan()));
+
+        // alpha = 0.5, seeded with the first SMA
+        let ema = val.ema(3);
+        assert!(ema[0].is_nan() && ema[1].is_nan());
+        assert_approx_eq!(f64, 11.0, ema[2]);
+        assert_approx_eq!(f64, 12.0, ema[3]);
+        assert_approx_eq!(f64, 13.0, ema[4]);
+        let ema = val.ema(1);
+        assert_approx_eq!(f64, 10.0, ema[0]);
+        assert_approx_eq!(f64, 14.0, ema[4]);
+
+        let empty = list(&[]);
+        assert!(empty.vwap().is_nan());
+        assert!(empty.sma(3).is_empty());
+        assert!(empty.ema(3).is_empty());
+        assert!(list(&[(10.0, 0)]).vwap().is_nan());
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_to_csv() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/CandleList.json"
+        ));
+        let mut val = serde_json::from_str::<CandleList>(json).unwrap();
+        val.candles.truncate(2);
+
+        let mut buf = Vec::new();
+        val.to_csv(&mut buf, true).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "datetime,open,high,low,close,volume\n\
+             2021-12-10T12:00:00Z,175.01,175.15,175.01,175.04,10719\n\
+             2021-12-10T12:01:00Z,175.08,175.09,175.05,175.05,500\n"
+        );
+
+        let mut buf = Vec::new();
+        val.to_csv_with_tz(&mut buf, &chrono_tz::America::New_York, false)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            "2021-12-10T07:00:00-05:00,175.01,175.15,175.01,175.04,10719\n\
+             2021-12-10T07:01:00-05:00,175.08,175.09,175.05,175.05,500\n"
+        );
+    }
 }
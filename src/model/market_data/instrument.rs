@@ -159,6 +159,7 @@ pub enum InstrumentAssetType {
     Indicator,
     MutualFund,
     Option,
+    #[serde(other)]
     Unknown,
 }
 
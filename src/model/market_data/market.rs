@@ -2,8 +2,59 @@ use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashMap;
 
+use crate::api::parameter::Market;
+use crate::model::datetime::iso8601_datetime;
+
 pub type Markets = HashMap<String, HashMap<String, Hours>>;
 
+/// Lookups over every product of a market, `Markets` being a plain `HashMap`.
+pub trait MarketsExt {
+    /// Whether any product of `market` is in its regular session at `now`, see [`Hours::is_open_at`].
+    ///
+    /// `None` if `market` was not requested.
+    fn is_open(&self, market: Market, now: chrono::DateTime<chrono::Utc>) -> Option<bool>;
+
+    /// The first regular session start of `market` after `now`.
+    ///
+    /// `None` if `market` was not requested or has no session left on the returned date.
+    fn next_open(
+        &self,
+        market: Market,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Option<chrono::DateTime<chrono::Utc>>;
+}
+
+impl MarketsExt for Markets {
+    fn is_open(&self, market: Market, now: chrono::DateTime<chrono::Utc>) -> Option<bool> {
+        self.get(market_key(market))
+            .map(|products| products.values().any(|x| x.is_open_at(now)))
+    }
+
+    fn next_open(
+        &self,
+        market: Market,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.get(market_key(market))?
+            .values()
+            .filter_map(|x| x.session_hours.as_ref()?.get("regularMarket"))
+            .flatten()
+            .map(|x| x.start)
+            .filter(|x| *x > now)
+            .min()
+    }
+}
+
+fn market_key(market: Market) -> &'static str {
+    match market {
+        Market::Equity => "equity",
+        Market::Option => "option",
+        Market::Bond => "bond",
+        Market::Future => "future",
+        Market::Forex => "forex",
+    }
+}
+
 #[allow(clippy::struct_field_names)]
 #[serde_with::apply(
     Option => #[serde(skip_serializing_if = "Option::is_none")],
@@ -21,10 +72,55 @@ pub struct Hours {
     pub session_hours: Option<HashMap<String, Vec<Interval>>>,
 }
 
+impl Hours {
+    /// Whether `at` falls within the regular session, i.e. `sessionHours.regularMarket`.
+    ///
+    /// Pre- and post-market sessions are not counted, see [`Hours::session_at`].
+    #[must_use]
+    pub fn is_open_at(&self, at: chrono::DateTime<chrono::Utc>) -> bool {
+        self.session_at(at) == Some("regularMarket")
+    }
+
+    /// [`Hours::is_open_at`] the current time.
+    #[must_use]
+    pub fn is_open_now(&self) -> bool {
+        self.is_open_at(chrono::Utc::now())
+    }
+
+    /// The name of the session `at` falls within, e.g. `preMarket`, `regularMarket` or `postMarket`.
+    ///
+    /// Sessions include their start but not their end.
+    #[must_use]
+    pub fn session_at(&self, at: chrono::DateTime<chrono::Utc>) -> Option<&str> {
+        self.session_hours
+            .iter()
+            .flatten()
+            .find(|(_, intervals)| intervals.iter().any(|x| x.start <= at && at < x.end))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// The first session start or end after `at`, `None` once the last session of the day ended.
+    #[must_use]
+    pub fn next_session_change(
+        &self,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.session_hours
+            .iter()
+            .flat_map(HashMap::values)
+            .flatten()
+            .flat_map(|x| [x.start, x.end])
+            .filter(|x| *x > at)
+            .min()
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Interval {
+    #[serde(with = "iso8601_datetime")]
     pub start: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "iso8601_datetime")]
     pub end: chrono::DateTime<chrono::Utc>,
 }
 
@@ -43,6 +139,7 @@ pub enum MarketType {
     Indicator,
     MutualFund,
     Option,
+    #[serde(other)]
     Unknown,
 }
 
@@ -88,4 +185,150 @@ mod tests {
         println!("{message}");
         assert_eq!(message, "");
     }
+
+    #[test]
+    fn test_session_hours() {
+        let json = r#"{
+            "date": "2022-04-14",
+            "marketType": "EQUITY",
+            "exchange": "NULL",
+            "category": "NULL",
+            "product": "EQ",
+            "productName": "equity",
+            "isOpen": true,
+            "sessionHours": {
+                "preMarket": [
+                    {
+                        "start": "2022-04-14T07:00:00-04:00",
+                        "end": "2022-04-14T09:30:00-04:00"
+                    }
+                ],
+                "regularMarket": [
+                    {
+                        "start": "2022-04-14T09:30:00-04:00",
+                        "end": "2022-04-14T16:00:00-04:00"
+                    }
+                ],
+                "postMarket": [
+                    {
+                        "start": "2022-04-14T16:00:00-04:00",
+                        "end": "2022-04-14T20:00:00-04:00"
+                    }
+                ]
+            }
+        }"#;
+        let val = serde_json::from_str::<Hours>(json).unwrap();
+        let at = |s: &str| chrono::DateTime::parse_from_rfc3339(s).unwrap().to_utc();
+
+        // before the pre-market
+        assert!(!val.is_open_at(at("2022-04-14T06:00:00-04:00")));
+        assert_eq!(val.session_at(at("2022-04-14T06:00:00-04:00")), None);
+        assert_eq!(
+            val.next_session_change(at("2022-04-14T06:00:00-04:00")),
+            Some(at("2022-04-14T07:00:00-04:00"))
+        );
+
+        // pre-market
+        assert!(!val.is_open_at(at("2022-04-14T08:00:00-04:00")));
+        assert_eq!(
+            val.session_at(at("2022-04-14T08:00:00-04:00")),
+            Some("preMarket")
+        );
+
+        // regular hours, open at the bell
+        assert!(val.is_open_at(at("2022-04-14T09:30:00-04:00")));
+        assert!(val.is_open_at(at("2022-04-14T15:59:59-04:00")));
+        assert_eq!(
+            val.next_session_change(at("2022-04-14T12:00:00-04:00")),
+            Some(at("2022-04-14T16:00:00-04:00"))
+        );
+
+        // post-market, closed at the bell
+        assert!(!val.is_open_at(at("2022-04-14T16:00:00-04:00")));
+        assert_eq!(
+            val.session_at(at("2022-04-14T16:00:00-04:00")),
+            Some("postMarket")
+        );
+
+        // after the post-market
+        assert_eq!(
+            val.next_session_change(at("2022-04-14T20:00:00-04:00")),
+            None
+        );
+        assert!(!val.is_open_now());
+    }
+
+    #[test]
+    fn test_markets_ext() {
+        let json = r#"{
+            "equity": {
+                "EQ": {
+                    "date": "2022-04-14",
+                    "marketType": "EQUITY",
+                    "product": "EQ",
+                    "isOpen": true,
+                    "sessionHours": {
+                        "preMarket": [
+                            {
+                                "start": "2022-04-14T07:00:00-04:00",
+                                "end": "2022-04-14T09:30:00-04:00"
+                            }
+                        ],
+                        "regularMarket": [
+                            {
+                                "start": "2022-04-14T09:30:00-04:00",
+                                "end": "2022-04-14T16:00:00-04:00"
+                            }
+                        ]
+                    }
+                }
+            },
+            "option": {
+                "option": {
+                    "date": "2022-04-16",
+                    "marketType": "OPTION",
+                    "product": "option",
+                    "isOpen": false
+                }
+            }
+        }"#;
+        let val = serde_json::from_str::<Markets>(json).unwrap();
+        let at = |s: &str| chrono::DateTime::parse_from_rfc3339(s).unwrap().to_utc();
+
+        // pre-market
+        assert_eq!(
+            val.is_open(Market::Equity, at("2022-04-14T08:00:00-04:00")),
+            Some(false)
+        );
+        assert_eq!(
+            val.next_open(Market::Equity, at("2022-04-14T08:00:00-04:00")),
+            Some(at("2022-04-14T09:30:00-04:00"))
+        );
+
+        // regular hours
+        assert_eq!(
+            val.is_open(Market::Equity, at("2022-04-14T12:00:00-04:00")),
+            Some(true)
+        );
+        assert_eq!(
+            val.next_open(Market::Equity, at("2022-04-14T12:00:00-04:00")),
+            None
+        );
+
+        // closed all day
+        assert_eq!(
+            val.is_open(Market::Option, at("2022-04-16T12:00:00-04:00")),
+            Some(false)
+        );
+        assert_eq!(
+            val.next_open(Market::Option, at("2022-04-16T12:00:00-04:00")),
+            None
+        );
+
+        // not requested
+        assert_eq!(
+            val.is_open(Market::Bond, at("2022-04-14T12:00:00-04:00")),
+            None
+        );
+    }
 }
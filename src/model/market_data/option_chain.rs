@@ -1,11 +1,11 @@
 use serde::Deserialize;
 use serde::Serialize;
-use serde_with::{serde_as, TimestampMilliSeconds};
 use std::collections::HashMap;
 
 use super::quote_response::option::ExerciseType;
 use super::quote_response::option::ExpirationType;
 use super::quote_response::option::SettlementType;
+use crate::model::datetime::{iso8601_datetime, millis_datetime};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -32,7 +32,120 @@ pub struct OptionChain {
     pub is_chain_truncated: Option<bool>,
 }
 
-#[serde_as]
+type ExpDateMap = HashMap<String, HashMap<String, Vec<OptionContract>>>;
+
+fn cmp_contracts(a: &OptionContract, b: &OptionContract) -> std::cmp::Ordering {
+    a.expiration_date
+        .cmp(&b.expiration_date)
+        .then_with(|| a.strike_price.total_cmp(&b.strike_price))
+        .then_with(|| a.symbol.cmp(&b.symbol))
+}
+
+fn sorted_contracts<'a>(
+    maps: impl IntoIterator<Item = &'a ExpDateMap>,
+) -> std::vec::IntoIter<&'a OptionContract> {
+    let mut contracts: Vec<&OptionContract> = maps
+        .into_iter()
+        .flat_map(HashMap::values)
+        .flat_map(HashMap::values)
+        .flatten()
+        .collect();
+    contracts.sort_by(|a, b| cmp_contracts(a, b));
+    contracts.into_iter()
+}
+
+impl OptionChain {
+    /// Iterates calls and puts of all expirations and strikes.
+    ///
+    /// Sorted by expiration date, then by strike price, then by symbol, so a call comes before its put.
+    pub fn contracts(&self) -> impl Iterator<Item = &OptionContract> {
+        sorted_contracts([&self.call_exp_date_map, &self.put_exp_date_map])
+    }
+
+    /// Iterates calls of all expirations and strikes, sorted as [`OptionChain::contracts`].
+    pub fn calls(&self) -> impl Iterator<Item = &OptionContract> {
+        sorted_contracts([&self.call_exp_date_map])
+    }
+
+    /// Iterates puts of all expirations and strikes, sorted as [`OptionChain::contracts`].
+    pub fn puts(&self) -> impl Iterator<Item = &OptionContract> {
+        sorted_contracts([&self.put_exp_date_map])
+    }
+
+    /// Takes calls and puts of all expirations and strikes, sorted as [`OptionChain::contracts`].
+    #[must_use]
+    pub fn into_contracts(self) -> Vec<OptionContract> {
+        let mut contracts: Vec<OptionContract> = [self.call_exp_date_map, self.put_exp_date_map]
+            .into_iter()
+            .flat_map(HashMap::into_values)
+            .flat_map(HashMap::into_values)
+            .flatten()
+            .collect();
+        contracts.sort_by(cmp_contracts);
+        contracts
+    }
+
+    /// The strike at which the options of the chain would expire with the least total value.
+    ///
+    /// Every strike of the chain is tried as the expiration price of the underlying, summing the
+    /// intrinsic value of all calls and puts weighted by their open interest.
+    /// Ties go to the strike closest to [`OptionChain::underlying_price`].
+    /// Returns `None` if the chain has no contract.
+    #[cfg(feature = "calculations")]
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn max_pain(&self) -> Option<f64> {
+        let mut strikes: Vec<f64> = self.contracts().map(|x| x.strike_price).collect();
+        strikes.sort_by(f64::total_cmp);
+        strikes.dedup();
+
+        let pain = |price: f64| -> f64 {
+            self.contracts()
+                .map(|x| {
+                    let intrinsic = match x.put_call {
+                        PutCall::Call => price - x.strike_price,
+                        PutCall::Put => x.strike_price - price,
+                    };
+                    intrinsic.max(0.0) * x.open_interest as f64
+                })
+                .sum()
+        };
+
+        strikes
+            .into_iter()
+            .map(|strike| (strike, pain(strike)))
+            .min_by(|(a, pain_a), (b, pain_b)| {
+                pain_a.total_cmp(pain_b).then_with(|| {
+                    (a - self.underlying_price)
+                        .abs()
+                        .total_cmp(&(b - self.underlying_price).abs())
+                })
+            })
+            .map(|(strike, _)| strike)
+    }
+
+    /// Returns calls and puts whose strike is within `tolerance` of `strike`, across all expirations.
+    ///
+    /// Sorted by expiration date, then by symbol.
+    #[must_use]
+    pub fn contracts_at_strike(&self, strike: f64, tolerance: f64) -> Vec<&OptionContract> {
+        let mut contracts: Vec<&OptionContract> = self
+            .call_exp_date_map
+            .values()
+            .chain(self.put_exp_date_map.values())
+            .flat_map(HashMap::values)
+            .flatten()
+            .filter(|x| (x.strike_price - strike).abs() <= tolerance)
+            .collect();
+        contracts.sort_by(|a, b| {
+            a.expiration_date
+                .cmp(&b.expiration_date)
+                .then_with(|| a.symbol.cmp(&b.symbol))
+        });
+        contracts
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Underlying {
@@ -58,11 +171,10 @@ pub struct Underlying {
     pub quote_time: i64,
     pub symbol: String,
     pub total_volume: u64,
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub trade_time: chrono::DateTime<chrono::Utc>,
 }
 
-#[serde_as]
 #[serde_with::apply(
     Option => #[serde(skip_serializing_if = "Option::is_none")],
 )]
@@ -86,11 +198,11 @@ pub struct OptionContract {
     pub open_price: f64,
     pub close_price: f64,
     pub total_volume: u64,
-    #[serde_as(as = "Option<TimestampMilliSeconds<i64>>")]
+    #[serde(default, with = "millis_datetime::option")]
     pub trade_date: Option<chrono::DateTime<chrono::Utc>>,
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub quote_time_in_long: chrono::DateTime<chrono::Utc>,
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub trade_time_in_long: chrono::DateTime<chrono::Utc>,
     pub net_change: f64,
     pub volatility: f64,
@@ -108,10 +220,11 @@ pub struct OptionContract {
     pub is_non_standard: Option<bool>,
     pub option_deliverables_list: Vec<OptionDeliverable>,
     pub strike_price: f64,
+    #[serde(with = "iso8601_datetime")]
     pub expiration_date: chrono::DateTime<chrono::Utc>,
     pub days_to_expiration: i64,
     pub expiration_type: ExpirationType,
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub last_trading_day: chrono::DateTime<chrono::Utc>,
     pub multiplier: f64,
     pub settlement_type: SettlementType,
@@ -234,4 +347,120 @@ mod tests {
         println!("{message}");
         assert_eq!(message, "");
     }
+
+    #[test]
+    fn test_contracts() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain_real.json"
+        ));
+        let val = serde_json::from_str::<OptionChain>(json).unwrap();
+
+        let count = |map: &ExpDateMap| map.values().flat_map(HashMap::values).flatten().count();
+        let calls: Vec<&OptionContract> = val.calls().collect();
+        let puts: Vec<&OptionContract> = val.puts().collect();
+        assert_eq!(calls.len(), count(&val.call_exp_date_map));
+        assert_eq!(puts.len(), count(&val.put_exp_date_map));
+        assert!(calls.iter().all(|x| x.put_call == PutCall::Call));
+        assert!(puts.iter().all(|x| x.put_call == PutCall::Put));
+
+        let contracts: Vec<&OptionContract> = val.contracts().collect();
+        assert_eq!(contracts.len(), calls.len() + puts.len());
+        assert!(contracts
+            .windows(2)
+            .all(|x| cmp_contracts(x[0], x[1]).is_le()));
+        assert!(calls.windows(2).all(|x| cmp_contracts(x[0], x[1]).is_le()));
+        assert_eq!(contracts[0].expiration_date, calls[0].expiration_date);
+
+        let owned = val.clone().into_contracts();
+        assert_eq!(owned.len(), contracts.len());
+        assert!(owned.iter().zip(contracts).all(|(a, b)| a == b));
+    }
+
+    #[cfg(feature = "calculations")]
+    #[test]
+    fn test_max_pain() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain_real.json"
+        ));
+        let mut val = serde_json::from_str::<OptionChain>(json).unwrap();
+
+        // keep a single expiration with three strikes
+        let template = val.contracts().next().unwrap().clone();
+        let contract = |put_call: PutCall, strike_price: f64, open_interest: i64| OptionContract {
+            put_call,
+            open_interest,
+            strike_price,
+            ..template.clone()
+        };
+        let map = |contracts: Vec<OptionContract>| -> ExpDateMap {
+            HashMap::from([(
+                "2024-05-17:0".to_string(),
+                contracts
+                    .into_iter()
+                    .map(|x| (x.strike_price.to_string(), vec![x]))
+                    .collect(),
+            )])
+        };
+        val.call_exp_date_map = map(vec![
+            contract(PutCall::Call, 90.0, 100),
+            contract(PutCall::Call, 100.0, 50),
+            contract(PutCall::Call, 110.0, 10),
+        ]);
+        val.put_exp_date_map = map(vec![
+            contract(PutCall::Put, 90.0, 10),
+            contract(PutCall::Put, 100.0, 50),
+            contract(PutCall::Put, 110.0, 200),
+        ]);
+        // at 90: puts 50 * 10 + 200 * 20 = 4500
+        // at 100: calls 100 * 10, puts 200 * 10 = 3000
+        // at 110: calls 100 * 20 + 50 * 10 = 2500
+        assert_eq!(val.max_pain(), Some(110.0));
+
+        // a tie goes to the strike closest to the underlying
+        val.put_exp_date_map = map(vec![]);
+        val.call_exp_date_map = map(vec![
+            contract(PutCall::Call, 90.0, 0),
+            contract(PutCall::Call, 100.0, 0),
+        ]);
+        val.underlying_price = 98.0;
+        assert_eq!(val.max_pain(), Some(100.0));
+
+        val.call_exp_date_map.clear();
+        assert_eq!(val.max_pain(), None);
+    }
+
+    #[test]
+    fn test_contracts_at_strike() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain.json"
+        ));
+        let val = serde_json::from_str::<OptionChain>(json).unwrap();
+        assert_eq!(val.contracts_at_strike(0.0, 0.0).len(), 18);
+        assert!(val.contracts_at_strike(100.0, 1.0).is_empty());
+
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/OptionChain_real.json"
+        ));
+        let val = serde_json::from_str::<OptionChain>(json).unwrap();
+
+        let contracts = val.contracts_at_strike(190.0, 0.0);
+        // a call and a put for each of the 21 expirations
+        assert_eq!(contracts.len(), 42);
+        assert_eq!(contracts[0].symbol, "AAPL  240517C00190000");
+        assert_eq!(contracts[1].symbol, "AAPL  240517P00190000");
+        assert_eq!(contracts[2].symbol, "AAPL  240524C00190000");
+        assert!(contracts
+            .windows(2)
+            .all(|x| x[0].expiration_date <= x[1].expiration_date));
+
+        let contracts = val.contracts_at_strike(157.0, 0.5);
+        assert_eq!(contracts.len(), 6);
+        assert!(contracts
+            .iter()
+            .all(|x| float_cmp::approx_eq!(f64, x.strike_price, 157.5)));
+    }
 }
@@ -0,0 +1,226 @@
+//! Schwab option symbols, e.g. `AAPL  240517C00100000`.
+
+use chrono::NaiveDate;
+
+use super::option_chain::PutCall;
+use crate::Error;
+
+/// The parts of an option symbol.
+///
+/// The symbol is fixed-width: the underlying padded with spaces to 6 characters, the expiration
+/// as `YYMMDD`, `C` or `P`, and the strike multiplied by 1000 on 8 digits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionSymbol {
+    pub underlying: String,
+    pub expiration: NaiveDate,
+    pub put_call: PutCall,
+    pub strike: f64,
+}
+
+impl OptionSymbol {
+    /// Check the parts of a symbol, e.g. `OptionSymbol::new("AAPL", expiration, PutCall::Call, 100.0)`.
+    ///
+    /// The underlying must be 1 to 6 ASCII characters without spaces, the strike positive and below `100_000`.
+    pub fn new(
+        underlying: &str,
+        expiration: NaiveDate,
+        put_call: PutCall,
+        strike: f64,
+    ) -> Result<Self, Error> {
+        let invalid =
+            |reason: &str| Error::InvalidOptionSymbol(format!("{underlying:?} {strike}: {reason}"));
+
+        if underlying.is_empty()
+            || underlying.len() > 6
+            || !underlying.bytes().all(|x| x.is_ascii_graphic())
+        {
+            return Err(invalid("expected 1 to 6 ASCII characters as underlying"));
+        }
+        if !(strike > 0.0 && (strike * 1000.0).round() < 100_000_000.0) {
+            return Err(invalid("expected a strike within 0 and 100000"));
+        }
+
+        Ok(Self {
+            underlying: underlying.to_string(),
+            expiration,
+            put_call,
+            strike,
+        })
+    }
+
+    #[must_use]
+    pub fn underlying(&self) -> &str {
+        &self.underlying
+    }
+
+    #[must_use]
+    pub fn expiry(&self) -> NaiveDate {
+        self.expiration
+    }
+
+    #[must_use]
+    pub fn strike(&self) -> f64 {
+        self.strike
+    }
+
+    #[must_use]
+    pub fn is_call(&self) -> bool {
+        self.put_call == PutCall::Call
+    }
+
+    /// Format as Schwab expects, e.g. `AAPL  240517C00100000`.
+    ///
+    /// The strike is rounded to the nearest thousandth.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn to_schwab_symbol(&self) -> String {
+        let put_call = match self.put_call {
+            PutCall::Call => 'C',
+            PutCall::Put => 'P',
+        };
+        format!(
+            "{:<6}{}{put_call}{:08}",
+            self.underlying,
+            self.expiration.format("%y%m%d"),
+            (self.strike * 1000.0).round() as u64
+        )
+    }
+
+    /// Parse a symbol formatted as [`OptionSymbol::to_schwab_symbol`].
+    pub fn parse(s: &str) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::InvalidOptionSymbol(format!("{s:?}: {reason}"));
+
+        if s.len() != 21 || !s.is_ascii() {
+            return Err(invalid("expected 21 ASCII characters"));
+        }
+
+        let underlying = s[..6].trim_end();
+        if underlying.is_empty() {
+            return Err(invalid("missing underlying"));
+        }
+        let expiration = NaiveDate::parse_from_str(&s[6..12], "%y%m%d")
+            .map_err(|e| invalid(&format!("invalid expiration, {e}")))?;
+        let put_call = match &s[12..13] {
+            "C" => PutCall::Call,
+            "P" => PutCall::Put,
+            _ => return Err(invalid("expected C or P")),
+        };
+        if !s[13..].bytes().all(|x| x.is_ascii_digit()) {
+            return Err(invalid("invalid strike"));
+        }
+        let strike: u32 = s[13..].parse().map_err(|_| invalid("invalid strike"))?;
+
+        Ok(Self {
+            underlying: underlying.to_string(),
+            expiration,
+            put_call,
+            strike: f64::from(strike) / 1000.0,
+        })
+    }
+}
+
+impl std::fmt::Display for OptionSymbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_schwab_symbol())
+    }
+}
+
+impl From<OptionSymbol> for String {
+    fn from(value: OptionSymbol) -> Self {
+        value.to_schwab_symbol()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use float_cmp::assert_approx_eq;
+
+    #[test]
+    fn test_round_trip() {
+        for (symbol, underlying, put_call, strike) in [
+            ("AAPL  240517C00100000", "AAPL", PutCall::Call, 100.0),
+            ("AAPL  240517P00157500", "AAPL", PutCall::Put, 157.5),
+            ("F     240517C00012500", "F", PutCall::Call, 12.5),
+            ("SPXW  240517P05302125", "SPXW", PutCall::Put, 5302.125),
+            ("GOOGL 240517C00000500", "GOOGL", PutCall::Call, 0.5),
+        ] {
+            let val = OptionSymbol::parse(symbol).unwrap();
+            assert_eq!(val.underlying, underlying);
+            assert_eq!(
+                val.expiration,
+                NaiveDate::from_ymd_opt(2024, 5, 17).unwrap()
+            );
+            assert_eq!(val.put_call, put_call);
+            assert_approx_eq!(f64, val.strike, strike);
+            assert_eq!(val.to_schwab_symbol(), symbol);
+        }
+
+        let val = OptionSymbol {
+            underlying: "AAPL".to_string(),
+            expiration: NaiveDate::from_ymd_opt(2024, 12, 20).unwrap(),
+            put_call: PutCall::Put,
+            // floating point noise is rounded away
+            strike: 0.1 + 0.2,
+        };
+        assert_eq!(val.to_schwab_symbol(), "AAPL  241220P00000300");
+    }
+
+    #[test]
+    fn test_new() {
+        let expiration = NaiveDate::from_ymd_opt(2024, 5, 17).unwrap();
+        let val = OptionSymbol::new("AAPL", expiration, PutCall::Call, 100.0).unwrap();
+        assert_eq!(val.to_string(), "AAPL  240517C00100000");
+        assert_eq!(String::from(val.clone()), "AAPL  240517C00100000");
+        assert_eq!(OptionSymbol::parse(&val.to_string()).unwrap(), val);
+        assert_eq!(val.underlying(), "AAPL");
+        assert_eq!(val.expiry(), expiration);
+        assert_approx_eq!(f64, val.strike(), 100.0);
+        assert!(val.is_call());
+
+        let val = OptionSymbol::new("SPXW", expiration, PutCall::Put, 5302.125).unwrap();
+        assert_eq!(val.to_string(), "SPXW  240517P05302125");
+        assert!(!val.is_call());
+
+        for (underlying, strike) in [
+            ("", 100.0),
+            ("TOOLONG", 100.0),
+            ("BRK B", 100.0),
+            ("AAPL", 0.0),
+            ("AAPL", -1.0),
+            ("AAPL", 100_000.0),
+            ("AAPL", f64::NAN),
+        ] {
+            assert!(
+                matches!(
+                    OptionSymbol::new(underlying, expiration, PutCall::Call, strike),
+                    Err(Error::InvalidOptionSymbol(_))
+                ),
+                "{underlying} {strike}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for symbol in [
+            "",
+            "AAPL240517C00100000",
+            "      240517C00100000",
+            "AAPL  241317C00100000",
+            "AAPL  240517X00100000",
+            "AAPL  240517C0010000A",
+            "AAPL  240517C+0100000",
+            "AAPLé 40517C00100000",
+        ] {
+            assert!(
+                matches!(
+                    OptionSymbol::parse(symbol),
+                    Err(Error::InvalidOptionSymbol(_))
+                ),
+                "{symbol}"
+            );
+        }
+    }
+}
@@ -34,6 +34,27 @@ pub enum QuoteResponse {
 }
 
 impl QuoteResponse {
+    /// The quote of any asset type, for code handling them alike, `None` for bonds
+    #[must_use]
+    pub fn as_quote_data(&self) -> Option<&dyn QuoteData> {
+        match self {
+            QuoteResponse::Bond(_) => None,
+            QuoteResponse::Equity(x) => Some(x.as_ref()),
+            QuoteResponse::Forex(x) => Some(x),
+            QuoteResponse::Future(x) => Some(x),
+            QuoteResponse::FutureOption(x) => Some(x),
+            QuoteResponse::Index(x) => Some(x),
+            QuoteResponse::MutualFund(x) => Some(x),
+            QuoteResponse::Option(x) => Some(x.as_ref()),
+        }
+    }
+
+    /// Returns the mark price, `None` for bonds, indexes and mutual funds
+    #[must_use]
+    pub fn mark(&self) -> Option<f64> {
+        self.as_quote_data()?.mark()
+    }
+
     /// Returns the symbol of the quote
     #[must_use]
     pub fn symbol(&self) -> &str {
@@ -136,6 +157,13 @@ impl QuoteResponse {
         }
     }
 
+    /// Returns the current best (bid, ask) prices, `None` for bonds
+    #[must_use]
+    pub fn bid_ask(&self) -> Option<(f64, f64)> {
+        let data = self.as_quote_data()?;
+        data.bid_price().zip(data.ask_price())
+    }
+
     /// Returns the number of shares for bid
     #[must_use]
     pub fn bid_size(&self) -> Option<i64> {
@@ -205,7 +233,7 @@ impl QuoteResponse {
             QuoteResponse::Future(x) => Some(x.quote.last_price),
             QuoteResponse::FutureOption(x) => Some(x.quote.last_price),
             QuoteResponse::Index(x) => Some(x.quote.last_price),
-            QuoteResponse::MutualFund(_) => None,
+            QuoteResponse::MutualFund(x) => x.quote.last_price,
             QuoteResponse::Option(x) => Some(x.quote.last_price),
         }
     }
@@ -254,6 +282,21 @@ impl QuoteResponse {
         }
     }
 
+    /// Returns the net percentage change, `None` for bonds
+    #[must_use]
+    pub fn net_percent_change(&self) -> Option<f64> {
+        match self {
+            QuoteResponse::Bond(_) => None,
+            QuoteResponse::Equity(x) => x.quote.net_percent_change,
+            QuoteResponse::Forex(x) => Some(x.quote.net_percent_change),
+            QuoteResponse::Future(x) => Some(x.quote.future_percent_change),
+            QuoteResponse::FutureOption(x) => Some(x.quote.net_percent_change),
+            QuoteResponse::Index(x) => Some(x.quote.net_percent_change),
+            QuoteResponse::MutualFund(x) => Some(x.quote.net_percent_change),
+            QuoteResponse::Option(x) => Some(x.quote.net_percent_change),
+        }
+    }
+
     /// Returns the day's open trade price
     #[must_use]
     pub fn open_price(&self) -> Option<f64> {
@@ -314,6 +357,188 @@ impl QuoteResponse {
     }
 }
 
+/// The fields shared by the quotes of every asset type, see [`QuoteResponse::as_quote_data`].
+///
+/// The prices missing from an asset type, e.g. the bid and ask of an index, are `None`.
+pub trait QuoteData {
+    fn symbol(&self) -> &str;
+
+    fn bid_price(&self) -> Option<f64> {
+        None
+    }
+
+    fn ask_price(&self) -> Option<f64> {
+        None
+    }
+
+    fn last_price(&self) -> Option<f64>;
+
+    fn total_volume(&self) -> Option<u64>;
+
+    fn mark(&self) -> Option<f64> {
+        None
+    }
+}
+
+impl QuoteData for equity::EquityResponse {
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    fn bid_price(&self) -> Option<f64> {
+        Some(self.quote.bid_price)
+    }
+
+    fn ask_price(&self) -> Option<f64> {
+        Some(self.quote.ask_price)
+    }
+
+    fn last_price(&self) -> Option<f64> {
+        Some(self.quote.last_price)
+    }
+
+    fn total_volume(&self) -> Option<u64> {
+        Some(self.quote.total_volume)
+    }
+
+    fn mark(&self) -> Option<f64> {
+        Some(self.quote.mark)
+    }
+}
+
+impl QuoteData for forex::ForexResponse {
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    fn bid_price(&self) -> Option<f64> {
+        Some(self.quote.bid_price)
+    }
+
+    fn ask_price(&self) -> Option<f64> {
+        Some(self.quote.ask_price)
+    }
+
+    fn last_price(&self) -> Option<f64> {
+        Some(self.quote.last_price)
+    }
+
+    fn total_volume(&self) -> Option<u64> {
+        Some(self.quote.total_volume)
+    }
+
+    fn mark(&self) -> Option<f64> {
+        Some(self.quote.mark)
+    }
+}
+
+impl QuoteData for future::FutureResponse {
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    fn bid_price(&self) -> Option<f64> {
+        Some(self.quote.bid_price)
+    }
+
+    fn ask_price(&self) -> Option<f64> {
+        Some(self.quote.ask_price)
+    }
+
+    fn last_price(&self) -> Option<f64> {
+        Some(self.quote.last_price)
+    }
+
+    fn total_volume(&self) -> Option<u64> {
+        Some(self.quote.total_volume)
+    }
+
+    fn mark(&self) -> Option<f64> {
+        Some(self.quote.mark)
+    }
+}
+
+impl QuoteData for option::OptionResponse {
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    fn bid_price(&self) -> Option<f64> {
+        Some(self.quote.bid_price)
+    }
+
+    fn ask_price(&self) -> Option<f64> {
+        Some(self.quote.ask_price)
+    }
+
+    fn last_price(&self) -> Option<f64> {
+        Some(self.quote.last_price)
+    }
+
+    fn total_volume(&self) -> Option<u64> {
+        Some(self.quote.total_volume)
+    }
+
+    fn mark(&self) -> Option<f64> {
+        Some(self.quote.mark)
+    }
+}
+
+impl QuoteData for future_option::FutureOptionResponse {
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    fn bid_price(&self) -> Option<f64> {
+        Some(self.quote.bid_price)
+    }
+
+    fn ask_price(&self) -> Option<f64> {
+        Some(self.quote.ask_price)
+    }
+
+    fn last_price(&self) -> Option<f64> {
+        Some(self.quote.last_price)
+    }
+
+    fn total_volume(&self) -> Option<u64> {
+        Some(self.quote.total_volume)
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn mark(&self) -> Option<f64> {
+        Some(self.quote.mark as f64)
+    }
+}
+
+impl QuoteData for index::IndexResponse {
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    fn last_price(&self) -> Option<f64> {
+        Some(self.quote.last_price)
+    }
+
+    fn total_volume(&self) -> Option<u64> {
+        Some(self.quote.total_volume)
+    }
+}
+
+impl QuoteData for mutual_fund::MutualFundResponse {
+    fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    fn last_price(&self) -> Option<f64> {
+        self.quote.last_price
+    }
+
+    fn total_volume(&self) -> Option<u64> {
+        self.quote.total_volume
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -393,5 +618,115 @@ mod tests {
             result.trade_time()
         );
         assert_eq!(41_282_925, result.total_volume().unwrap());
+        assert_eq!(Some((189.9, 189.92)), result.bid_ask());
+        assert_approx_eq!(f64, 189.9, result.mark().unwrap());
+    }
+
+    #[test]
+    fn test_quote_data() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/QuoteResponse_real.json"
+        ));
+        let val = serde_json::from_str::<QuoteResponseMap>(json).unwrap();
+
+        for (symbol, quote) in &val.responses {
+            let data = quote.as_quote_data().unwrap();
+            assert_eq!(symbol, data.symbol());
+            assert_eq!(quote.bid_price(), data.bid_price());
+            assert_eq!(quote.ask_price(), data.ask_price());
+            assert_eq!(quote.last_price(), data.last_price());
+            assert_eq!(quote.total_volume(), data.total_volume());
+        }
+
+        let data = val.responses["EUR/USD"].as_quote_data().unwrap();
+        assert_approx_eq!(f64, 1.08693, data.mark().unwrap());
+        let data = val.responses["$SPX"].as_quote_data().unwrap();
+        assert_eq!(None, data.bid_price());
+        assert_eq!(None, data.mark());
+
+        // bonds carry no quote data
+        let bond = QuoteResponse::Bond("912797GL5".to_string());
+        assert!(bond.as_quote_data().is_none());
+        assert_eq!(None, bond.mark());
+        assert_eq!(None, bond.bid_ask());
+        assert_eq!(None, bond.net_percent_change());
+    }
+
+    #[test]
+    fn test_methods_index() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/QuoteResponse/IndexResponse.json"
+        ));
+
+        let mut val = serde_json::from_str::<HashMap<String, QuoteResponse>>(json).unwrap();
+
+        let result = val.remove("$SPX").unwrap();
+        let QuoteResponse::Index(ref index) = result else {
+            panic!("expected an index quote, got {result:?}");
+        };
+        assert_eq!(
+            "S&P DOW JONES INDEX            S&P 500",
+            index.reference.description
+        );
+        assert_eq!("$SPX", result.symbol());
+        assert_approx_eq!(f64, 4423.46, result.n52week_high().unwrap());
+        assert_approx_eq!(f64, 4385.52, result.n52week_low().unwrap());
+        assert_approx_eq!(f64, 4766.18, result.close_price());
+        assert_approx_eq!(f64, 4423.46, result.high_price().unwrap());
+        assert_approx_eq!(f64, 4396.2, result.last_price().unwrap());
+        assert_approx_eq!(f64, 4385.52, result.low_price().unwrap());
+        assert_approx_eq!(f64, -369.98, result.net_change());
+        assert_approx_eq!(
+            f64,
+            -7.762_610_728_088_33,
+            result.net_percent_change().unwrap()
+        );
+        assert_approx_eq!(f64, 4412.61, result.open_price().unwrap());
+        assert_eq!(
+            chrono::DateTime::from_timestamp_millis(1_644_854_683_056).unwrap(),
+            result.trade_time()
+        );
+        assert_eq!(628_009_977, result.total_volume().unwrap());
+
+        // no bid/ask for an index
+        assert_eq!(None, result.ask_price());
+        assert_eq!(None, result.bid_price());
+        assert_eq!(None, result.bid_ask());
+        assert_eq!(None, result.last_size());
+        assert_eq!(None, result.quote_time());
+    }
+
+    #[test]
+    fn test_methods_mixed() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/QuoteResponse_real.json"
+        ));
+        let json: serde_json::Value = serde_json::from_str(json).unwrap();
+
+        let mut val = serde_json::from_value::<QuoteResponseMap>(json.clone()).unwrap();
+
+        let result = val.responses.remove("AAPL  240517C00100000").unwrap();
+        assert!(matches!(result, QuoteResponse::Option(_)));
+        assert_eq!("AAPL  240517C00100000", result.symbol());
+        assert_approx_eq!(f64, 89.69, result.last_price().unwrap());
+        assert_approx_eq!(f64, -0.1887, result.net_change());
+        assert_eq!(Some((89.2, 90.7)), result.bid_ask());
+
+        let result = val.responses.remove("$SPX").unwrap();
+        assert_approx_eq!(f64, 5303.27, result.last_price().unwrap());
+        assert_eq!(None, result.bid_ask());
+
+        let result = val.responses.remove("AAAIX").unwrap();
+        assert_approx_eq!(f64, 7.88, result.last_price().unwrap());
+        assert_eq!(None, result.bid_ask());
+
+        // every remaining variant is readable without matching
+        for (symbol, quote) in &val.responses {
+            assert_eq!(symbol, quote.symbol());
+            assert!(quote.last_price().is_some());
+        }
     }
 }
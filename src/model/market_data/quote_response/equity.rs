@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
-use serde_with::{serde_as, TimestampMilliSeconds};
+
+use crate::model::datetime::{iso8601_datetime, millis_datetime};
 
 /// Quote info of Equity security
 #[serde_with::apply(
@@ -40,7 +41,6 @@ pub struct EquityResponse {
 }
 
 /// Quote data for extended hours
-#[serde_as]
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ExtendedMarket {
@@ -82,7 +82,7 @@ pub struct ExtendedMarket {
     /// example: 1621368000400
     ///
     /// Extended market quote time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub quote_time: chrono::DateTime<chrono::Utc>,
 
     /// example: 12345
@@ -93,7 +93,7 @@ pub struct ExtendedMarket {
     /// example: 1621368000400
     ///
     /// Extended market trade time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub trade_time: chrono::DateTime<chrono::Utc>,
 }
 
@@ -115,6 +115,7 @@ pub struct Fundamental {
     /// pattern: yyyy-MM-dd'T'HH:mm:ssZ
     ///
     /// Declaration date in yyyy-mm-ddThh:mm:ssZ
+    #[serde(default, with = "iso8601_datetime::option")]
     pub declaration_date: Option<chrono::DateTime<chrono::Utc>>,
 
     /// example: 0.88
@@ -125,6 +126,7 @@ pub struct Fundamental {
     /// example: 2021-05-07T00:00:00Z
     ///
     /// Dividend date in yyyy-mm-ddThh:mm:ssZ
+    #[serde(default, with = "iso8601_datetime::option")]
     pub div_ex_date: Option<chrono::DateTime<chrono::Utc>>,
 
     /// nullable: true
@@ -142,6 +144,7 @@ pub struct Fundamental {
     /// pattern: yyyy-MM-dd'T'HH:mm:ssZ
     ///
     /// Dividend pay date in yyyy-mm-ddThh:mm:ssZ
+    #[serde(default, with = "iso8601_datetime::option")]
     pub div_pay_date: Option<chrono::DateTime<chrono::Utc>>,
 
     /// example: 0.7
@@ -169,6 +172,7 @@ pub struct Fundamental {
     /// pattern: yyyy-MM-dd'T'HH:mm:ssZ
     ///
     /// Next Dividend date
+    #[serde(default, with = "iso8601_datetime::option")]
     pub next_div_ex_date: Option<chrono::DateTime<chrono::Utc>>,
 
     /// example: 2021-02-12T00:00:00Z
@@ -176,6 +180,7 @@ pub struct Fundamental {
     /// pattern: yyyy-MM-dd'T'HH:mm:ssZ
     ///
     /// Next Dividend pay date
+    #[serde(default, with = "iso8601_datetime::option")]
     pub next_div_pay_date: Option<chrono::DateTime<chrono::Utc>>,
 
     /// example: 28.599
@@ -184,11 +189,11 @@ pub struct Fundamental {
     pub pe_ratio: f64,
 
     // not in schema
+    #[serde(default, with = "iso8601_datetime::option")]
     pub last_earnings_date: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 /// Quote data of Equity security
-#[serde_as]
 #[serde_with::apply(
     Option => #[serde(skip_serializing_if = "Option::is_none")],
 )]
@@ -226,7 +231,7 @@ pub struct QuoteEquity {
     /// example: 1621376892336
     ///
     /// Last ask time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub ask_time: chrono::DateTime<chrono::Utc>,
 
     /// example: XNYS
@@ -248,7 +253,7 @@ pub struct QuoteEquity {
     /// example: 1621376892336
     ///
     /// Last bid time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub bid_time: chrono::DateTime<chrono::Utc>,
 
     /// example: 126.27
@@ -313,7 +318,7 @@ pub struct QuoteEquity {
     /// example: 1621376892336
     ///
     /// Last quote time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub quote_time: chrono::DateTime<chrono::Utc>,
 
     /// example: Normal
@@ -329,7 +334,7 @@ pub struct QuoteEquity {
     /// example: 1621376731304
     ///
     /// Last trade time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub trade_time: chrono::DateTime<chrono::Utc>,
 
     /// example: 0.0094
@@ -395,7 +400,6 @@ pub struct ReferenceEquity {
 }
 
 /// Market info of security
-#[serde_as]
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RegularMarket {
@@ -426,7 +430,7 @@ pub struct RegularMarket {
     /// example: 1621368000400
     ///
     /// Regular market trade time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     #[serde(rename = "regularMarketTradeTime")]
     pub trade_time: chrono::DateTime<chrono::Utc>,
 }
@@ -1,6 +1,7 @@
 use serde::Deserialize;
 use serde::Serialize;
-use serde_with::{serde_as, TimestampMilliSeconds};
+
+use crate::model::datetime::millis_datetime;
 
 /// Quote info of Forex security
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -26,7 +27,6 @@ pub struct ForexResponse {
 }
 
 /// Quote data of Forex security
-#[serde_as]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QuoteForex {
@@ -108,7 +108,7 @@ pub struct QuoteForex {
     /// example: 1621376892336
     ///
     /// Last quote time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub quote_time: chrono::DateTime<chrono::Utc>,
 
     /// example: Normal
@@ -134,7 +134,7 @@ pub struct QuoteForex {
     /// example: 1621376731304
     ///
     /// Last trade time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub trade_time: chrono::DateTime<chrono::Utc>,
 }
 
@@ -1,6 +1,7 @@
 use serde::Deserialize;
 use serde::Serialize;
-use serde_with::{serde_as, TimestampMilliSeconds};
+
+use crate::model::datetime::millis_datetime;
 
 /// Quote info of Future security
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -25,7 +26,6 @@ pub struct FutureResponse {
 }
 
 /// Quote data of Future security
-#[serde_as]
 #[serde_with::apply(
     Option => #[serde(skip_serializing_if = "Option::is_none")],
 )]
@@ -51,7 +51,7 @@ pub struct QuoteFuture {
     /// example: 1621376892336
     ///
     /// Last ask time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub ask_time: chrono::DateTime<chrono::Utc>,
 
     /// example: XNYS
@@ -73,7 +73,7 @@ pub struct QuoteFuture {
     /// example: 1621376892336
     ///
     /// Last bid time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub bid_time: chrono::DateTime<chrono::Utc>,
 
     /// example: 4123
@@ -133,7 +133,7 @@ pub struct QuoteFuture {
     /// example: 1621427004585
     ///
     /// Last quote time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub quote_time: chrono::DateTime<chrono::Utc>,
 
     /// example: false
@@ -149,7 +149,7 @@ pub struct QuoteFuture {
     /// example: 1621376892336
     ///
     /// settlement time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub settle_time: chrono::DateTime<chrono::Utc>,
 
     /// example: 0.25
@@ -170,12 +170,11 @@ pub struct QuoteFuture {
     /// example: 1621376731304
     ///
     /// Last trade time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub trade_time: chrono::DateTime<chrono::Utc>,
 }
 
 /// Reference data of Future security
-#[serde_as]
 #[serde_with::apply(
     Option => #[serde(skip_serializing_if = "Option::is_none")],
 )]
@@ -203,7 +202,7 @@ pub struct ReferenceFuture {
     /// example: 1623988800000
     ///
     /// Future expiration date in milliseconds since epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub future_expiration_date: chrono::DateTime<chrono::Utc>,
 
     /// example: true
@@ -1,8 +1,8 @@
 use serde::Deserialize;
 use serde::Serialize;
-use serde_with::{serde_as, TimestampMilliSeconds};
 
 use super::option::ContractType;
+use crate::model::datetime::millis_datetime;
 
 /// Quote info of Future Option security
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -27,7 +27,6 @@ pub struct FutureOptionResponse {
 }
 
 /// Quote data of Option security
-#[serde_as]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QuoteFutureOption {
@@ -125,7 +124,7 @@ pub struct QuoteFutureOption {
     /// example: 1621376892336
     ///
     /// Last quote time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub quote_time: chrono::DateTime<chrono::Utc>,
 
     /// example: Normal
@@ -156,12 +155,11 @@ pub struct QuoteFutureOption {
     /// example: 1621376731304
     ///
     /// Last trade time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub trade_time: chrono::DateTime<chrono::Utc>,
 }
 
 /// Reference data of Future Option security
-#[serde_as]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ReferenceFutureOption {
@@ -187,7 +185,7 @@ pub struct ReferenceFutureOption {
     pub multiplier: f64,
 
     /// date of expiration in long
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub expiration_date: chrono::DateTime<chrono::Utc>,
 
     /// Style of expiration
@@ -1,6 +1,7 @@
 use serde::Deserialize;
 use serde::Serialize;
-use serde_with::{serde_as, TimestampMilliSeconds};
+
+use crate::model::datetime::millis_datetime;
 
 /// Quote info of Index security
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -28,7 +29,6 @@ pub struct IndexResponse {
 }
 
 /// Quote data of Index security
-#[serde_as]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QuoteIndex {
@@ -90,7 +90,7 @@ pub struct QuoteIndex {
     /// example: 1621376731304
     ///
     /// Last trade time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub trade_time: chrono::DateTime<chrono::Utc>,
 }
 
@@ -1,8 +1,8 @@
 use serde::Deserialize;
 use serde::Serialize;
-use serde_with::{serde_as, TimestampMilliSeconds};
 
 use super::equity::Fundamental;
+use crate::model::datetime::millis_datetime;
 
 /// Quote info of Mutual Fund security
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -32,7 +32,6 @@ pub struct MutualFundResponse {
 }
 
 /// Quote data of Mutual Fund security
-#[serde_as]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QuoteMutualFund {
@@ -83,7 +82,7 @@ pub struct QuoteMutualFund {
     /// example: 1621376731304
     ///
     /// Last trade time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub trade_time: chrono::DateTime<chrono::Utc>,
 
     // not in schema
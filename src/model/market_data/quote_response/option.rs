@@ -1,6 +1,7 @@
 use serde::Deserialize;
 use serde::Serialize;
-use serde_with::{serde_as, TimestampMilliSeconds};
+
+use crate::model::datetime::millis_datetime;
 
 /// Quote info of Option security
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -25,7 +26,6 @@ pub struct OptionResponse {
 }
 
 /// Quote data of Option security
-#[serde_as]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QuoteOption {
@@ -94,7 +94,7 @@ pub struct QuoteOption {
     /// example: 126.99
     ///
     /// Indicative Quote Time in milliseconds since Epoch applicable only for Indicative Option Symbols
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub ind_quote_time: chrono::DateTime<chrono::Utc>,
 
     /// example: -0.0067
@@ -158,7 +158,7 @@ pub struct QuoteOption {
     /// example: 1621376892336
     ///
     /// Last quote time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub quote_time: chrono::DateTime<chrono::Utc>,
 
     /// example: -0.3732
@@ -194,7 +194,7 @@ pub struct QuoteOption {
     /// example: 1621376731304
     ///
     /// Last trade time in milliseconds since Epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub trade_time: chrono::DateTime<chrono::Utc>,
 
     /// example: 3247.96
@@ -214,7 +214,6 @@ pub struct QuoteOption {
 }
 
 /// Reference data of Option security
-#[serde_as]
 #[serde_with::apply(
     Option => #[serde(skip_serializing_if = "Option::is_none")],
 )]
@@ -289,7 +288,7 @@ pub struct ReferenceOption {
     /// example: 1629504000000
     ///
     /// milliseconds since epoch
-    #[serde_as(as = "TimestampMilliSeconds<i64>")]
+    #[serde(with = "millis_datetime")]
     pub last_trading_day: chrono::DateTime<chrono::Utc>,
 
     /// example: 100
@@ -0,0 +1,127 @@
+//! Ticker symbols, e.g. `AAPL`, `$SPX` or `/ES`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::model::OptionSymbol;
+
+const MAX_LEN: usize = 20;
+
+/// A symbol checked to be neither empty nor longer than 20 characters.
+///
+/// The spaces padding the underlying of an option symbol, e.g. `AAPL  240517C00100000`, are not counted,
+/// and an option symbol with a 6 character root, e.g. `GOOGL1240517C00100000`, is accepted as an [`OptionSymbol`].
+///
+/// The `Api` methods taking `impl Into<Symbol>` check it again before sending, so a `String`
+/// converted without [`Symbol::try_new`] fails with [`Error::InvalidParameter`] instead of a `400` response.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Symbol(String);
+
+impl Symbol {
+    pub fn try_new(s: impl Into<String>) -> Result<Self, Error> {
+        Self(s.into()).checked()
+    }
+
+    pub(crate) fn checked(self) -> Result<Self, Error> {
+        if self.0.trim().is_empty() {
+            return Err(Error::InvalidParameter("empty symbol".to_string()));
+        }
+        if self.0.chars().filter(|x| *x != ' ').count() > MAX_LEN
+            && OptionSymbol::parse(&self.0).is_err()
+        {
+            return Err(Error::InvalidParameter(format!(
+                "symbol {:?} is longer than {MAX_LEN} characters",
+                self.0
+            )));
+        }
+
+        Ok(self)
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl From<Symbol> for String {
+    fn from(value: Symbol) -> Self {
+        value.0
+    }
+}
+
+impl AsRef<str> for Symbol {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::ops::Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol() {
+        let symbol = Symbol::try_new("AAPL").unwrap();
+        assert_eq!(symbol.to_string(), "AAPL");
+        assert_eq!(symbol.len(), 4);
+        assert_eq!(String::from(symbol), "AAPL");
+        assert!(Symbol::try_new("$SPX".to_string()).is_ok());
+        assert!(Symbol::try_new("AAPL  240517C00100000").is_ok());
+        // 6 character root, 21 characters without padding
+        assert!(Symbol::try_new("GOOGL1240517C00100000").is_ok());
+
+        assert!(matches!(
+            Symbol::try_new(""),
+            Err(Error::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            Symbol::try_new("  "),
+            Err(Error::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            Symbol::try_new("A".repeat(21)),
+            Err(Error::InvalidParameter(_))
+        ));
+        assert!(matches!(
+            Symbol::try_new("GOOGL1240517X00100000"),
+            Err(Error::InvalidParameter(_))
+        ));
+
+        // unchecked until sent
+        let symbol = Symbol::from("");
+        assert!(symbol.checked().is_err());
+
+        assert_eq!(
+            serde_json::to_string(&Symbol::from("VTI")).unwrap(),
+            r#""VTI""#
+        );
+    }
+}
@@ -5,6 +5,7 @@ pub mod accounts;
 pub mod order;
 pub mod order_request;
 pub mod preview_order;
+pub mod realized_pnl;
 pub mod service_error;
 pub mod transactions;
 pub mod user_preference;
@@ -6,8 +6,47 @@ pub type AccountNumbers = Vec<AccountNumberHash>;
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountNumberHash {
+    /// The plain account number, not accepted by the trader API.
     pub account_number: String,
-    pub hash_value: String,
+    pub hash_value: EncryptedAccountNumber,
+}
+
+/// The encrypted ID of an account, the `hashValue` of [`AccountNumberHash`], expected by every account call.
+///
+/// Use `From<String>` only for a hash value kept from an earlier call, never for the plain account number.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct EncryptedAccountNumber(String);
+
+impl EncryptedAccountNumber {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for EncryptedAccountNumber {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for EncryptedAccountNumber {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl From<EncryptedAccountNumber> for String {
+    fn from(value: EncryptedAccountNumber) -> Self {
+        value.0
+    }
+}
+
+impl std::fmt::Display for EncryptedAccountNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
 }
 
 #[cfg(test)]
@@ -25,4 +64,20 @@ mod tests {
         println!("{val:?}");
         assert!(val.is_ok());
     }
+
+    #[test]
+    fn test_encrypted_account_number() {
+        let json = r#"[{"accountNumber": "12345678", "hashValue": "ABCDEF0123456789"}]"#;
+
+        let val = serde_json::from_str::<AccountNumbers>(json).unwrap();
+        assert_eq!(
+            val[0].hash_value,
+            EncryptedAccountNumber::from("ABCDEF0123456789")
+        );
+        assert_eq!(val[0].hash_value.to_string(), "ABCDEF0123456789");
+        assert_eq!(
+            serde_json::to_value(&val).unwrap(),
+            serde_json::from_str::<serde_json::Value>(json).unwrap()
+        );
+    }
 }
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 
+use crate::model::datetime::iso8601_datetime;
+
 pub type Accounts = Vec<Account>;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -9,6 +11,59 @@ pub struct Account {
     pub securities_account: SecuritiesAccount,
 }
 
+impl Account {
+    /// Whether this is a margin account, which short sales require.
+    #[must_use]
+    pub fn is_margin(&self) -> bool {
+        matches!(self.securities_account, SecuritiesAccount::Margin(_))
+    }
+
+    /// The positions, empty unless requested with `fields=positions`.
+    #[must_use]
+    pub fn positions(&self) -> &[Position] {
+        let base = match &self.securities_account {
+            SecuritiesAccount::Margin(x) => &x.securities_account_base,
+            SecuritiesAccount::Cash(x) => &x.securities_account_base,
+        };
+        base.positions.as_deref().unwrap_or_default()
+    }
+
+    /// The market value of all positions, where short positions count negative.
+    #[must_use]
+    pub fn total_market_value(&self) -> f64 {
+        self.positions().iter().map(|x| x.market_value).sum()
+    }
+
+    /// The market value of the long positions.
+    #[must_use]
+    pub fn total_long_market_value(&self) -> f64 {
+        self.positions()
+            .iter()
+            .filter(|x| x.long_quantity > 0.0)
+            .map(|x| x.market_value)
+            .sum()
+    }
+
+    /// The market value of the short positions, negative when there are any.
+    #[must_use]
+    pub fn total_short_market_value(&self) -> f64 {
+        self.positions()
+            .iter()
+            .filter(|x| x.short_quantity > 0.0)
+            .map(|x| x.market_value)
+            .sum()
+    }
+
+    /// The open profit and loss of all long and short positions.
+    #[must_use]
+    pub fn unrealized_pnl(&self) -> f64 {
+        self.positions()
+            .iter()
+            .map(|x| x.long_open_profit_loss + x.short_open_profit_loss)
+            .sum()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "UPPERCASE")]
 pub enum SecuritiesAccount {
@@ -40,6 +95,35 @@ pub struct MarginAccount {
     pub projected_balances: Option<MarginBalance>,
 }
 
+impl MarginAccount {
+    /// Returns `currentBalances.availableFunds`
+    ///
+    /// The equity that can be used to open new positions, without margin leverage.
+    /// This is what can be spent on securities that are not marginable.
+    #[must_use]
+    pub fn available_funds(&self) -> Option<f64> {
+        self.current_balances.and_then(|b| b.available_funds)
+    }
+
+    /// Returns `currentBalances.availableFundsNonMarginableTrade`
+    ///
+    /// The funds available to buy non-marginable securities (e.g. options, OTC stocks).
+    #[must_use]
+    pub fn available_funds_non_marginable_trade(&self) -> Option<f64> {
+        self.current_balances
+            .and_then(|b| b.available_funds_non_marginable_trade)
+    }
+
+    /// Returns `currentBalances.buyingPower`
+    ///
+    /// The total amount of marginable securities that can be bought, including margin leverage.
+    /// This is usually a multiple of [`MarginAccount::available_funds`], so do not use it as the cash on hand.
+    #[must_use]
+    pub fn buying_power(&self) -> Option<f64> {
+        self.current_balances.and_then(|b| b.buying_power)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MarginInitialBalance {
@@ -167,6 +251,35 @@ pub struct CashBalance {
     pub short_option_market_value: Option<f64>,
 }
 
+/// The balances of an account, without positions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "UPPERCASE",
+    rename_all_fields = "camelCase"
+)]
+pub enum Balances {
+    Margin {
+        account_number: String,
+        initial_balances: Option<MarginInitialBalance>,
+        current_balances: Option<MarginBalance>,
+        projected_balances: Option<MarginBalance>,
+    },
+    Cash {
+        account_number: String,
+        initial_balances: Option<CashInitialBalance>,
+        current_balances: Option<CashBalance>,
+        projected_balances: Option<CashBalance>,
+    },
+}
+
+/// `/accounts/{accountNumber}` reduced to [`Balances`].
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AccountBalances {
+    pub(crate) securities_account: Balances,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Position {
@@ -205,6 +318,23 @@ pub enum AccountsInstrument {
     CollectiveInvestment(AccountCollectiveInvestment),
 }
 
+impl AccountsInstrument {
+    /// Returns the symbol of the instrument
+    #[must_use]
+    pub fn symbol(&self) -> &str {
+        match self {
+            AccountsInstrument::CashEquivalent(x) => &x.accounts_base_instrument.symbol,
+            AccountsInstrument::Equity(x) => &x.accounts_base_instrument.symbol,
+            AccountsInstrument::FixedIncome(x) => &x.accounts_base_instrument.symbol,
+            AccountsInstrument::MutualFund(x) => &x.accounts_base_instrument.symbol,
+            AccountsInstrument::Option(x) => &x.accounts_base_instrument.symbol,
+            AccountsInstrument::Index(x) => &x.accounts_base_instrument.symbol,
+            AccountsInstrument::Currency(x) => &x.accounts_base_instrument.symbol,
+            AccountsInstrument::CollectiveInvestment(x) => &x.accounts_base_instrument.symbol,
+        }
+    }
+}
+
 impl Default for AccountsInstrument {
     fn default() -> Self {
         Self::CashEquivalent(AccountCashEquivalent::default())
@@ -234,6 +364,7 @@ pub struct AccountFixedIncome {
     #[serde(flatten)]
     pub accounts_base_instrument: AccountsBaseInstrument,
 
+    #[serde(with = "iso8601_datetime")]
     pub maturity_date: chrono::DateTime<chrono::Utc>,
     pub factor: f64,
     pub variable_rate: f64,
@@ -335,6 +466,7 @@ pub enum AccountCashEquivalentType {
     SweepVehicle,
     Savings,
     MoneyMarketFund,
+    #[serde(other)]
     Unknown,
 }
 
@@ -344,6 +476,7 @@ pub enum AccountOptionPullCall {
     #[default]
     Put,
     Call,
+    #[serde(other)]
     Unknown,
 }
 
@@ -354,6 +487,7 @@ pub enum AccountOptionType {
     Vanilla,
     Binary,
     Barrier,
+    #[serde(other)]
     Unknown,
 }
 
@@ -361,6 +495,8 @@ pub enum AccountOptionType {
 mod tests {
     use super::*;
 
+    use float_cmp::assert_approx_eq;
+
     #[test]
     fn test_de_account() {
         let json = include_str!(concat!(
@@ -373,6 +509,95 @@ mod tests {
         assert!(val.is_ok());
     }
 
+    #[test]
+    fn test_is_margin() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Accounts_real.json"
+        ));
+
+        let val = serde_json::from_str::<Accounts>(json).unwrap();
+        let margin: Vec<bool> = val.iter().map(Account::is_margin).collect();
+        assert_eq!(margin, vec![false, true]);
+    }
+
+    #[test]
+    fn test_portfolio_totals() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Account_real.json"
+        ));
+        let mut json: serde_json::Value = serde_json::from_str(json).unwrap();
+
+        let val = serde_json::from_value::<Account>(json.clone()).unwrap();
+        assert_approx_eq!(f64, val.total_market_value(), 0.0);
+        assert_approx_eq!(f64, val.unrealized_pnl(), 0.0);
+
+        let position = |long: f64, short: f64, market_value: f64, pnl: f64| {
+            let mut position: serde_json::Value =
+                serde_json::from_str::<serde_json::Value>(include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/tests/model/Trader/Account.json"
+                )))
+                .unwrap()["securitiesAccount"]["positions"][0]
+                    .clone();
+            position["instrument"] = serde_json::json!({
+                "assetType": "EQUITY",
+                "cusip": "037833100",
+                "symbol": "AAPL",
+                "description": "APPLE INC",
+                "instrumentId": 1,
+            });
+            position["longQuantity"] = long.into();
+            position["shortQuantity"] = short.into();
+            position["marketValue"] = market_value.into();
+            if long > 0.0 {
+                position["longOpenProfitLoss"] = pnl.into();
+            } else {
+                position["shortOpenProfitLoss"] = pnl.into();
+            }
+            position
+        };
+        json["securitiesAccount"]["positions"] = serde_json::json!([
+            position(10.0, 0.0, 1899.0, 120.5),
+            position(5.0, 0.0, 400.0, -20.0),
+            position(0.0, 2.0, -300.0, 15.25),
+        ]);
+
+        let val = serde_json::from_value::<Account>(json).unwrap();
+        assert_approx_eq!(f64, val.total_market_value(), 1999.0);
+        assert_approx_eq!(f64, val.total_long_market_value(), 2299.0);
+        assert_approx_eq!(f64, val.total_short_market_value(), -300.0);
+        assert_approx_eq!(f64, val.unrealized_pnl(), 115.75);
+    }
+
+    #[test]
+    fn test_de_balances() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Account_real.json"
+        ));
+
+        let val = serde_json::from_str::<AccountBalances>(json).unwrap();
+        let Balances::Cash {
+            current_balances, ..
+        } = val.securities_account
+        else {
+            panic!("not a cash account");
+        };
+        assert!(current_balances.is_some());
+
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Accounts_real.json"
+        ));
+
+        let val = serde_json::from_str::<Vec<AccountBalances>>(json).unwrap();
+        assert!(val
+            .iter()
+            .any(|x| matches!(x.securities_account, Balances::Margin { .. })));
+    }
+
     #[test]
     fn test_de_accounts() {
         let json = include_str!(concat!(
@@ -396,4 +621,29 @@ mod tests {
         println!("{val:?}");
         assert!(val.is_ok());
     }
+
+    #[test]
+    fn test_margin_balance_accessors() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Accounts_real.json"
+        ));
+
+        let val = serde_json::from_str::<Accounts>(json).unwrap();
+        let margin = val
+            .into_iter()
+            .find_map(|x| match x.securities_account {
+                SecuritiesAccount::Margin(m) => Some(m),
+                SecuritiesAccount::Cash(_) => None,
+            })
+            .unwrap();
+
+        assert_approx_eq!(f64, 4596.0, margin.available_funds().unwrap());
+        assert_approx_eq!(
+            f64,
+            4596.0,
+            margin.available_funds_non_marginable_trade().unwrap()
+        );
+        assert_approx_eq!(f64, 9192.0, margin.buying_power().unwrap());
+    }
 }
@@ -4,6 +4,7 @@ use serde::Serialize;
 use crate::model::trader::accounts::AccountsInstrument;
 
 use super::preview_order::Instruction;
+use crate::model::datetime::iso8601_datetime;
 
 #[allow(clippy::struct_field_names)]
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -12,6 +13,7 @@ pub struct Order {
     pub session: Session,
     pub duration: Duration,
     pub order_type: OrderType,
+    #[serde(default, with = "iso8601_datetime::option")]
     pub cancel_time: Option<chrono::DateTime<chrono::Utc>>,
     pub complex_order_strategy_type: ComplexOrderStrategyType,
     pub quantity: f64,
@@ -19,6 +21,7 @@ pub struct Order {
     pub remaining_quantity: f64,
     pub requested_destination: RequestedDestination,
     pub destination_link_name: String,
+    #[serde(default, with = "iso8601_datetime::option")]
     pub release_time: Option<chrono::DateTime<chrono::Utc>>,
     pub stop_price: Option<f64>,
     pub stop_price_link_basis: Option<StopPriceLinkBasis>,
@@ -40,7 +43,9 @@ pub struct Order {
     /// default: false
     pub editable: bool,
     pub status: Status,
+    #[serde(with = "iso8601_datetime")]
     pub entered_time: chrono::DateTime<chrono::Utc>,
+    #[serde(default, with = "iso8601_datetime::option")]
     pub close_time: Option<chrono::DateTime<chrono::Utc>>,
     pub tag: Option<String>,
     pub account_number: i64,
@@ -53,6 +58,32 @@ pub struct Order {
     pub status_description: Option<String>,
 }
 
+impl Order {
+    /// Whether the order may still fill, see [`Status::is_open`].
+    #[must_use]
+    pub fn is_open(&self) -> bool {
+        self.status.is_open()
+    }
+
+    /// Whether the order is completely filled, i.e. [`Status::Filled`].
+    #[must_use]
+    pub fn is_filled(&self) -> bool {
+        self.status == Status::Filled
+    }
+
+    /// Whether the order is done and will not change anymore, see [`Status::is_terminal`].
+    #[must_use]
+    pub fn is_terminal(&self) -> bool {
+        self.status.is_terminal()
+    }
+
+    /// Whether Schwab accepts canceling the order: it is flagged `cancelable` and still open.
+    #[must_use]
+    pub fn is_cancelable(&self) -> bool {
+        self.cancelable && self.is_open()
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderLegCollection {
@@ -86,6 +117,7 @@ pub struct ExecutionLeg {
     pub quantity: f64,
     pub mismarked_quantity: f64,
     pub instrument_id: i64,
+    #[serde(with = "iso8601_datetime")]
     pub time: chrono::DateTime<chrono::Utc>,
 }
 
@@ -124,6 +156,7 @@ pub enum Duration {
     EndOfWeek,
     EndOfMonth,
     NextEndOfMonth,
+    #[serde(other)]
     Unknown,
 }
 
@@ -174,6 +207,7 @@ pub enum OrderType {
     /// `More info <https://www.investopedia.com/ask/answers/042215/whats-difference-between-credit-spread-and-debt-spread.asp>`__
     NetZero,
     LimitOnClose,
+    #[serde(other)]
     Unknown,
 }
 
@@ -371,9 +405,34 @@ pub enum Status {
     AwaitingReleaseTime,
     PendingAcknowledgement,
     PendingRecall,
+    #[serde(other)]
     Unknown,
 }
 
+impl Status {
+    /// Whether the order is done and will not change anymore.
+    #[must_use]
+    pub fn is_terminal(self) -> bool {
+        matches!(
+            self,
+            Status::Filled
+                | Status::Canceled
+                | Status::Rejected
+                | Status::Expired
+                | Status::Replaced
+        )
+    }
+
+    /// Whether the order may still fill: every status but the terminal ones and [`Status::Unknown`].
+    ///
+    /// This includes the statuses waiting on something, e.g. [`Status::AwaitingParentOrder`],
+    /// [`Status::PendingActivation`] or [`Status::Queued`], and the pending cancels and replacements.
+    #[must_use]
+    pub fn is_open(self) -> bool {
+        !self.is_terminal() && self != Status::Unknown
+    }
+}
+
 #[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ActivityType {
@@ -447,6 +506,73 @@ pub enum DivCapGains {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_de_unknown_variant() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Order_real.json"
+        ));
+        let mut json: serde_json::Value = serde_json::from_str(json).unwrap();
+        json["orderType"] = "NEW_ORDER_TYPE".into();
+        json["status"] = "NEW_STATUS".into();
+        json["duration"] = "NEW_DURATION".into();
+
+        let val = serde_json::from_value::<Order>(json).unwrap();
+        assert_eq!(val.order_type, OrderType::Unknown);
+        assert_eq!(val.status, Status::Unknown);
+        assert_eq!(val.duration, Duration::Unknown);
+
+        // still serialized as UNKNOWN
+        assert_eq!(
+            serde_json::to_value(Status::Unknown).unwrap(),
+            serde_json::json!("UNKNOWN")
+        );
+        assert_eq!(
+            serde_json::from_str::<Status>(r#""UNKNOWN""#).unwrap(),
+            Status::Unknown
+        );
+    }
+
+    #[test]
+    fn test_status_helpers() {
+        // (status, open, terminal)
+        for (status, open, terminal) in [
+            (Status::AwaitingParentOrder, true, false),
+            (Status::AwaitingCondition, true, false),
+            (Status::AwaitingStopCondition, true, false),
+            (Status::AwaitingManualReview, true, false),
+            (Status::Accepted, true, false),
+            (Status::AwaitingUrOut, true, false),
+            (Status::PendingActivation, true, false),
+            (Status::Queued, true, false),
+            (Status::Working, true, false),
+            (Status::Rejected, false, true),
+            (Status::PendingCancel, true, false),
+            (Status::Canceled, false, true),
+            (Status::PendingReplace, true, false),
+            (Status::Replaced, false, true),
+            (Status::Filled, false, true),
+            (Status::Expired, false, true),
+            (Status::New, true, false),
+            (Status::AwaitingReleaseTime, true, false),
+            (Status::PendingAcknowledgement, true, false),
+            (Status::PendingRecall, true, false),
+            (Status::Unknown, false, false),
+        ] {
+            for cancelable in [true, false] {
+                let order = Order {
+                    cancelable,
+                    status,
+                    ..Order::default()
+                };
+                assert_eq!(order.is_open(), open, "{status:?}");
+                assert_eq!(order.is_terminal(), terminal, "{status:?}");
+                assert_eq!(order.is_filled(), status == Status::Filled, "{status:?}");
+                assert_eq!(order.is_cancelable(), cancelable && open, "{status:?}");
+            }
+        }
+    }
+
     #[test]
     fn test_de_order() {
         let json = include_str!(concat!(
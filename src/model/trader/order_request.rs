@@ -4,6 +4,7 @@ use serde::Serialize;
 use serde_with::skip_serializing_none;
 
 use super::accounts::AccountsInstrument;
+use super::accounts::AssetType;
 use super::order::ComplexOrderStrategyType;
 use super::order::Duration;
 use super::order::Order;
@@ -13,6 +14,7 @@ use super::order::OrderStrategyType;
 use super::order::OrderType;
 use super::order::PriceLinkBasis;
 use super::order::PriceLinkType;
+use super::order::QuantityType;
 use super::order::Session;
 use super::order::SpecialInstruction;
 use super::order::Status;
@@ -21,6 +23,7 @@ use super::order::StopPriceLinkType;
 use super::order::StopType;
 use super::order::TaxLotMethod;
 use super::preview_order::Instruction;
+use crate::model::datetime::iso8601_datetime;
 use crate::model::market_data::instrument::InstrumentAssetType;
 use crate::model::InstrumentResponse;
 use crate::Error;
@@ -28,18 +31,20 @@ use crate::Error;
 /// More Info in [Charles Schwab Developer Portal](https://developer.schwab.com/) : API Products -> Trader API - Individual -> Accounts and Trading Production -> Documentation -> Place Order Samples
 #[skip_serializing_none]
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, Builder)]
-#[builder(setter(strip_option), default)]
+#[builder(setter(strip_option), default, build_fn(validate = "Self::validate"))]
 #[serde(rename_all = "camelCase")]
 pub struct OrderRequest {
     pub session: Option<Session>,
     pub duration: Option<Duration>,
     pub order_type: Option<OrderTypeRequest>,
+    #[serde(default, with = "iso8601_datetime::option")]
     pub cancel_time: Option<chrono::DateTime<chrono::Utc>>,
     pub complex_order_strategy_type: Option<ComplexOrderStrategyType>,
     pub quantity: Option<f64>,
     pub filled_quantity: Option<f64>,
     pub remaining_quantity: Option<f64>,
     pub destination_link_name: Option<String>,
+    #[serde(default, with = "iso8601_datetime::option")]
     pub release_time: Option<chrono::DateTime<chrono::Utc>>,
     pub stop_price: Option<f64>,
     pub stop_price_link_basis: Option<StopPriceLinkBasis>,
@@ -50,7 +55,7 @@ pub struct OrderRequest {
     pub price_link_type: Option<PriceLinkType>,
     pub price: Option<f64>,
     pub tax_lot_method: Option<TaxLotMethod>,
-    /// xml: OrderedMap { "name": "orderLegCollection", "wrapped": true }
+    /// xml: `OrderedMap` { "name": "orderLegCollection", "wrapped": true }
     pub order_leg_collection: Option<Vec<OrderLegCollectionRequest>>,
     pub activation_price: Option<f64>,
     pub special_instruction: Option<SpecialInstruction>,
@@ -61,14 +66,16 @@ pub struct OrderRequest {
     /// default: false
     pub editable: Option<bool>,
     pub status: Option<Status>,
+    #[serde(default, with = "iso8601_datetime::option")]
     pub entered_time: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default, with = "iso8601_datetime::option")]
     pub close_time: Option<chrono::DateTime<chrono::Utc>>,
     pub account_number: Option<i64>,
-    /// xml: OrderedMap { "name": "orderActivity", "wrapped": true }
+    /// xml: `OrderedMap` { "name": "orderActivity", "wrapped": true }
     pub order_activity_collection: Option<Vec<OrderActivity>>,
-    /// xml: OrderedMap { "name": "replacingOrder", "wrapped": true }
+    /// xml: `OrderedMap` { "name": "replacingOrder", "wrapped": true }
     pub replacing_order_collection: Option<Vec<String>>,
-    /// xml: OrderedMap { "name": "childOrder", "wrapped": true }
+    /// xml: `OrderedMap` { "name": "childOrder", "wrapped": true }
     pub child_order_strategies: Option<Vec<OrderRequest>>,
     pub status_description: Option<String>,
 }
@@ -78,7 +85,8 @@ impl From<Order> for OrderRequest {
         Self {
             session: Some(value.session),
             duration: Some(value.duration),
-            order_type: Some(value.order_type.into()),
+            // an order type unknown to this crate has no request counterpart
+            order_type: value.order_type.try_into().ok(),
             cancel_time: value.cancel_time,
             complex_order_strategy_type: Some(value.complex_order_strategy_type),
             quantity: Some(value.quantity),
@@ -132,6 +140,7 @@ impl OrderRequest {
         let order_leg_collection = vec![OrderLegCollectionRequest {
             instruction,
             quantity,
+            quantity_type: None,
             instrument: symbol,
         }];
         OrderRequestBuilder::default()
@@ -144,6 +153,19 @@ impl OrderRequest {
             .map_err(Error::OrderRequestBuild)
     }
 
+    /// Create a market order for `amount` dollars worth of `symbol`, which may buy fractional shares.
+    pub fn market_dollars(
+        symbol: InstrumentRequest,
+        instruction: Instruction,
+        amount: f64,
+    ) -> Result<Self, Error> {
+        let mut order = Self::market(symbol, instruction, amount)?;
+        for leg in order.order_leg_collection.iter_mut().flatten() {
+            leg.quantity_type = Some(QuantityType::Dollars);
+        }
+        Ok(order)
+    }
+
     /// Create a limit order.
     pub fn limit(
         symbol: InstrumentRequest,
@@ -154,6 +176,7 @@ impl OrderRequest {
         let order_leg_collection = vec![OrderLegCollectionRequest {
             instruction,
             quantity,
+            quantity_type: None,
             instrument: symbol,
         }];
         OrderRequestBuilder::default()
@@ -167,6 +190,406 @@ impl OrderRequest {
             .build()
             .map_err(Error::OrderRequestBuild)
     }
+
+    /// Create a stop order.
+    ///
+    /// `stop_price` must be positive.
+    pub fn stop(
+        symbol: InstrumentRequest,
+        instruction: Instruction,
+        quantity: f64,
+        stop_price: f64,
+    ) -> Result<Self, Error> {
+        check_positive("stopPrice", stop_price)?;
+        let order_leg_collection = vec![OrderLegCollectionRequest {
+            instruction,
+            quantity,
+            quantity_type: None,
+            instrument: symbol,
+        }];
+        OrderRequestBuilder::default()
+            .order_type(OrderTypeRequest::Stop)
+            .session(Session::Normal)
+            .stop_price(stop_price)
+            .duration(Duration::Day)
+            .order_strategy_type(OrderStrategyType::Single)
+            .order_leg_collection(order_leg_collection)
+            .build()
+            .map_err(Error::OrderRequestBuild)
+    }
+
+    /// Create a stop-limit order, which becomes a limit order at `price` once `stop_price` is reached.
+    ///
+    /// Both prices must be positive.
+    pub fn stop_limit(
+        symbol: InstrumentRequest,
+        instruction: Instruction,
+        quantity: f64,
+        stop_price: f64,
+        price: f64,
+    ) -> Result<Self, Error> {
+        check_positive("stopPrice", stop_price)?;
+        check_positive("price", price)?;
+        let order_leg_collection = vec![OrderLegCollectionRequest {
+            instruction,
+            quantity,
+            quantity_type: None,
+            instrument: symbol,
+        }];
+        OrderRequestBuilder::default()
+            .order_type(OrderTypeRequest::StopLimit)
+            .session(Session::Normal)
+            .stop_price(stop_price)
+            .price(price)
+            .duration(Duration::Day)
+            .order_strategy_type(OrderStrategyType::Single)
+            .order_leg_collection(order_leg_collection)
+            .build()
+            .map_err(Error::OrderRequestBuild)
+    }
+
+    /// Create a trailing stop order, trailing `stop_price_offset` away from the last price,
+    /// where `stop_price_link_type` tells whether the offset is a value, a percentage or ticks.
+    ///
+    /// Set `stop_price_link_basis` on the result to trail another price, e.g. the bid.
+    pub fn trailing_stop(
+        symbol: InstrumentRequest,
+        instruction: Instruction,
+        quantity: f64,
+        stop_price_link_type: StopPriceLinkType,
+        stop_price_offset: f64,
+    ) -> Result<Self, Error> {
+        check_positive("stopPriceOffset", stop_price_offset)?;
+        let order_leg_collection = vec![OrderLegCollectionRequest {
+            instruction,
+            quantity,
+            quantity_type: None,
+            instrument: symbol,
+        }];
+        OrderRequestBuilder::default()
+            .complex_order_strategy_type(ComplexOrderStrategyType::None)
+            .order_type(OrderTypeRequest::TrailingStop)
+            .session(Session::Normal)
+            .stop_price_link_basis(StopPriceLinkBasis::Last)
+            .stop_price_link_type(stop_price_link_type)
+            .stop_price_offset(stop_price_offset)
+            .duration(Duration::Day)
+            .order_strategy_type(OrderStrategyType::Single)
+            .order_leg_collection(order_leg_collection)
+            .build()
+            .map_err(Error::OrderRequestBuild)
+    }
+
+    /// Create a trailing stop-limit order, which becomes a limit order `limit_offset` dollars
+    /// away from the stop price once triggered. See [`OrderRequest::trailing_stop`] for the stop.
+    ///
+    /// Both offsets must be positive.
+    pub fn trailing_stop_limit(
+        symbol: InstrumentRequest,
+        instruction: Instruction,
+        quantity: f64,
+        stop_price_link_type: StopPriceLinkType,
+        stop_price_offset: f64,
+        limit_offset: f64,
+    ) -> Result<Self, Error> {
+        check_positive("price", limit_offset)?;
+        let mut order = Self::trailing_stop(
+            symbol,
+            instruction,
+            quantity,
+            stop_price_link_type,
+            stop_price_offset,
+        )?;
+        order.order_type = Some(OrderTypeRequest::TrailingStopLimit);
+        order.price_link_basis = Some(PriceLinkBasis::Trigger);
+        order.price_link_type = Some(PriceLinkType::Value);
+        order.price = Some(limit_offset);
+        Ok(order)
+    }
+
+    /// Create an order exercising a long option position.
+    ///
+    /// Exercising closes the held contracts, so the leg is `SELL_TO_CLOSE`.
+    pub fn exercise(option_symbol: String, quantity: f64) -> Result<Self, Error> {
+        let order_leg_collection = vec![OrderLegCollectionRequest {
+            instruction: Instruction::SellToClose,
+            quantity,
+            quantity_type: None,
+            instrument: InstrumentRequest::Option {
+                symbol: option_symbol,
+            },
+        }];
+        OrderRequestBuilder::default()
+            .order_type(OrderTypeRequest::Exercise)
+            .session(Session::Normal)
+            .duration(Duration::Day)
+            .order_strategy_type(OrderStrategyType::Single)
+            .order_leg_collection(order_leg_collection)
+            .build()
+            .map_err(Error::OrderRequestBuild)
+    }
+
+    /// Create a market order closing positions with the `FLATTEN` strategy.
+    ///
+    /// Every leg must close a position: `SELL`, `BUY_TO_COVER`, `SELL_TO_CLOSE` or `BUY_TO_CLOSE`.
+    pub fn flatten(legs: Vec<OrderLegCollectionRequest>) -> Result<Self, Error> {
+        if legs.is_empty() {
+            return Err(Error::OrderRequestBuild(
+                OrderRequestBuilderError::ValidationError(
+                    "FLATTEN needs at least one leg".to_string(),
+                ),
+            ));
+        }
+        if let Some(leg) = legs.iter().find(|leg| {
+            !matches!(
+                leg.instruction,
+                Instruction::Sell
+                    | Instruction::BuyToCover
+                    | Instruction::SellToClose
+                    | Instruction::BuyToClose
+            )
+        }) {
+            return Err(Error::OrderRequestBuild(
+                OrderRequestBuilderError::ValidationError(format!(
+                    "FLATTEN cannot {:?}",
+                    leg.instruction
+                )),
+            ));
+        }
+
+        OrderRequestBuilder::default()
+            .order_type(OrderTypeRequest::Market)
+            .session(Session::Normal)
+            .duration(Duration::Day)
+            .order_strategy_type(OrderStrategyType::Flatten)
+            .order_leg_collection(legs)
+            .build()
+            .map_err(Error::OrderRequestBuild)
+    }
+
+    /// Create a One-Cancels-Other order: once one of them fills, the other is cancelled.
+    ///
+    /// Neither child may be an `OCO` or `TRIGGER` order itself.
+    pub fn oco(first: OrderRequest, second: OrderRequest) -> Result<Self, Error> {
+        for child in [&first, &second] {
+            if matches!(
+                child.order_strategy_type,
+                OrderStrategyType::Oco | OrderStrategyType::Trigger
+            ) {
+                return Err(Error::OrderRequestBuild(
+                    OrderRequestBuilderError::ValidationError(format!(
+                        "OCO cannot nest a {:?} order",
+                        child.order_strategy_type
+                    )),
+                ));
+            }
+        }
+
+        OrderRequestBuilder::default()
+            .order_strategy_type(OrderStrategyType::Oco)
+            .child_order_strategies(vec![first, second])
+            .build()
+            .map_err(Error::OrderRequestBuild)
+    }
+
+    /// Create a One-Triggers-Another order: `on_fill` is submitted once `primary` fills.
+    ///
+    /// `primary` keeps its legs and pricing; an `OCO` order cannot be the primary.
+    pub fn trigger(mut primary: OrderRequest, on_fill: OrderRequest) -> Result<Self, Error> {
+        if primary.order_strategy_type == OrderStrategyType::Oco {
+            return Err(Error::OrderRequestBuild(
+                OrderRequestBuilderError::ValidationError(
+                    "TRIGGER cannot be fired by an OCO order".to_string(),
+                ),
+            ));
+        }
+
+        primary.order_strategy_type = OrderStrategyType::Trigger;
+        primary.child_order_strategies = Some(vec![on_fill]);
+        Ok(primary)
+    }
+
+    /// Create a bracket order: a limit entry at `entry_limit_price` which, once filled, triggers an OCO
+    /// of a limit exit at `profit_target_price` and a stop exit at `stop_loss_price`, both good till cancel.
+    ///
+    /// `instruction` opens the position, e.g. `BUY` or `SELL_SHORT`, and the exits close it.
+    /// The profit target must be beyond the entry and the stop loss on the other side.
+    pub fn bracket(
+        entry_instrument: InstrumentRequest,
+        instruction: Instruction,
+        quantity: f64,
+        entry_limit_price: f64,
+        profit_target_price: f64,
+        stop_loss_price: f64,
+    ) -> Result<Self, Error> {
+        let (exit_instruction, long) = match instruction {
+            Instruction::Buy => (Instruction::Sell, true),
+            Instruction::BuyToOpen => (Instruction::SellToClose, true),
+            Instruction::SellShort => (Instruction::BuyToCover, false),
+            Instruction::SellToOpen => (Instruction::BuyToClose, false),
+            _ => {
+                return Err(Error::OrderRequestBuild(
+                    OrderRequestBuilderError::ValidationError(format!(
+                        "bracket cannot open with {instruction:?}"
+                    )),
+                ))
+            }
+        };
+        let ordered = if long {
+            stop_loss_price < entry_limit_price && entry_limit_price < profit_target_price
+        } else {
+            profit_target_price < entry_limit_price && entry_limit_price < stop_loss_price
+        };
+        if !ordered {
+            return Err(Error::OrderRequestBuild(
+                OrderRequestBuilderError::ValidationError(format!(
+                    "bracket needs the entry {entry_limit_price} between the profit target {profit_target_price} and the stop loss {stop_loss_price}"
+                )),
+            ));
+        }
+
+        let entry = Self::limit(
+            entry_instrument.clone(),
+            instruction,
+            quantity,
+            entry_limit_price,
+        )?;
+        let mut profit_target = Self::limit(
+            entry_instrument.clone(),
+            exit_instruction,
+            quantity,
+            profit_target_price,
+        )?;
+        profit_target.duration = Some(Duration::GoodTillCancel);
+        let mut stop_loss = Self::stop(
+            entry_instrument,
+            exit_instruction,
+            quantity,
+            stop_loss_price,
+        )?;
+        stop_loss.duration = Some(Duration::GoodTillCancel);
+
+        Self::trigger(entry, Self::oco(profit_target, stop_loss)?)
+    }
+
+    /// Whether the order, or one of its child orders, needs a margin account, i.e. sells short.
+    #[must_use]
+    pub fn requires_margin(&self) -> bool {
+        self.order_leg_collection
+            .iter()
+            .flatten()
+            .any(|leg| leg.instruction == Instruction::SellShort)
+            || self
+                .child_order_strategies
+                .iter()
+                .flatten()
+                .any(OrderRequest::requires_margin)
+    }
+
+    /// Set the price at which a conditional order becomes active.
+    ///
+    /// Only meaningful with stop-type orders: `STOP`, `STOP_LIMIT`, `TRAILING_STOP` and `TRAILING_STOP_LIMIT`.
+    pub fn set_activation_price(&mut self, val: f64) -> Result<&mut Self, Error> {
+        match self.order_type {
+            Some(
+                OrderTypeRequest::Stop
+                | OrderTypeRequest::StopLimit
+                | OrderTypeRequest::TrailingStop
+                | OrderTypeRequest::TrailingStopLimit,
+            ) => {}
+            x => {
+                return Err(Error::InvalidActivationPrice(format!(
+                    "not supported with order type {x:?}"
+                )))
+            }
+        }
+        if !val.is_finite() || val <= 0.0 {
+            return Err(Error::InvalidActivationPrice(format!(
+                "{val} is not a positive price"
+            )));
+        }
+
+        self.activation_price = Some(val);
+        Ok(self)
+    }
+
+    /// Compare the fields set in this request with the ones Schwab recorded.
+    ///
+    /// Fields only present in `recorded` (e.g. `orderId`, `status`) are ignored, also in the legs,
+    /// where Schwab fills in e.g. `quantityType`.
+    /// An `EQUITY` instrument that Schwab recorded as `COLLECTIVE_INVESTMENT`, as it does for ETFs, is not a change.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the request can not be serialized
+    #[must_use]
+    pub fn diff(&self, recorded: &OrderRequest) -> Vec<FieldChange> {
+        let mut recorded = recorded.clone();
+        self.ignore_server_fills(&mut recorded);
+
+        let submitted = serde_json::to_value(self).expect("value");
+        let recorded = serde_json::to_value(recorded).expect("value");
+        let (serde_json::Value::Object(submitted), serde_json::Value::Object(mut recorded)) =
+            (submitted, recorded)
+        else {
+            unreachable!("OrderRequest is serialized as an object");
+        };
+
+        submitted
+            .into_iter()
+            .filter_map(|(field, submitted)| {
+                let recorded = recorded.remove(&field).unwrap_or_default();
+                (submitted != recorded).then_some(FieldChange {
+                    field,
+                    submitted,
+                    recorded,
+                })
+            })
+            .collect()
+    }
+
+    /// Align the legs and child orders of `recorded` with this request where Schwab fills in or reclassifies a field.
+    fn ignore_server_fills(&self, recorded: &mut OrderRequest) {
+        for (submitted, recorded) in self
+            .order_leg_collection
+            .iter()
+            .flatten()
+            .zip(recorded.order_leg_collection.iter_mut().flatten())
+        {
+            if submitted.quantity_type.is_none() {
+                recorded.quantity_type = None;
+            }
+            if let (
+                InstrumentRequest::Equity { symbol },
+                InstrumentRequest::CollectiveInvestment {
+                    symbol: recorded_symbol,
+                },
+            ) = (&submitted.instrument, &recorded.instrument)
+            {
+                if symbol == recorded_symbol {
+                    recorded.instrument = submitted.instrument.clone();
+                }
+            }
+        }
+
+        for (submitted, recorded) in self
+            .child_order_strategies
+            .iter()
+            .flatten()
+            .zip(recorded.child_order_strategies.iter_mut().flatten())
+        {
+            submitted.ignore_server_fills(recorded);
+        }
+    }
+}
+
+/// A field that Schwab recorded differently from what was submitted
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    /// Field name in camelCase, e.g. `price`
+    pub field: String,
+    pub submitted: serde_json::Value,
+    pub recorded: serde_json::Value,
 }
 
 /// Same as `super::order::OrderType`, but does not have UNKNOWN since this type is not allowed as an input
@@ -219,9 +642,12 @@ pub enum OrderTypeRequest {
     LimitOnClose,
 }
 
-impl From<OrderType> for OrderTypeRequest {
-    fn from(value: OrderType) -> Self {
-        match value {
+/// Fails with [`Error::InvalidParameter`] for [`OrderType::Unknown`], which can not be placed.
+impl TryFrom<OrderType> for OrderTypeRequest {
+    type Error = Error;
+
+    fn try_from(value: OrderType) -> Result<Self, Self::Error> {
+        Ok(match value {
             OrderType::Market => OrderTypeRequest::Market,
             OrderType::Limit => OrderTypeRequest::Limit,
             OrderType::Stop => OrderTypeRequest::Stop,
@@ -236,18 +662,46 @@ impl From<OrderType> for OrderTypeRequest {
             OrderType::NetCredit => OrderTypeRequest::NetCredit,
             OrderType::NetZero => OrderTypeRequest::NetZero,
             OrderType::LimitOnClose => OrderTypeRequest::LimitOnClose,
-            OrderType::Unknown => panic!("Unknown"),
+            OrderType::Unknown => {
+                return Err(Error::InvalidParameter(
+                    "unknown order type can not be placed".to_string(),
+                ))
+            }
+        })
+    }
+}
+
+impl From<OrderTypeRequest> for OrderType {
+    fn from(value: OrderTypeRequest) -> Self {
+        match value {
+            OrderTypeRequest::Market => OrderType::Market,
+            OrderTypeRequest::Limit => OrderType::Limit,
+            OrderTypeRequest::Stop => OrderType::Stop,
+            OrderTypeRequest::StopLimit => OrderType::StopLimit,
+            OrderTypeRequest::TrailingStop => OrderType::TrailingStop,
+            OrderTypeRequest::Cabinet => OrderType::Cabinet,
+            OrderTypeRequest::NonMarketable => OrderType::NonMarketable,
+            OrderTypeRequest::MarketOnClose => OrderType::MarketOnClose,
+            OrderTypeRequest::Exercise => OrderType::Exercise,
+            OrderTypeRequest::TrailingStopLimit => OrderType::TrailingStopLimit,
+            OrderTypeRequest::NetDebit => OrderType::NetDebit,
+            OrderTypeRequest::NetCredit => OrderType::NetCredit,
+            OrderTypeRequest::NetZero => OrderType::NetZero,
+            OrderTypeRequest::LimitOnClose => OrderType::LimitOnClose,
         }
     }
 }
 
 /// Similar to `super::order::OrderLegCollection`, but more simple
+#[skip_serializing_none]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderLegCollectionRequest {
     pub instrument: InstrumentRequest,
     pub instruction: Instruction,
     pub quantity: f64,
+    /// `DOLLARS` makes `quantity` an amount of money, for fractional shares.
+    pub quantity_type: Option<QuantityType>,
 }
 
 impl From<OrderLegCollection> for OrderLegCollectionRequest {
@@ -256,17 +710,53 @@ impl From<OrderLegCollection> for OrderLegCollectionRequest {
             instrument: value.instrument.into(),
             instruction: value.instruction,
             quantity: value.quantity,
+            quantity_type: value.quantity_type,
         }
     }
 }
 
 /// Similar to `super::accounts::AccountsInstrument`, but more simple
-/// only support Equity, Option now in schwab API
+///
+/// Only the asset types Schwab accepts in orders, the others are sent as `Equity`.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "assetType", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum InstrumentRequest {
     Equity { symbol: String },
     Option { symbol: String },
+    MutualFund { symbol: String },
+    CollectiveInvestment { symbol: String },
+    Index { symbol: String },
+}
+
+impl InstrumentRequest {
+    /// An option by its Schwab symbol, given as a `String` or an [`OptionSymbol`](crate::model::OptionSymbol).
+    pub fn option(symbol: impl Into<String>) -> Self {
+        Self::Option {
+            symbol: symbol.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn symbol(&self) -> &str {
+        match self {
+            Self::Equity { symbol }
+            | Self::Option { symbol }
+            | Self::MutualFund { symbol }
+            | Self::CollectiveInvestment { symbol }
+            | Self::Index { symbol } => symbol,
+        }
+    }
+
+    #[must_use]
+    pub fn asset_type(&self) -> AssetType {
+        match self {
+            Self::Equity { .. } => AssetType::Equity,
+            Self::Option { .. } => AssetType::Option,
+            Self::MutualFund { .. } => AssetType::MutualFund,
+            Self::CollectiveInvestment { .. } => AssetType::CollectiveInvestment,
+            Self::Index { .. } => AssetType::Index,
+        }
+    }
 }
 
 impl From<AccountsInstrument> for InstrumentRequest {
@@ -281,19 +771,19 @@ impl From<AccountsInstrument> for InstrumentRequest {
             AccountsInstrument::FixedIncome(x) => Self::Equity {
                 symbol: x.accounts_base_instrument.symbol,
             },
-            AccountsInstrument::MutualFund(x) => Self::Equity {
+            AccountsInstrument::MutualFund(x) => Self::MutualFund {
                 symbol: x.accounts_base_instrument.symbol,
             },
             AccountsInstrument::Option(x) => Self::Option {
                 symbol: x.accounts_base_instrument.symbol,
             },
-            AccountsInstrument::Index(x) => Self::Equity {
+            AccountsInstrument::Index(x) => Self::Index {
                 symbol: x.accounts_base_instrument.symbol,
             },
             AccountsInstrument::Currency(x) => Self::Equity {
                 symbol: x.accounts_base_instrument.symbol,
             },
-            AccountsInstrument::CollectiveInvestment(x) => Self::Equity {
+            AccountsInstrument::CollectiveInvestment(x) => Self::CollectiveInvestment {
                 symbol: x.accounts_base_instrument.symbol,
             },
         }
@@ -305,17 +795,24 @@ impl From<InstrumentResponse> for InstrumentRequest {
         match value.asset_type {
             InstrumentAssetType::Bond
             | InstrumentAssetType::Equity
-            | InstrumentAssetType::Etf
             | InstrumentAssetType::Extended
             | InstrumentAssetType::Forex
             | InstrumentAssetType::Future
             | InstrumentAssetType::Fundamental
-            | InstrumentAssetType::Index
             | InstrumentAssetType::Indicator
-            | InstrumentAssetType::MutualFund
             | InstrumentAssetType::Unknown => Self::Equity {
                 symbol: value.symbol,
             },
+            // same as `AccountsInstrument::CollectiveInvestment`, which Schwab uses for ETFs
+            InstrumentAssetType::Etf => Self::CollectiveInvestment {
+                symbol: value.symbol,
+            },
+            InstrumentAssetType::Index => Self::Index {
+                symbol: value.symbol,
+            },
+            InstrumentAssetType::MutualFund => Self::MutualFund {
+                symbol: value.symbol,
+            },
             InstrumentAssetType::FutureOption | InstrumentAssetType::Option => Self::Option {
                 symbol: value.symbol,
             },
@@ -323,6 +820,79 @@ impl From<InstrumentResponse> for InstrumentRequest {
     }
 }
 
+impl OrderRequestBuilder {
+    /// Catch pricing missing for the order type, and negative amounts, before sending the order.
+    fn validate(&self) -> Result<(), String> {
+        let price = self.price.flatten();
+        let stop_price = self.stop_price.flatten();
+        match self.order_type.flatten() {
+            Some(OrderTypeRequest::Limit) if price.is_none() => {
+                return Err("Limit orders require a price".to_string());
+            }
+            Some(OrderTypeRequest::StopLimit) if price.is_none() || stop_price.is_none() => {
+                return Err("StopLimit orders require a price and a stopPrice".to_string());
+            }
+            Some(OrderTypeRequest::Stop) if stop_price.is_none() => {
+                return Err("Stop orders require a stopPrice".to_string());
+            }
+            Some(OrderTypeRequest::TrailingStop | OrderTypeRequest::TrailingStopLimit)
+                if stop_price.is_none() && self.stop_price_offset.flatten().is_none() =>
+            {
+                return Err(
+                    "TrailingStop orders require a stopPrice or a stopPriceOffset".to_string(),
+                );
+            }
+            Some(order_type @ (OrderTypeRequest::NetDebit | OrderTypeRequest::NetCredit))
+                if self
+                    .order_leg_collection
+                    .as_ref()
+                    .and_then(Option::as_ref)
+                    .map_or(0, Vec::len)
+                    < 2 =>
+            {
+                return Err(format!(
+                    "{order_type:?} orders require an orderLegCollection of at least 2 legs"
+                ));
+            }
+            _ => {}
+        }
+
+        for (field, val) in [
+            ("price", price),
+            ("stopPrice", stop_price),
+            ("quantity", self.quantity.flatten()),
+        ] {
+            if let Some(val) = val.filter(|x| *x < 0.0) {
+                return Err(format!("{field} must not be negative: {val}"));
+            }
+        }
+        if let Some(leg) = self
+            .order_leg_collection
+            .iter()
+            .flatten()
+            .flatten()
+            .find(|leg| leg.quantity < 0.0)
+        {
+            return Err(format!(
+                "orderLegCollection quantity must not be negative: {}",
+                leg.quantity
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+fn check_positive(field: &str, val: f64) -> Result<(), Error> {
+    if val > 0.0 {
+        return Ok(());
+    }
+
+    Err(Error::OrderRequestBuild(
+        OrderRequestBuilderError::ValidationError(format!("{field} must be positive: {val}")),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -342,6 +912,86 @@ mod tests {
         assert!(val.is_ok());
     }
 
+    #[test]
+    fn test_instrument_from_response() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/MarketData/Instruments_real.json"
+        ));
+        let instruments = serde_json::from_str::<crate::model::Instruments>(json)
+            .unwrap()
+            .instruments;
+
+        // ETF, same as a collective investment position
+        assert_eq!(
+            InstrumentRequest::from(instruments[0].clone()),
+            InstrumentRequest::CollectiveInvestment {
+                symbol: "VTI".to_string()
+            }
+        );
+        assert_eq!(
+            InstrumentRequest::from(instruments[2].clone()),
+            InstrumentRequest::Equity {
+                symbol: "AAPL".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let submitted = OrderRequest::limit(
+            InstrumentRequest::Equity {
+                symbol: "VTI".to_string(),
+            },
+            Instruction::Buy,
+            1.0,
+            30.0,
+        )
+        .unwrap();
+
+        // Schwab records an ETF bought as EQUITY as COLLECTIVE_INVESTMENT
+        let mut recorded = submitted.clone();
+        recorded.order_leg_collection.as_mut().unwrap()[0].instrument =
+            InstrumentRequest::CollectiveInvestment {
+                symbol: "VTI".to_string(),
+            };
+        assert_eq!(submitted.diff(&recorded), vec![]);
+
+        // another symbol is still a change
+        recorded.order_leg_collection.as_mut().unwrap()[0].instrument =
+            InstrumentRequest::CollectiveInvestment {
+                symbol: "VEA".to_string(),
+            };
+        let changes = submitted.diff(&recorded);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "orderLegCollection");
+    }
+
+    #[test]
+    fn test_diff_quantity_type() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Order.json"
+        ));
+        let recorded: OrderRequest = serde_json::from_str::<Order>(json).unwrap().into();
+        assert_eq!(
+            recorded.order_leg_collection.as_ref().unwrap()[0].quantity_type,
+            Some(QuantityType::AllShares)
+        );
+
+        // submitted without a quantityType, Schwab fills in ALL_SHARES
+        let mut submitted = recorded.clone();
+        submitted.order_leg_collection.as_mut().unwrap()[0].quantity_type = None;
+        assert_eq!(submitted.diff(&recorded), vec![]);
+
+        // a submitted quantityType is still compared
+        submitted.order_leg_collection.as_mut().unwrap()[0].quantity_type =
+            Some(QuantityType::Dollars);
+        let changes = submitted.diff(&recorded);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "orderLegCollection");
+    }
+
     #[test]
     fn test_market() {
         // Buy Market: Stock
@@ -371,43 +1021,330 @@ mod tests {
         assert_json_matches!(
             order_req,
             expected,
-            Config::new(CompareMode::Inclusive).numeric_mode(NumericMode::AssumeFloat)
+            Config::new(CompareMode::Inclusive).numeric_mode(NumericMode::AssumeFloat)
+        );
+    }
+
+    #[test]
+    fn test_market_mutual_fund() {
+        // Buy Market: Mutual Fund
+        // Buy 10 shares of VFIAX at the Market good for the Day.
+        let expected = json!({
+            "orderType": "MARKET",
+            "session": "NORMAL",
+            "duration": "DAY",
+            "orderStrategyType": "SINGLE",
+            "orderLegCollection": [
+                {
+                    "instruction": "BUY",
+                    "quantity": 10,
+                    "instrument": {
+                        "symbol": "VFIAX",
+                        "assetType": "MUTUAL_FUND"
+                    }
+                }
+            ]
+        });
+
+        let symbol = InstrumentRequest::MutualFund {
+            symbol: "VFIAX".to_string(),
+        };
+        let order_req = OrderRequest::market(symbol, Instruction::Buy, 10.0).unwrap();
+        let order_req = serde_json::to_value(order_req).unwrap();
+        assert_json_matches!(
+            order_req,
+            expected,
+            Config::new(CompareMode::Inclusive).numeric_mode(NumericMode::AssumeFloat)
+        );
+
+        assert_eq!(
+            serde_json::to_value(InstrumentRequest::CollectiveInvestment {
+                symbol: "VTI".to_string()
+            })
+            .unwrap(),
+            json!({"assetType": "COLLECTIVE_INVESTMENT", "symbol": "VTI"})
+        );
+        assert_eq!(
+            serde_json::to_value(InstrumentRequest::Index {
+                symbol: "$SPX".to_string()
+            })
+            .unwrap(),
+            json!({"assetType": "INDEX", "symbol": "$SPX"})
+        );
+    }
+
+    #[test]
+    fn test_market_dollars() {
+        // Buy Market: Stock
+        // Buy $100 of XYZ at the Market good for the Day, fractional shares included.
+        let expected = json!({
+            "orderType": "MARKET",
+            "session": "NORMAL",
+            "duration": "DAY",
+            "orderStrategyType": "SINGLE",
+            "orderLegCollection": [
+                {
+                    "instruction": "BUY",
+                    "quantity": 100,
+                    "quantityType": "DOLLARS",
+                    "instrument": {
+                        "symbol": "XYZ",
+                        "assetType": "EQUITY"
+                    }
+                }
+            ]
+        });
+
+        let symbol = InstrumentRequest::Equity {
+            symbol: "XYZ".to_string(),
+        };
+        let order_req = OrderRequest::market_dollars(symbol, Instruction::Buy, 100.0).unwrap();
+        let order_req = serde_json::to_value(order_req).unwrap();
+        assert_json_matches!(
+            order_req,
+            expected,
+            Config::new(CompareMode::Strict).numeric_mode(NumericMode::AssumeFloat)
+        );
+
+        let order_req: OrderRequest = serde_json::from_value(expected).unwrap();
+        assert_eq!(
+            order_req.order_leg_collection.unwrap()[0].quantity_type,
+            Some(QuantityType::Dollars)
+        );
+    }
+
+    #[test]
+    fn test_limit() {
+        // Buy Limit: Single Option
+        // Buy to open 10 contracts of the XYZ March 15, 2024 $50 CALL at a Limit of $6.45 good for the Day.
+        let expected = json!({
+            "complexOrderStrategyType": "NONE",
+            "orderType": "LIMIT",
+            "session": "NORMAL",
+            "price": 6.45,
+            "duration": "DAY",
+            "orderStrategyType": "SINGLE",
+            "orderLegCollection": [
+                {
+                    "instruction": "BUY_TO_OPEN",
+                    "quantity": 10,
+                    "instrument": {
+                        "symbol": "XYZ   240315C00500000",
+                        "assetType": "OPTION"
+                    }
+                }
+            ]
+        });
+
+        let symbol = InstrumentRequest::Option {
+            symbol: "XYZ   240315C00500000".to_string(),
+        };
+        let order_req = OrderRequest::limit(symbol, Instruction::BuyToOpen, 10.0, 6.45).unwrap();
+        let order_req = serde_json::to_value(order_req).unwrap();
+        assert_json_matches!(
+            order_req,
+            expected,
+            Config::new(CompareMode::Inclusive).numeric_mode(NumericMode::AssumeFloat)
+        );
+
+        // the same symbol built from its parts
+        let symbol = crate::model::OptionSymbol::new(
+            "XYZ",
+            chrono::NaiveDate::from_ymd_opt(2024, 3, 15).unwrap(),
+            crate::model::market_data::option_chain::PutCall::Call,
+            50.0,
+        )
+        .unwrap();
+        assert_eq!(
+            InstrumentRequest::option(symbol),
+            InstrumentRequest::option("XYZ   240315C00050000")
+        );
+    }
+
+    #[test]
+    fn test_stop() {
+        // Sell Stop: Stock
+        // Sell 15 shares of XYZ with a Stop at $45.97 good for the Day.
+        let expected = json!({
+            "orderType": "STOP",
+            "session": "NORMAL",
+            "stopPrice": 45.97,
+            "duration": "DAY",
+            "orderStrategyType": "SINGLE",
+            "orderLegCollection": [
+                {
+                    "instruction": "SELL",
+                    "quantity": 15,
+                    "instrument": {
+                        "symbol": "XYZ",
+                        "assetType": "EQUITY"
+                    }
+                }
+            ]
+        });
+
+        let symbol = InstrumentRequest::Equity {
+            symbol: "XYZ".to_string(),
+        };
+        let order_req = OrderRequest::stop(symbol.clone(), Instruction::Sell, 15.0, 45.97).unwrap();
+        let order_req = serde_json::to_value(order_req).unwrap();
+        assert_json_matches!(
+            order_req,
+            expected,
+            Config::new(CompareMode::Strict).numeric_mode(NumericMode::AssumeFloat)
+        );
+
+        for stop_price in [0.0, -45.97, f64::NAN] {
+            assert!(matches!(
+                OrderRequest::stop(symbol.clone(), Instruction::Sell, 15.0, stop_price),
+                Err(Error::OrderRequestBuild(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_exercise() {
+        // Exercise 2 contracts of XYZ 240517C00050000.
+        let expected = json!({
+            "orderType": "EXERCISE",
+            "session": "NORMAL",
+            "duration": "DAY",
+            "orderStrategyType": "SINGLE",
+            "orderLegCollection": [
+                {
+                    "instruction": "SELL_TO_CLOSE",
+                    "quantity": 2,
+                    "instrument": {
+                        "symbol": "XYZ   240517C00050000",
+                        "assetType": "OPTION"
+                    }
+                }
+            ]
+        });
+
+        let order_req = OrderRequest::exercise("XYZ   240517C00050000".to_string(), 2.0).unwrap();
+        let order_req = serde_json::to_value(order_req).unwrap();
+        assert_json_matches!(
+            order_req,
+            expected,
+            Config::new(CompareMode::Strict).numeric_mode(NumericMode::AssumeFloat)
+        );
+    }
+
+    #[test]
+    fn test_flatten() {
+        // Close a long position of 10 shares of XYZ and a short option position.
+        let expected = json!({
+            "orderType": "MARKET",
+            "session": "NORMAL",
+            "duration": "DAY",
+            "orderStrategyType": "FLATTEN",
+            "orderLegCollection": [
+                {
+                    "instruction": "SELL",
+                    "quantity": 10,
+                    "instrument": {
+                        "symbol": "XYZ",
+                        "assetType": "EQUITY"
+                    }
+                },
+                {
+                    "instruction": "BUY_TO_CLOSE",
+                    "quantity": 1,
+                    "instrument": {
+                        "symbol": "XYZ   240517C00050000",
+                        "assetType": "OPTION"
+                    }
+                }
+            ]
+        });
+
+        let legs = vec![
+            OrderLegCollectionRequest {
+                instruction: Instruction::Sell,
+                quantity: 10.0,
+                quantity_type: None,
+                instrument: InstrumentRequest::Equity {
+                    symbol: "XYZ".to_string(),
+                },
+            },
+            OrderLegCollectionRequest {
+                instruction: Instruction::BuyToClose,
+                quantity: 1.0,
+                quantity_type: None,
+                instrument: InstrumentRequest::Option {
+                    symbol: "XYZ   240517C00050000".to_string(),
+                },
+            },
+        ];
+        let order_req = OrderRequest::flatten(legs.clone()).unwrap();
+        let order_req = serde_json::to_value(order_req).unwrap();
+        assert_json_matches!(
+            order_req,
+            expected,
+            Config::new(CompareMode::Strict).numeric_mode(NumericMode::AssumeFloat)
         );
+
+        // opening instructions are rejected
+        let mut opening = legs;
+        opening[0].instruction = Instruction::Buy;
+        assert!(matches!(
+            OrderRequest::flatten(opening),
+            Err(Error::OrderRequestBuild(_))
+        ));
+        assert!(matches!(
+            OrderRequest::flatten(vec![]),
+            Err(Error::OrderRequestBuild(_))
+        ));
     }
 
     #[test]
-    fn test_limit() {
-        // Buy Limit: Single Option
-        // Buy to open 10 contracts of the XYZ March 15, 2024 $50 CALL at a Limit of $6.45 good for the Day.
+    fn test_stop_limit() {
+        // Sell Stop Limit: Stock
+        // Sell 15 shares of XYZ with a Stop at $45.97 and a Limit of $45.50 good for the Day.
         let expected = json!({
-            "complexOrderStrategyType": "NONE",
-            "orderType": "LIMIT",
+            "orderType": "STOP_LIMIT",
             "session": "NORMAL",
-            "price": 6.45,
+            "stopPrice": 45.97,
+            "price": 45.5,
             "duration": "DAY",
             "orderStrategyType": "SINGLE",
             "orderLegCollection": [
                 {
-                    "instruction": "BUY_TO_OPEN",
-                    "quantity": 10,
+                    "instruction": "SELL",
+                    "quantity": 15,
                     "instrument": {
-                        "symbol": "XYZ   240315C00500000",
-                        "assetType": "OPTION"
+                        "symbol": "XYZ",
+                        "assetType": "EQUITY"
                     }
                 }
             ]
         });
 
-        let symbol = InstrumentRequest::Option {
-            symbol: "XYZ   240315C00500000".to_string(),
+        let symbol = InstrumentRequest::Equity {
+            symbol: "XYZ".to_string(),
         };
-        let order_req = OrderRequest::limit(symbol, Instruction::BuyToOpen, 10.0, 6.45).unwrap();
+        let order_req =
+            OrderRequest::stop_limit(symbol.clone(), Instruction::Sell, 15.0, 45.97, 45.5).unwrap();
         let order_req = serde_json::to_value(order_req).unwrap();
         assert_json_matches!(
             order_req,
             expected,
-            Config::new(CompareMode::Inclusive).numeric_mode(NumericMode::AssumeFloat)
+            Config::new(CompareMode::Strict).numeric_mode(NumericMode::AssumeFloat)
         );
+
+        for (stop_price, price) in [(0.0, 45.5), (45.97, -45.5)] {
+            assert!(matches!(
+                OrderRequest::stop_limit(
+                    symbol.clone(),
+                    Instruction::Sell,
+                    15.0,
+                    stop_price,
+                    price
+                ),
+                Err(Error::OrderRequestBuild(_))
+            ));
+        }
     }
 
     #[test]
@@ -455,11 +1392,13 @@ mod tests {
                 OrderLegCollectionRequest {
                     instruction: Instruction::BuyToOpen,
                     quantity: 2.0,
+                    quantity_type: None,
                     instrument: symbol1,
                 },
                 OrderLegCollectionRequest {
                     instruction: Instruction::SellToOpen,
                     quantity: 2.0,
+                    quantity_type: None,
                     instrument: symbol2,
                 },
             ])
@@ -526,6 +1465,7 @@ mod tests {
             .order_leg_collection(vec![OrderLegCollectionRequest {
                 instruction: Instruction::Sell,
                 quantity: 10.0,
+                quantity_type: None,
                 instrument: symbol.clone(),
             }])
             .build()
@@ -539,7 +1479,8 @@ mod tests {
             .order_leg_collection(vec![OrderLegCollectionRequest {
                 instruction: Instruction::Buy,
                 quantity: 10.0,
-                instrument: symbol,
+                quantity_type: None,
+                instrument: symbol.clone(),
             }])
             .child_order_strategies(vec![child_order_req])
             .build()
@@ -550,6 +1491,31 @@ mod tests {
             expected,
             Config::new(CompareMode::Inclusive).numeric_mode(NumericMode::AssumeFloat)
         );
+
+        // helper
+        let order_req = OrderRequest::trigger(
+            OrderRequest::limit(symbol.clone(), Instruction::Buy, 10.0, 34.97).unwrap(),
+            OrderRequest::limit(symbol.clone(), Instruction::Sell, 10.0, 42.03).unwrap(),
+        )
+        .unwrap();
+        let order_req = serde_json::to_value(order_req).unwrap();
+        assert_json_matches!(
+            order_req,
+            expected,
+            Config::new(CompareMode::Inclusive).numeric_mode(NumericMode::AssumeFloat)
+        );
+
+        // an OCO order cannot be the primary
+        let oco = OrderRequest::oco(
+            OrderRequest::limit(symbol.clone(), Instruction::Sell, 10.0, 42.03).unwrap(),
+            OrderRequest::stop(symbol.clone(), Instruction::Sell, 10.0, 30.00).unwrap(),
+        )
+        .unwrap();
+        let result = OrderRequest::trigger(
+            oco,
+            OrderRequest::market(symbol, Instruction::Buy, 10.0).unwrap(),
+        );
+        assert!(matches!(result, Err(Error::OrderRequestBuild(_))));
     }
 
     #[test]
@@ -609,34 +1575,32 @@ mod tests {
             .order_leg_collection(vec![OrderLegCollectionRequest {
                 instruction: Instruction::Sell,
                 quantity: 2.0,
+                quantity_type: None,
                 instrument: symbol.clone(),
             }])
             .build()
             .unwrap();
-        let child_order_req2 = OrderRequestBuilder::default()
-            .order_type(OrderTypeRequest::StopLimit)
-            .session(Session::Normal)
-            .duration(Duration::Day)
-            .price(37.00)
-            .stop_price(37.03)
-            .order_leg_collection(vec![OrderLegCollectionRequest {
-                instruction: Instruction::Sell,
-                quantity: 2.0,
-                instrument: symbol.clone(),
-            }])
-            .build()
-            .unwrap();
-        let order_req = OrderRequestBuilder::default()
-            .order_strategy_type(OrderStrategyType::Oco)
-            .child_order_strategies(vec![child_order_req1, child_order_req2])
-            .build()
-            .unwrap();
+        let child_order_req2 =
+            OrderRequest::stop_limit(symbol.clone(), Instruction::Sell, 2.0, 37.03, 37.00).unwrap();
+        let order_req = OrderRequest::oco(child_order_req1, child_order_req2).unwrap();
         let order_req = serde_json::to_value(order_req).unwrap();
         assert_json_matches!(
             order_req,
             expected,
-            Config::new(CompareMode::Inclusive).numeric_mode(NumericMode::AssumeFloat)
+            Config::new(CompareMode::Strict).numeric_mode(NumericMode::AssumeFloat)
+        );
+
+        // illegal nesting
+        let oco = OrderRequest::oco(
+            OrderRequest::limit(symbol.clone(), Instruction::Sell, 2.0, 45.97).unwrap(),
+            OrderRequest::stop(symbol.clone(), Instruction::Sell, 2.0, 37.03).unwrap(),
+        )
+        .unwrap();
+        let result = OrderRequest::oco(
+            oco,
+            OrderRequest::market(symbol, Instruction::Sell, 2.0).unwrap(),
         );
+        assert!(matches!(result, Err(Error::OrderRequestBuild(_))));
     }
 
     #[test]
@@ -715,6 +1679,7 @@ mod tests {
             .order_leg_collection(vec![OrderLegCollectionRequest {
                 instruction: Instruction::Sell,
                 quantity: 5.0,
+                quantity_type: None,
                 instrument: symbol.clone(),
             }])
             .build()
@@ -727,6 +1692,7 @@ mod tests {
             .order_leg_collection(vec![OrderLegCollectionRequest {
                 instruction: Instruction::Sell,
                 quantity: 5.0,
+                quantity_type: None,
                 instrument: symbol.clone(),
             }])
             .build()
@@ -745,6 +1711,7 @@ mod tests {
             .order_leg_collection(vec![OrderLegCollectionRequest {
                 instruction: Instruction::Buy,
                 quantity: 5.0,
+                quantity_type: None,
                 instrument: symbol.clone(),
             }])
             .child_order_strategies(vec![child_order_req])
@@ -758,6 +1725,112 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bracket() {
+        // Conditional Order: One Triggers A One Cancels Another, also known as a 1st Trigger OCO order.
+        let expected = json!({
+            "orderStrategyType": "TRIGGER",
+            "session": "NORMAL",
+            "duration": "DAY",
+            "orderType": "LIMIT",
+            "price": 14.97,
+            "orderLegCollection": [
+                {
+                    "instruction": "BUY",
+                    "quantity": 5,
+                    "instrument": {
+                        "assetType": "EQUITY",
+                        "symbol": "XYZ"
+                    }
+                }
+            ],
+            "childOrderStrategies": [
+                {
+                    "orderStrategyType": "OCO",
+                    "childOrderStrategies": [
+                        {
+                            "orderStrategyType": "SINGLE",
+                            "session": "NORMAL",
+                            "duration": "GOOD_TILL_CANCEL",
+                            "orderType": "LIMIT",
+                            "price": 15.27,
+                            "orderLegCollection": [
+                                {
+                                    "instruction": "SELL",
+                                    "quantity": 5,
+                                    "instrument": {
+                                        "assetType": "EQUITY",
+                                        "symbol": "XYZ"
+                                    }
+                                }
+                            ]
+                        },
+                        {
+                            "orderStrategyType": "SINGLE",
+                            "session": "NORMAL",
+                            "duration": "GOOD_TILL_CANCEL",
+                            "orderType": "STOP",
+                            "stopPrice": 11.27,
+                            "orderLegCollection": [
+                                {
+                                    "instruction": "SELL",
+                                    "quantity": 5,
+                                    "instrument": {
+                                        "assetType": "EQUITY",
+                                        "symbol": "XYZ"
+                                    }
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        });
+
+        let symbol = InstrumentRequest::Equity {
+            symbol: "XYZ".to_string(),
+        };
+        let order_req =
+            OrderRequest::bracket(symbol.clone(), Instruction::Buy, 5.0, 14.97, 15.27, 11.27)
+                .unwrap();
+        let order_req = serde_json::to_value(order_req).unwrap();
+        assert_json_matches!(
+            order_req,
+            expected,
+            Config::new(CompareMode::Inclusive).numeric_mode(NumericMode::AssumeFloat)
+        );
+
+        // short side
+        let order_req = OrderRequest::bracket(
+            symbol.clone(),
+            Instruction::SellShort,
+            5.0,
+            14.97,
+            11.27,
+            15.27,
+        )
+        .unwrap();
+        assert!(order_req.requires_margin());
+        let oco = &order_req.child_order_strategies.unwrap()[0];
+        for exit in oco.child_order_strategies.as_ref().unwrap() {
+            assert_eq!(
+                exit.order_leg_collection.as_ref().unwrap()[0].instruction,
+                Instruction::BuyToCover
+            );
+        }
+
+        // prices on the wrong side
+        assert!(matches!(
+            OrderRequest::bracket(symbol.clone(), Instruction::Buy, 5.0, 14.97, 11.27, 15.27),
+            Err(Error::OrderRequestBuild(_))
+        ));
+        // not opening a position
+        assert!(matches!(
+            OrderRequest::bracket(symbol, Instruction::Sell, 5.0, 14.97, 11.27, 15.27),
+            Err(Error::OrderRequestBuild(_))
+        ));
+    }
+
     #[test]
     fn test_sell_trailing_stop() {
         // Sell Trailing Stop: Stock
@@ -799,15 +1872,260 @@ mod tests {
             .order_leg_collection(vec![OrderLegCollectionRequest {
                 instruction: Instruction::Sell,
                 quantity: 10.0,
+                quantity_type: None,
+                instrument: symbol.clone(),
+            }])
+            .build()
+            .unwrap();
+        let order_req = serde_json::to_value(order_req).unwrap();
+        assert_json_matches!(
+            order_req,
+            expected,
+            Config::new(CompareMode::Inclusive).numeric_mode(NumericMode::AssumeFloat)
+        );
+    }
+
+    #[test]
+    fn test_trailing_stop() {
+        // Sell Trailing Stop: Stock
+        // Sell 10 shares of XYZ with a Trailing Stop where the trail is a -$10 offset from the time the order is submitted.
+        let expected = json!({
+            "complexOrderStrategyType": "NONE",
+            "orderType": "TRAILING_STOP",
+            "session": "NORMAL",
+            "stopPriceLinkBasis": "BID",
+            "stopPriceLinkType": "VALUE",
+            "stopPriceOffset": 10,
+            "duration": "DAY",
+            "orderStrategyType": "SINGLE",
+            "orderLegCollection": [
+                {
+                    "instruction": "SELL",
+                    "quantity": 10,
+                    "instrument": {
+                        "symbol": "XYZ",
+                        "assetType": "EQUITY"
+                    }
+                }
+            ]
+        });
+
+        let symbol = InstrumentRequest::Equity {
+            symbol: "XYZ".to_string(),
+        };
+        let mut order_req = OrderRequest::trailing_stop(
+            symbol.clone(),
+            Instruction::Sell,
+            10.0,
+            StopPriceLinkType::Value,
+            10.0,
+        )
+        .unwrap();
+        // check default
+        assert_eq!(
+            order_req.stop_price_link_basis,
+            Some(StopPriceLinkBasis::Last)
+        );
+        order_req.stop_price_link_basis = Some(StopPriceLinkBasis::Bid);
+        let order_req = serde_json::to_value(order_req).unwrap();
+        assert_json_matches!(
+            order_req,
+            expected,
+            Config::new(CompareMode::Strict).numeric_mode(NumericMode::AssumeFloat)
+        );
+
+        for offset in [0.0, -10.0] {
+            assert!(matches!(
+                OrderRequest::trailing_stop(
+                    symbol.clone(),
+                    Instruction::Sell,
+                    10.0,
+                    StopPriceLinkType::Value,
+                    offset
+                ),
+                Err(Error::OrderRequestBuild(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_trailing_stop_limit() {
+        // Sell 10 shares of XYZ with a 5% trailing stop, limited to $1 below the stop once triggered.
+        let expected = json!({
+            "complexOrderStrategyType": "NONE",
+            "orderType": "TRAILING_STOP_LIMIT",
+            "session": "NORMAL",
+            "stopPriceLinkBasis": "LAST",
+            "stopPriceLinkType": "PERCENT",
+            "stopPriceOffset": 5,
+            "priceLinkBasis": "TRIGGER",
+            "priceLinkType": "VALUE",
+            "price": 1,
+            "duration": "DAY",
+            "orderStrategyType": "SINGLE",
+            "orderLegCollection": [
+                {
+                    "instruction": "SELL",
+                    "quantity": 10,
+                    "instrument": {
+                        "symbol": "XYZ",
+                        "assetType": "EQUITY"
+                    }
+                }
+            ]
+        });
+
+        let symbol = InstrumentRequest::Equity {
+            symbol: "XYZ".to_string(),
+        };
+        let order_req = OrderRequest::trailing_stop_limit(
+            symbol.clone(),
+            Instruction::Sell,
+            10.0,
+            StopPriceLinkType::Percent,
+            5.0,
+            1.0,
+        )
+        .unwrap();
+        let order_req = serde_json::to_value(order_req).unwrap();
+        assert_json_matches!(
+            order_req,
+            expected,
+            Config::new(CompareMode::Strict).numeric_mode(NumericMode::AssumeFloat)
+        );
+
+        for (stop_price_offset, limit_offset) in [(0.0, 1.0), (5.0, 0.0), (5.0, -1.0)] {
+            assert!(matches!(
+                OrderRequest::trailing_stop_limit(
+                    symbol.clone(),
+                    Instruction::Sell,
+                    10.0,
+                    StopPriceLinkType::Percent,
+                    stop_price_offset,
+                    limit_offset
+                ),
+                Err(Error::OrderRequestBuild(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_activation_price() {
+        // Buy Stop Limit: Stock
+        // Buy 10 shares of XYZ with a Stop Limit order where the stop price is $40.00 and limit is $40.10, only active once XYZ trades at $39.50.
+        let expected = json!({
+            "orderType": "STOP_LIMIT",
+            "session": "NORMAL",
+            "price": 40.1,
+            "stopPrice": 40.0,
+            "activationPrice": 39.5,
+            "duration": "DAY",
+            "orderStrategyType": "SINGLE",
+            "orderLegCollection": [
+                {
+                    "instruction": "BUY",
+                    "quantity": 10,
+                    "instrument": {
+                        "symbol": "XYZ",
+                        "assetType": "EQUITY"
+                    }
+                }
+            ]
+        });
+
+        let symbol = InstrumentRequest::Equity {
+            symbol: "XYZ".to_string(),
+        };
+
+        let mut order_req = OrderRequestBuilder::default()
+            .order_type(OrderTypeRequest::StopLimit)
+            .session(Session::Normal)
+            .duration(Duration::Day)
+            .price(40.1)
+            .stop_price(40.0)
+            .order_leg_collection(vec![OrderLegCollectionRequest {
+                instruction: Instruction::Buy,
+                quantity: 10.0,
+                quantity_type: None,
                 instrument: symbol.clone(),
             }])
             .build()
             .unwrap();
+        order_req.set_activation_price(39.5).unwrap();
         let order_req = serde_json::to_value(order_req).unwrap();
         assert_json_matches!(
             order_req,
             expected,
             Config::new(CompareMode::Inclusive).numeric_mode(NumericMode::AssumeFloat)
         );
+
+        // not a stop-type order
+        let mut order_req =
+            OrderRequest::limit(symbol.clone(), Instruction::Buy, 10.0, 40.1).unwrap();
+        assert!(matches!(
+            order_req.set_activation_price(39.5),
+            Err(Error::InvalidActivationPrice(_))
+        ));
+        assert_eq!(order_req.activation_price, None);
+
+        // not a positive price
+        let mut order_req = OrderRequestBuilder::default()
+            .order_type(OrderTypeRequest::Stop)
+            .stop_price(40.0)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            order_req.set_activation_price(-1.0),
+            Err(Error::InvalidActivationPrice(_))
+        ));
+    }
+    #[test]
+    fn test_build_validate() {
+        let leg = OrderLegCollectionRequest {
+            instruction: Instruction::Buy,
+            quantity: 10.0,
+            quantity_type: None,
+            instrument: InstrumentRequest::Equity {
+                symbol: "XYZ".to_string(),
+            },
+        };
+        let error = |builder: &OrderRequestBuilder| match builder.build() {
+            Err(OrderRequestBuilderError::ValidationError(e)) => e,
+            x => panic!("{x:?}"),
+        };
+
+        let mut builder = OrderRequestBuilder::default();
+        builder.order_leg_collection(vec![leg.clone()]);
+        assert!(builder.build().is_ok());
+
+        builder.order_type(OrderTypeRequest::Limit);
+        assert_eq!(error(&builder), "Limit orders require a price");
+        builder.price(-1.0);
+        assert_eq!(error(&builder), "price must not be negative: -1");
+        builder.price(10.0);
+        assert!(builder.build().is_ok());
+
+        builder.order_type(OrderTypeRequest::StopLimit);
+        assert!(error(&builder).contains("stopPrice"));
+        builder.order_type(OrderTypeRequest::Stop);
+        assert_eq!(error(&builder), "Stop orders require a stopPrice");
+        builder.stop_price(9.0);
+        assert!(builder.build().is_ok());
+
+        builder.order_type(OrderTypeRequest::NetDebit);
+        assert_eq!(
+            error(&builder),
+            "NetDebit orders require an orderLegCollection of at least 2 legs"
+        );
+        let mut short_leg = leg.clone();
+        short_leg.quantity = -10.0;
+        builder.order_leg_collection(vec![leg, short_leg]);
+        assert!(error(&builder).contains("quantity must not be negative"));
+
+        let mut builder = OrderRequestBuilder::default();
+        builder.order_type(OrderTypeRequest::TrailingStop);
+        assert!(error(&builder).contains("stopPriceOffset"));
+        builder.stop_price_offset(10.0);
+        assert!(builder.build().is_ok());
     }
 }
@@ -6,7 +6,10 @@ use super::order::ComplexOrderStrategyType;
 use super::order::Duration;
 use super::order::OrderStrategyType;
 use super::order::OrderType;
+use super::order::QuantityType;
 use super::order::Session;
+use super::order_request::OrderRequest;
+use crate::model::datetime::iso8601_datetime;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -17,13 +20,64 @@ pub struct PreviewOrder {
     pub commission_and_fee: CommissionAndFee,
 }
 
+/// The preview body of the exact order to place, for `Api::post_accounts_preview_order`.
+///
+/// The fields Schwab computes, such as the balances, are left to their default.
+impl From<&OrderRequest> for PreviewOrder {
+    #[allow(clippy::cast_possible_truncation)]
+    fn from(value: &OrderRequest) -> Self {
+        let legs = value.order_leg_collection.as_deref().unwrap_or_default();
+        let quantity = value
+            .quantity
+            .unwrap_or_else(|| legs.iter().map(|x| x.quantity).sum());
+        let amount_indicator = if legs
+            .iter()
+            .any(|x| x.quantity_type == Some(QuantityType::Dollars))
+        {
+            AmountIndicator::Dollars
+        } else {
+            AmountIndicator::Shares
+        };
+
+        Self {
+            order_strategy: OrderStrategy {
+                order_strategy_type: value.order_strategy_type,
+                session: value.session.unwrap_or_default(),
+                duration: value.duration.unwrap_or_default(),
+                order_type: value.order_type.unwrap_or_default().into(),
+                price: value.price.unwrap_or_default(),
+                quantity: quantity.round() as i64,
+                remaining_quantity: quantity.round() as i64,
+                strategy: value.complex_order_strategy_type.unwrap_or_default(),
+                amount_indicator,
+                order_legs: legs
+                    .iter()
+                    .zip(1..)
+                    .map(|(leg, leg_id)| OrderLeg {
+                        quantity: leg.quantity,
+                        final_symbol: leg.instrument.symbol().to_string(),
+                        leg_id,
+                        asset_type: leg.instrument.asset_type(),
+                        instruction: leg.instruction,
+                        ..OrderLeg::default()
+                    })
+                    .collect(),
+                ..OrderStrategy::default()
+            },
+            ..Self::default()
+        }
+    }
+}
+
 #[allow(clippy::struct_field_names)]
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderStrategy {
     pub account_number: String,
     pub advanced_order_type: AdvancedOrderType,
+    #[serde(with = "iso8601_datetime")]
     pub close_time: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "iso8601_datetime")]
     pub entered_time: chrono::DateTime<chrono::Utc>,
     pub order_balance: OrderBalance,
     pub order_strategy_type: OrderStrategyType,
@@ -147,6 +201,7 @@ pub enum AmountIndicator {
     Shares,
     AllShares,
     Percentage,
+    #[serde(other)]
     Unknown,
 }
 
@@ -157,6 +212,7 @@ pub enum SettlementInstruction {
     Regular,
     Cash,
     NextDay,
+    #[serde(other)]
     Unknown,
 }
 
@@ -199,6 +255,7 @@ pub enum APIOrderStatus {
     AwaitingReleaseTime,
     PendingAcknowledgement,
     PendingRecall,
+    #[serde(other)]
     Unknown,
 }
 
@@ -238,6 +295,7 @@ pub enum APIRuleAction {
     Alert,
     Reject,
     Review,
+    #[serde(other)]
     Unknown,
 }
 
@@ -269,6 +327,7 @@ pub enum FeeType {
     IndexOptionFee,
     TefraTax,
     StateTax,
+    #[serde(other)]
     Unknown,
 }
 
@@ -276,6 +335,11 @@ pub enum FeeType {
 mod tests {
     use super::*;
 
+    use assert_json_diff::{assert_json_matches, CompareMode, Config, NumericMode};
+    use serde_json::json;
+
+    use crate::model::InstrumentRequest;
+
     #[test]
     fn test_de() {
         let json = include_str!(concat!(
@@ -287,4 +351,45 @@ mod tests {
         println!("{val:?}");
         assert!(val.is_ok());
     }
+    #[test]
+    fn test_from_order_request() {
+        let order_req = OrderRequest::limit(
+            InstrumentRequest::Equity {
+                symbol: "XYZ".to_string(),
+            },
+            Instruction::Buy,
+            15.0,
+            10.5,
+        )
+        .unwrap();
+
+        let expected = json!({
+            "orderStrategy": {
+                "orderStrategyType": "SINGLE",
+                "session": "NORMAL",
+                "duration": "DAY",
+                "orderType": "LIMIT",
+                "price": 10.5,
+                "quantity": 15,
+                "strategy": "NONE",
+                "amountIndicator": "SHARES",
+                "orderLegs": [
+                    {
+                        "quantity": 15,
+                        "finalSymbol": "XYZ",
+                        "legId": 1,
+                        "assetType": "EQUITY",
+                        "instruction": "BUY"
+                    }
+                ]
+            }
+        });
+
+        let preview = serde_json::to_value(PreviewOrder::from(&order_req)).unwrap();
+        assert_json_matches!(
+            preview,
+            expected,
+            Config::new(CompareMode::Inclusive).numeric_mode(NumericMode::AssumeFloat)
+        );
+    }
 }
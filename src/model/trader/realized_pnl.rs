@@ -0,0 +1,256 @@
+//! Realized profit and loss of [`Transaction`]s, matching closing trades against open tax lots.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::order::TaxLotMethod;
+use super::transactions::{Transaction, TransactionType};
+use crate::error::Error;
+
+/// The part of an open lot closed by a later trade.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RealizedLot {
+    pub symbol: String,
+    /// Positive for a closed long lot, negative for a covered short one.
+    pub quantity: f64,
+    pub open_date: DateTime<Utc>,
+    pub close_date: DateTime<Utc>,
+    /// Cash paid per unit to open, or received for a short.
+    pub open_price: f64,
+    /// Cash received per unit to close, or paid for a short.
+    pub close_price: f64,
+    pub realized: f64,
+}
+
+/// Quantities within it of zero are rounding leftovers of fractional shares, e.g. `0.3 - 0.1 - 0.2`.
+const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone)]
+struct Lot {
+    quantity: f64,
+    /// signed cash flow per unit, negative when paid
+    cash: f64,
+    date: DateTime<Utc>,
+}
+
+/// Match the trades of `transactions` per symbol, in trade date order, and return the closed lots.
+///
+/// Quantities and cash come from the `amount` and `cost` of the transfer items,
+/// so fees are left out and option contracts are valued with their multiplier.
+/// A trade larger than the open position closes it and opens the remainder on the other side.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidParameter`] for [`TaxLotMethod::SpecificLot`] and [`TaxLotMethod::LossHarvester`],
+/// which depend on choices not recorded in the transactions.
+pub fn realized_pnl(
+    transactions: &[Transaction],
+    method: TaxLotMethod,
+) -> Result<Vec<RealizedLot>, Error> {
+    if matches!(
+        method,
+        TaxLotMethod::SpecificLot | TaxLotMethod::LossHarvester
+    ) {
+        return Err(Error::InvalidParameter(format!(
+            "{method:?} is not supported"
+        )));
+    }
+
+    let mut trades: Vec<&Transaction> = transactions
+        .iter()
+        .filter(|x| x.type_field == TransactionType::Trade)
+        .collect();
+    trades.sort_by_key(|x| x.trade_date);
+
+    let mut open: HashMap<&str, Vec<Lot>> = HashMap::new();
+    let mut realized = vec![];
+    for transaction in trades {
+        for item in &transaction.transfer_items {
+            if item.fee_type.is_some() || item.amount == 0.0 {
+                continue;
+            }
+
+            let symbol = item.instrument.symbol();
+            let lots = open.entry(symbol).or_default();
+            let cash = item.cost / item.amount.abs();
+            let mut remaining = item.amount;
+            while remaining.abs() > EPSILON {
+                let Some(index) = pick(lots, remaining, method) else {
+                    break;
+                };
+
+                let lot = &mut lots[index];
+                let quantity = if lot.quantity.abs() <= remaining.abs() + EPSILON {
+                    lot.quantity
+                } else {
+                    -remaining
+                };
+                realized.push(RealizedLot {
+                    symbol: symbol.to_string(),
+                    quantity,
+                    open_date: lot.date,
+                    close_date: transaction.trade_date,
+                    open_price: lot.cash.abs(),
+                    close_price: cash.abs(),
+                    realized: quantity.abs() * (lot.cash + cash),
+                });
+
+                lot.quantity -= quantity;
+                remaining += quantity;
+                if lot.quantity.abs() <= EPSILON {
+                    lots.remove(index);
+                }
+            }
+
+            if remaining.abs() > EPSILON {
+                open_lot(
+                    lots,
+                    Lot {
+                        quantity: remaining,
+                        cash,
+                        date: transaction.trade_date,
+                    },
+                    method,
+                );
+            }
+        }
+    }
+
+    Ok(realized)
+}
+
+/// The lot closed next by a trade of `quantity`, if it is on the other side of the open ones.
+fn pick(lots: &[Lot], quantity: f64, method: TaxLotMethod) -> Option<usize> {
+    if lots.first()?.quantity.signum() == quantity.signum() {
+        return None;
+    }
+
+    let price = |i: &usize| lots[*i].cash.abs();
+    match method {
+        TaxLotMethod::Lifo => Some(lots.len() - 1),
+        TaxLotMethod::HighCost => (0..lots.len()).max_by(|a, b| price(a).total_cmp(&price(b))),
+        TaxLotMethod::LowCost => (0..lots.len()).min_by(|a, b| price(a).total_cmp(&price(b))),
+        _ => Some(0),
+    }
+}
+
+fn open_lot(lots: &mut Vec<Lot>, lot: Lot, method: TaxLotMethod) {
+    match lots.first_mut() {
+        Some(average) if method == TaxLotMethod::AverageCost => {
+            let quantity = average.quantity + lot.quantity;
+            average.cash = (average.cash * average.quantity + lot.cash * lot.quantity) / quantity;
+            average.quantity = quantity;
+        }
+        _ => lots.push(lot),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use float_cmp::assert_approx_eq;
+
+    fn trade(symbol: &str, date: &str, amount: f64, price: f64) -> Transaction {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Transaction_real.json"
+        ));
+        let mut json: serde_json::Value = serde_json::from_str(json).unwrap();
+        json["tradeDate"] = format!("{date}T14:30:00+0000").into();
+        let item = &mut json["transferItems"][0];
+        item["instrument"]["symbol"] = symbol.into();
+        item["amount"] = amount.into();
+        item["cost"] = (-amount * price).into();
+        item["price"] = price.into();
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn realized(lots: &[RealizedLot]) -> Vec<(f64, f64)> {
+        lots.iter().map(|x| (x.quantity, x.realized)).collect()
+    }
+
+    #[test]
+    fn test_realized_pnl() {
+        let transactions = vec![
+            // out of order on purpose
+            trade("AAPL", "2024-03-01", -15.0, 130.0),
+            trade("AAPL", "2024-01-01", 10.0, 100.0),
+            trade("AAPL", "2024-02-01", 10.0, 120.0),
+            trade("MSFT", "2024-01-15", 5.0, 400.0),
+            trade("MSFT", "2024-02-15", -5.0, 380.0),
+        ];
+
+        let fifo = realized_pnl(&transactions, TaxLotMethod::Fifo).unwrap();
+        assert_eq!(realized(&fifo), [(5.0, -100.0), (10.0, 300.0), (5.0, 50.0)]);
+        assert_eq!(fifo[0].symbol, "MSFT");
+        assert_eq!(fifo[1].symbol, "AAPL");
+        assert_eq!(fifo[1].open_date.to_rfc3339(), "2024-01-01T14:30:00+00:00");
+        assert_eq!(fifo[1].close_date.to_rfc3339(), "2024-03-01T14:30:00+00:00");
+        assert_approx_eq!(f64, fifo[1].open_price, 100.0);
+        assert_approx_eq!(f64, fifo[1].close_price, 130.0);
+
+        let lifo = realized_pnl(&transactions, TaxLotMethod::Lifo).unwrap();
+        assert_eq!(
+            realized(&lifo),
+            [(5.0, -100.0), (10.0, 100.0), (5.0, 150.0)]
+        );
+        assert_eq!(lifo[1].open_date.to_rfc3339(), "2024-02-01T14:30:00+00:00");
+
+        let high_cost = realized_pnl(&transactions, TaxLotMethod::HighCost).unwrap();
+        assert_eq!(realized(&high_cost), realized(&lifo));
+        let low_cost = realized_pnl(&transactions, TaxLotMethod::LowCost).unwrap();
+        assert_eq!(realized(&low_cost), realized(&fifo));
+
+        let average = realized_pnl(&transactions, TaxLotMethod::AverageCost).unwrap();
+        assert_eq!(realized(&average), [(5.0, -100.0), (15.0, 300.0)]);
+        assert_approx_eq!(f64, average[1].open_price, 110.0);
+
+        assert!(matches!(
+            realized_pnl(&transactions, TaxLotMethod::SpecificLot),
+            Err(Error::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn test_realized_pnl_short() {
+        let transactions = vec![
+            trade("TSLA", "2024-01-01", -10.0, 50.0),
+            trade("TSLA", "2024-01-02", 4.0, 40.0),
+            // covers the rest and goes long
+            trade("TSLA", "2024-01-03", 10.0, 45.0),
+            trade("TSLA", "2024-01-04", -4.0, 50.0),
+        ];
+
+        let fifo = realized_pnl(&transactions, TaxLotMethod::Fifo).unwrap();
+        assert_eq!(realized(&fifo), [(-4.0, 40.0), (-6.0, 30.0), (4.0, 20.0)]);
+        assert_approx_eq!(f64, fifo[0].open_price, 50.0);
+        assert_approx_eq!(f64, fifo[0].close_price, 40.0);
+        assert_eq!(fifo[2].open_date.to_rfc3339(), "2024-01-03T14:30:00+00:00");
+    }
+
+    #[test]
+    fn test_realized_pnl_fractional() {
+        let transactions = vec![
+            trade("VTI", "2024-01-01", 0.3, 200.0),
+            trade("VTI", "2024-01-02", -0.1, 210.0),
+            // leaves no rounding leftover to close later
+            trade("VTI", "2024-01-03", -0.2, 220.0),
+            trade("VTI", "2024-01-04", 1.0, 230.0),
+            trade("VTI", "2024-01-05", -1.0, 240.0),
+        ];
+
+        let fifo = realized_pnl(&transactions, TaxLotMethod::Fifo).unwrap();
+        assert_eq!(fifo.len(), 3);
+        assert_approx_eq!(f64, fifo[0].quantity, 0.1);
+        assert_approx_eq!(f64, fifo[0].realized, 1.0, epsilon = 1e-9);
+        assert_approx_eq!(f64, fifo[1].quantity, 0.2);
+        assert_approx_eq!(f64, fifo[1].realized, 4.0, epsilon = 1e-9);
+        assert_approx_eq!(f64, fifo[2].quantity, 1.0);
+        assert_approx_eq!(f64, fifo[2].realized, 10.0, epsilon = 1e-9);
+        assert_eq!(fifo[2].open_date.to_rfc3339(), "2024-01-04T14:30:00+00:00");
+    }
+}
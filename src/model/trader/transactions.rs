@@ -2,6 +2,7 @@ use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 
 use super::accounts::AssetType;
+use crate::model::datetime::iso8601_datetime;
 
 #[serde_with::apply(
     Option => #[serde(skip_serializing_if = "Option::is_none")],
@@ -18,7 +19,9 @@ pub struct Transaction {
     pub type_field: TransactionType,
     pub status: TransactionStatus,
     pub sub_account: TransactionSubAccount,
+    #[serde(with = "iso8601_datetime")]
     pub trade_date: chrono::DateTime<chrono::Utc>,
+    #[serde(default, with = "iso8601_datetime::option")]
     pub settlement_date: Option<chrono::DateTime<chrono::Utc>>,
     pub position_id: Option<i64>,
     pub order_id: Option<i64>,
@@ -28,6 +31,34 @@ pub struct Transaction {
     pub transfer_items: Vec<TransferItem>,
 }
 
+impl Transaction {
+    /// The sum of the `cost` of the fee items, negative when fees were paid.
+    #[must_use]
+    pub fn total_fees(&self) -> f64 {
+        self.transfer_items
+            .iter()
+            .filter(|x| x.fee_type.is_some())
+            .map(|x| x.cost)
+            .sum()
+    }
+
+    /// The sum of the `cost` of the items which are not fees, e.g. the traded instrument.
+    #[must_use]
+    pub fn principal_amount(&self) -> f64 {
+        self.transfer_items
+            .iter()
+            .filter(|x| x.fee_type.is_none())
+            .map(|x| x.cost)
+            .sum()
+    }
+
+    /// [`Transaction::principal_amount`] plus [`Transaction::total_fees`], expected to match `net_amount`.
+    #[must_use]
+    pub fn net_amount_check(&self) -> f64 {
+        self.principal_amount() + self.total_fees()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UserDetails {
@@ -72,6 +103,14 @@ impl<'de, T: DeserializeOwned> Deserialize<'de> for DuplicatedKey<T> {
     }
 }
 
+impl<T: DeserializeOwned> std::ops::Deref for DuplicatedKey<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "assetType", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionInstrument {
@@ -88,6 +127,42 @@ pub enum TransactionInstrument {
     Product(Product),
 }
 
+impl TransactionInstrument {
+    /// Returns the fields shared by every instrument
+    #[must_use]
+    pub fn base(&self) -> &TransactionBaseInstrument {
+        match self {
+            TransactionInstrument::TransactionCashEquivalent(x) => &x.transaction_base_instrument,
+            TransactionInstrument::CollectiveInvestment(x) => &x.transaction_base_instrument,
+            TransactionInstrument::Currency(x) => &x.transaction_base_instrument,
+            TransactionInstrument::TransactionEquity(x) => &x.transaction_base_instrument,
+            TransactionInstrument::TransactionFixedIncome(x) => &x.transaction_base_instrument,
+            TransactionInstrument::Forex(x) => &x.transaction_base_instrument,
+            TransactionInstrument::Future(x) => &x.transaction_base_instrument,
+            TransactionInstrument::Index(x) => &x.transaction_base_instrument,
+            TransactionInstrument::TransactionMutualFund(x) => &x.transaction_base_instrument,
+            TransactionInstrument::TransactionOption(x) => &x.transaction_base_instrument,
+            TransactionInstrument::Product(x) => &x.transaction_base_instrument,
+        }
+    }
+
+    /// Returns the symbol of the instrument
+    #[must_use]
+    pub fn symbol(&self) -> &str {
+        &self.base().symbol
+    }
+
+    #[must_use]
+    pub fn cusip(&self) -> Option<&str> {
+        self.base().cusip.as_deref()
+    }
+
+    #[must_use]
+    pub fn instrument_id(&self) -> i64 {
+        self.base().instrument_id
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionCashEquivalent {
@@ -133,6 +208,7 @@ pub struct TransactionFixedIncome {
 
     #[serde(rename = "type")]
     pub type_field: TransactionFixedIncomeType,
+    #[serde(with = "iso8601_datetime")]
     pub maturity_date: chrono::DateTime<chrono::Utc>,
     pub factor: f64,
     pub multiplier: f64,
@@ -161,8 +237,11 @@ pub struct Future {
     pub active_contract: bool,
     #[serde(rename = "type")]
     pub type_field: FutureType,
+    #[serde(with = "iso8601_datetime")]
     pub expiration_date: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "iso8601_datetime")]
     pub last_trading_date: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "iso8601_datetime")]
     pub first_notice_date: chrono::DateTime<chrono::Utc>,
     pub multiplier: f64,
 }
@@ -190,8 +269,11 @@ pub struct TransactionMutualFund {
     pub fund_group: String,
     #[serde(rename = "type")]
     pub type_field: TransactionMutualFundType,
+    #[serde(with = "iso8601_datetime")]
     pub exchange_cutoff_time: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "iso8601_datetime")]
     pub purchase_cutoff_time: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "iso8601_datetime")]
     pub redemption_cutoff_time: chrono::DateTime<chrono::Utc>,
 }
 
@@ -201,6 +283,7 @@ pub struct TransactionOption {
     #[serde(flatten)]
     pub transaction_base_instrument: TransactionBaseInstrument,
 
+    #[serde(with = "iso8601_datetime")]
     pub expiration_date: chrono::DateTime<chrono::Utc>,
     /// xml: `OrderedMap` { "name": "optionDeliverables", "wrapped": true }
     pub option_deliverables: Vec<TransactionAPIOptionDeliverable>,
@@ -258,6 +341,7 @@ pub enum TransactionCashEquivalentType {
     SweepVehicle,
     Savings,
     MoneyMarketFund,
+    #[serde(other)]
     Unknown,
 }
 
@@ -286,6 +370,7 @@ pub enum TransactionEquityType {
     ConvertibleStock,
     LimitedPartnership,
     WhenIssued,
+    #[serde(other)]
     Unknown,
 }
 
@@ -310,6 +395,7 @@ pub enum TransactionFixedIncomeType {
     AgencyBond,
     WhenAsAndIfIssuedBond,
     AssetBackedSecurity,
+    #[serde(other)]
     Unknown,
 }
 
@@ -318,6 +404,7 @@ pub enum TransactionFixedIncomeType {
 pub enum ForexType {
     Standard,
     Nbbo,
+    #[serde(other)]
     Unknown,
 }
 
@@ -325,6 +412,7 @@ pub enum ForexType {
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum FutureType {
     Standard,
+    #[serde(other)]
     Unknown,
 }
 
@@ -333,6 +421,7 @@ pub enum FutureType {
 pub enum IndexType {
     BroadBased,
     NarrowBased,
+    #[serde(other)]
     Unknown,
 }
 
@@ -344,6 +433,7 @@ pub enum TransactionMutualFundType {
     OpenEndTaxable,
     NoLoadNonTaxable,
     NoLoadTaxable,
+    #[serde(other)]
     Unknown,
 }
 
@@ -352,6 +442,7 @@ pub enum TransactionMutualFundType {
 pub enum TransactionOptionPullCall {
     Put,
     Call,
+    #[serde(other)]
     Unknown,
 }
 
@@ -359,6 +450,7 @@ pub enum TransactionOptionPullCall {
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ProductType {
     Tbd,
+    #[serde(other)]
     Unknown,
 }
 
@@ -368,6 +460,7 @@ pub enum TransactionOptionType {
     Vanilla,
     Binary,
     Barrier,
+    #[serde(other)]
     Unknown,
 }
 
@@ -389,6 +482,8 @@ pub enum TransactionType {
     MarginCall,
     MoneyMarket,
     SmaAdjustment,
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -397,6 +492,7 @@ pub enum TransactionStatus {
     Valid,
     Invalid,
     Pending,
+    #[serde(other)]
     Unknown,
 }
 
@@ -408,6 +504,7 @@ pub enum TransactionSubAccount {
     Short,
     Div,
     Income,
+    #[serde(other)]
     Unknown,
 }
 
@@ -418,6 +515,7 @@ pub enum TransactionActivityType {
     Execution,
     OrderAction,
     Transfer,
+    #[serde(other)]
     Unknown,
 }
 
@@ -428,6 +526,7 @@ pub enum UserDetailsType {
     BrokerUser,
     ClientUser,
     SystemUser,
+    #[serde(other)]
     Unknown,
 }
 
@@ -449,6 +548,7 @@ pub enum TransferItemFeeType {
     GstFee,
     TafFee,
     IndexOptionFee,
+    #[serde(other)]
     Unknown,
 }
 
@@ -458,6 +558,7 @@ pub enum TransferItemPositionEffect {
     Opening,
     Closing,
     Automatic,
+    #[serde(other)]
     Unknown,
 }
 
@@ -466,6 +567,7 @@ mod tests {
     use super::*;
 
     use assert_json_diff::{assert_json_matches_no_panic, CompareMode, Config, NumericMode};
+    use float_cmp::assert_approx_eq;
 
     #[test]
     fn test_de() {
@@ -479,6 +581,88 @@ mod tests {
         assert!(val.is_ok());
     }
 
+    #[test]
+    fn test_de_integer_amounts() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Transaction_real.json"
+        ));
+        let mut json: serde_json::Value = serde_json::from_str(json).unwrap();
+        json["netAmount"] = (-12).into();
+        for item in json["transferItems"].as_array_mut().unwrap() {
+            item["amount"] = 36.into();
+            item["cost"] = (-12).into();
+            item["price"] = 48.into();
+            if let Some(price) = item["instrument"].get_mut("closingPrice") {
+                *price = 50.into();
+            }
+        }
+        let json = json.to_string();
+        assert!(json.contains(r#""netAmount":-12,"#), "{json}");
+
+        let val = serde_json::from_str::<Transaction>(&json).unwrap();
+        assert!(float_cmp::approx_eq!(f64, val.net_amount, -12.0));
+        assert!(!val.transfer_items.is_empty());
+        for item in &val.transfer_items {
+            assert!(float_cmp::approx_eq!(f64, item.amount, 36.0));
+            assert!(float_cmp::approx_eq!(f64, item.cost, -12.0));
+            assert!(float_cmp::approx_eq!(f64, item.price.unwrap(), 48.0));
+        }
+    }
+
+    #[test]
+    fn test_fees() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Transaction_real.json"
+        ));
+        let mut json: serde_json::Value = serde_json::from_str(json).unwrap();
+        let mut fee = json["transferItems"][0].clone();
+        fee["amount"] = 0.into();
+        fee["cost"] = (-0.65).into();
+        fee["feeType"] = "COMMISSION".into();
+        json["transferItems"]
+            .as_array_mut()
+            .unwrap()
+            .push(fee.clone());
+        fee["cost"] = (-0.01).into();
+        fee["feeType"] = "SEC_FEE".into();
+        json["transferItems"].as_array_mut().unwrap().push(fee);
+        json["netAmount"] = (-13.0).into();
+
+        let mut val = serde_json::from_value::<Transaction>(json).unwrap();
+        assert_approx_eq!(f64, val.total_fees(), -0.66);
+        assert_approx_eq!(f64, val.principal_amount(), -12.34);
+        assert_approx_eq!(f64, val.net_amount_check(), val.net_amount);
+
+        val.transfer_items.clear();
+        assert_approx_eq!(f64, val.total_fees(), 0.0);
+        assert_approx_eq!(f64, val.principal_amount(), 0.0);
+        assert_approx_eq!(f64, val.net_amount_check(), 0.0);
+    }
+
+    #[test]
+    fn test_de_unknown_variant() {
+        let json = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/model/Trader/Transaction_real.json"
+        ));
+        let mut json: serde_json::Value = serde_json::from_str(json).unwrap();
+        json["type"] = "NEW_TRANSACTION_TYPE".into();
+        json["status"] = "NEW_STATUS".into();
+        json["subAccount"] = "NEW_SUB_ACCOUNT".into();
+        json["transferItems"][0]["positionEffect"] = "NEW_EFFECT".into();
+
+        let val = serde_json::from_value::<Transaction>(json).unwrap();
+        assert_eq!(val.type_field, TransactionType::Unknown);
+        assert_eq!(val.status, TransactionStatus::Unknown);
+        assert_eq!(val.sub_account, TransactionSubAccount::Unknown);
+        assert_eq!(
+            val.transfer_items[0].position_effect,
+            Some(TransferItemPositionEffect::Unknown)
+        );
+    }
+
     #[test]
     fn test_serde_real() {
         let json = include_str!(concat!(
@@ -517,6 +701,11 @@ mod tests {
         let val = serde_json::from_value::<Transaction>(json.clone()).unwrap();
         dbg!(&val);
 
+        let instrument = &val.transfer_items[0].instrument;
+        assert_eq!(instrument.symbol(), "BND");
+        assert_eq!(instrument.cusip(), None);
+        assert_eq!(instrument.instrument_id(), 5_224_897);
+
         let message = assert_json_matches_no_panic(
             &val,
             &json,
@@ -0,0 +1,657 @@
+//! Live quotes from Schwab's WebSocket streamer, logged in with the `streamerInfo` of
+//! [`crate::Api::get_user_preference`].
+//!
+//! Only the level one equities service is supported for now.
+//!
+//! The streamer login lasts as long as the access token, 30 minutes, so a long-lived stream logs in again
+//! with a refreshed token, see [`StreamerClient::relogin_with`].
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//!
+//! use futures::StreamExt;
+//! use schwab_api::streaming::RELOGIN_EVERY;
+//!
+//! # async fn run(api: schwab_api::Api<Arc<impl schwab_api::token::Tokener + Send + Sync + 'static>>) -> Result<(), schwab_api::Error> {
+//! let mut streamer = api
+//!     .streamer()
+//!     .await?
+//!     .relogin_with(api.tokener.clone(), RELOGIN_EVERY);
+//! streamer.subscribe_level_one_equities(&["AAPL", "VTI"]).await?;
+//! while let Some(update) = streamer.next().await {
+//!     println!("{:?}", update?);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use futures::{SinkExt, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use crate::error::Error;
+use crate::model::trader::user_preference::StreamerInfo;
+use crate::token::DynTokener;
+
+const LEVELONE_EQUITIES: &str = "LEVELONE_EQUITIES";
+
+/// The fields of [`LevelOneEquity`], by their number in the streamer documentation.
+const LEVELONE_EQUITIES_FIELDS: &str = "0,1,2,3,4,5,8,9,10,11,12,17,18,33,34,35";
+
+/// A period for [`StreamerClient::relogin_with`], ahead of the 30 minutes an access token lasts.
+pub const RELOGIN_EVERY: Duration = Duration::from_secs(RELOGIN_EVERY_SECS);
+
+const RELOGIN_EVERY_SECS: u64 = 25 * 60;
+
+/// A connection to the streamer, yielding the updates of its subscriptions as a [`Stream`].
+///
+/// The stream ends when the streamer closes the connection, [`StreamerClient::reconnect`] resumes it.
+#[derive(Debug)]
+pub struct StreamerClient {
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    info: StreamerInfo,
+    request_id: u64,
+    pending: VecDeque<Result<LevelOneEquity, Error>>,
+    outgoing: VecDeque<Message>,
+    /// The symbols of the last level one equities subscription, sent again on reconnect.
+    level_one_equities: Option<String>,
+    relogin: Option<Relogin>,
+}
+
+/// Logs in again periodically with the access token of a [`DynTokener`].
+struct Relogin {
+    tokener: Arc<dyn DynTokener>,
+    every: Duration,
+    sleep: Pin<Box<tokio::time::Sleep>>,
+    access_token: Option<BoxFuture<'static, Result<String, Error>>>,
+}
+
+impl std::fmt::Debug for Relogin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Relogin")
+            .field("every", &self.every)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StreamerClient {
+    /// Connect to `info.streamer_socket_url` and log in with `access_token`.
+    pub async fn connect(info: StreamerInfo, access_token: &str) -> Result<Self, Error> {
+        let ws = Self::open(&info).await?;
+        let mut client = Self {
+            ws,
+            info,
+            request_id: 0,
+            pending: VecDeque::new(),
+            outgoing: VecDeque::new(),
+            level_one_equities: None,
+            relogin: None,
+        };
+        client.relogin(access_token).await?;
+
+        Ok(client)
+    }
+
+    /// Log in again every `every` with the access token of `tokener`, refreshed by it when needed,
+    /// so that the stream outlives the access token it was opened with.
+    ///
+    /// The login is sent while the stream is polled, a rejected one is yielded as an [`Error::Streamer`].
+    #[must_use]
+    pub fn relogin_with(mut self, tokener: Arc<dyn DynTokener>, every: Duration) -> Self {
+        self.relogin = Some(Relogin {
+            tokener,
+            every,
+            sleep: Box::pin(tokio::time::sleep(every)),
+            access_token: None,
+        });
+        self
+    }
+
+    /// Log in again on the open connection with `access_token`, e.g. a refreshed one.
+    pub async fn relogin(&mut self, access_token: &str) -> Result<(), Error> {
+        let (request_id, request) = self.request("ADMIN", "LOGIN", &self.login(access_token));
+        self.ws.send(request).await.map_err(Box::new)?;
+        self.wait_response(&request_id).await
+    }
+
+    /// Open a new connection, log in with `access_token` and subscribe again to the symbols subscribed before.
+    pub async fn reconnect(&mut self, access_token: &str) -> Result<(), Error> {
+        self.ws = Self::open(&self.info).await?;
+        self.outgoing.clear();
+        self.relogin(access_token).await?;
+        if let Some(relogin) = &mut self.relogin {
+            relogin.access_token = None;
+            relogin
+                .sleep
+                .as_mut()
+                .reset(tokio::time::Instant::now() + relogin.every);
+        }
+
+        if let Some(symbols) = self.level_one_equities.clone() {
+            self.send_level_one_equities(symbols).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Subscribe to the level one quotes of `symbols`, replacing the symbols subscribed before.
+    ///
+    /// A rejected subscription is yielded by the stream as an [`Error::Streamer`].
+    pub async fn subscribe_level_one_equities(&mut self, symbols: &[&str]) -> Result<(), Error> {
+        let symbols = symbols.join(",");
+        self.level_one_equities = Some(symbols.clone());
+        self.send_level_one_equities(symbols).await
+    }
+
+    /// Log out and close the connection.
+    pub async fn logout(mut self) -> Result<(), Error> {
+        let (_, request) = self.request("ADMIN", "LOGOUT", &json!({}));
+        self.ws.send(request).await.map_err(Box::new)?;
+        self.ws.close(None).await.map_err(Box::new)?;
+        Ok(())
+    }
+
+    async fn open(
+        info: &StreamerInfo,
+    ) -> Result<WebSocketStream<MaybeTlsStream<TcpStream>>, Error> {
+        let (ws, _) = tokio_tungstenite::connect_async(info.streamer_socket_url.as_str())
+            .await
+            .map_err(Box::new)?;
+        Ok(ws)
+    }
+
+    async fn send_level_one_equities(&mut self, symbols: String) -> Result<(), Error> {
+        let parameters = json!({
+            "keys": symbols,
+            "fields": LEVELONE_EQUITIES_FIELDS,
+        });
+        let (_, request) = self.request(LEVELONE_EQUITIES, "SUBS", &parameters);
+        self.ws.send(request).await.map_err(Box::new)?;
+        Ok(())
+    }
+
+    fn login(&self, access_token: &str) -> serde_json::Value {
+        json!({
+            "Authorization": access_token,
+            "SchwabClientChannel": self.info.schwab_client_channel,
+            "SchwabClientFunctionId": self.info.schwab_client_function_id,
+        })
+    }
+
+    /// The next request and its ID.
+    fn request(
+        &mut self,
+        service: &str,
+        command: &str,
+        parameters: &serde_json::Value,
+    ) -> (String, Message) {
+        let request_id = self.request_id.to_string();
+        self.request_id += 1;
+
+        let request = json!({
+            "requests": [{
+                "service": service,
+                "command": command,
+                "requestid": request_id,
+                "SchwabClientCustomerId": self.info.schwab_client_customer_id,
+                "SchwabClientCorrelId": self.info.schwab_client_correl_id,
+                "parameters": parameters,
+            }]
+        });
+
+        (request_id, Message::text(request.to_string()))
+    }
+
+    /// Read until the response to `request_id`, keeping the updates received meanwhile.
+    async fn wait_response(&mut self, request_id: &str) -> Result<(), Error> {
+        while let Some(message) = self.ws.next().await {
+            let Message::Text(text) = message.map_err(Box::new)? else {
+                continue;
+            };
+            let message = serde_json::from_str::<StreamerMessage>(&text)?;
+            self.pending.extend(message.updates(Some(request_id)));
+            if let Some(response) = message.response.iter().find(|x| x.requestid == request_id) {
+                return response.check();
+            }
+        }
+
+        Err(Error::Streamer(format!(
+            "connection closed before the response to request {request_id}"
+        )))
+    }
+
+    /// Queue a login once the relogin period elapsed and the access token is fetched.
+    fn poll_relogin(&mut self, cx: &mut Context<'_>) -> Result<(), Error> {
+        let Some(relogin) = &mut self.relogin else {
+            return Ok(());
+        };
+        if relogin.access_token.is_none() {
+            if relogin.sleep.as_mut().poll(cx).is_pending() {
+                return Ok(());
+            }
+            let tokener = relogin.tokener.clone();
+            relogin.access_token = Some(Box::pin(
+                async move { tokener.get_access_token_boxed().await },
+            ));
+        }
+        let Some(Poll::Ready(access_token)) =
+            relogin.access_token.as_mut().map(|x| x.as_mut().poll(cx))
+        else {
+            return Ok(());
+        };
+        relogin.access_token = None;
+        relogin
+            .sleep
+            .as_mut()
+            .reset(tokio::time::Instant::now() + relogin.every);
+
+        let parameters = self.login(&access_token?);
+        let (_, request) = self.request("ADMIN", "LOGIN", &parameters);
+        self.outgoing.push_back(request);
+        Ok(())
+    }
+
+    /// Send the queued requests, as far as the connection accepts them.
+    fn poll_outgoing(&mut self, cx: &mut Context<'_>) -> Result<(), Error> {
+        while !self.outgoing.is_empty() {
+            match self.ws.poll_ready_unpin(cx) {
+                Poll::Ready(Ok(())) => {
+                    if let Some(request) = self.outgoing.pop_front() {
+                        self.ws.start_send_unpin(request).map_err(Box::new)?;
+                    }
+                }
+                Poll::Ready(Err(e)) => return Err(Box::new(e).into()),
+                Poll::Pending => return Ok(()),
+            }
+        }
+
+        match self.ws.poll_flush_unpin(cx) {
+            Poll::Ready(Err(e)) => Err(Box::new(e).into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Stream for StreamerClient {
+    type Item = Result<LevelOneEquity, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(update) = this.pending.pop_front() {
+                return Poll::Ready(Some(update));
+            }
+
+            if let Err(e) = this.poll_relogin(cx).and_then(|()| this.poll_outgoing(cx)) {
+                return Poll::Ready(Some(Err(e)));
+            }
+
+            match futures::ready!(this.ws.poll_next_unpin(cx)) {
+                None | Some(Ok(Message::Close(_))) => return Poll::Ready(None),
+                Some(Err(e)) => return Poll::Ready(Some(Err(Box::new(e).into()))),
+                Some(Ok(Message::Text(text))) => {
+                    match serde_json::from_str::<StreamerMessage>(&text) {
+                        Ok(message) => {
+                            let updates = message.updates(None);
+                            this.pending.extend(updates);
+                        }
+                        Err(e) => return Poll::Ready(Some(Err(e.into()))),
+                    }
+                }
+                // pings are answered by the websocket itself
+                Some(Ok(_)) => {}
+            }
+        }
+    }
+}
+
+/// A level one equity update.
+///
+/// Only the fields changed since the previous update of the same symbol are set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelOneEquity {
+    #[serde(rename = "key")]
+    pub symbol: String,
+    #[serde(rename = "1")]
+    pub bid_price: Option<f64>,
+    #[serde(rename = "2")]
+    pub ask_price: Option<f64>,
+    #[serde(rename = "3")]
+    pub last_price: Option<f64>,
+    #[serde(rename = "4")]
+    pub bid_size: Option<i64>,
+    #[serde(rename = "5")]
+    pub ask_size: Option<i64>,
+    #[serde(rename = "8")]
+    pub total_volume: Option<u64>,
+    #[serde(rename = "9")]
+    pub last_size: Option<i64>,
+    #[serde(rename = "10")]
+    pub high_price: Option<f64>,
+    #[serde(rename = "11")]
+    pub low_price: Option<f64>,
+    #[serde(rename = "12")]
+    pub close_price: Option<f64>,
+    #[serde(rename = "17")]
+    pub open_price: Option<f64>,
+    #[serde(rename = "18")]
+    pub net_change: Option<f64>,
+    #[serde(rename = "33")]
+    pub mark: Option<f64>,
+    /// milliseconds since Epoch
+    #[serde(rename = "34")]
+    pub quote_time: Option<i64>,
+    /// milliseconds since Epoch
+    #[serde(rename = "35")]
+    pub trade_time: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct StreamerMessage {
+    #[serde(default)]
+    response: Vec<StreamerResponse>,
+    #[serde(default)]
+    data: Vec<StreamerData>,
+}
+
+impl StreamerMessage {
+    /// The updates and the rejected requests, but the one `awaited`.
+    fn updates(&self, awaited: Option<&str>) -> Vec<Result<LevelOneEquity, Error>> {
+        let rejected = self
+            .response
+            .iter()
+            .filter(|x| x.service == LEVELONE_EQUITIES || x.service == "ADMIN")
+            .filter(|x| Some(x.requestid.as_str()) != awaited)
+            .filter_map(|x| x.check().err());
+        let updates = self
+            .data
+            .iter()
+            .filter(|x| x.service == LEVELONE_EQUITIES)
+            .flat_map(|x| &x.content)
+            .map(|x| LevelOneEquity::deserialize(x).map_err(Error::from));
+
+        rejected.map(Err).chain(updates).collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamerResponse {
+    service: String,
+    command: String,
+    requestid: String,
+    content: StreamerResponseContent,
+}
+
+impl StreamerResponse {
+    fn check(&self) -> Result<(), Error> {
+        if self.content.code == 0 {
+            return Ok(());
+        }
+
+        Err(Error::Streamer(format!(
+            "{} {} failed with code {}: {}",
+            self.service, self.command, self.content.code, self.content.msg
+        )))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamerResponseContent {
+    code: i64,
+    msg: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamerData {
+    service: String,
+    #[serde(default)]
+    content: Vec<serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::net::TcpListener;
+
+    fn info(url: String) -> StreamerInfo {
+        StreamerInfo {
+            streamer_socket_url: url,
+            schwab_client_customer_id: "customer".to_string(),
+            schwab_client_correl_id: "correl".to_string(),
+            schwab_client_channel: "N9".to_string(),
+            schwab_client_function_id: "APIAPP".to_string(),
+        }
+    }
+
+    type ServerStream = WebSocketStream<TcpStream>;
+
+    async fn read_request(ws: &mut ServerStream) -> serde_json::Value {
+        let request = ws.next().await.unwrap().unwrap();
+        serde_json::from_str(request.to_text().unwrap()).unwrap()
+    }
+
+    /// Answer the login `request` with `code`.
+    async fn send_login_response(ws: &mut ServerStream, request: &serde_json::Value, code: i64) {
+        let response = json!({
+            "response": [{
+                "service": "ADMIN",
+                "command": "LOGIN",
+                "requestid": request["requests"][0]["requestid"],
+                "SchwabClientCorrelId": "correl",
+                "timestamp": 1_715_908_546_054_i64,
+                "content": { "code": code, "msg": "server=s0635dc6-1;status=PN" }
+            }]
+        });
+        ws.send(Message::text(response.to_string())).await.unwrap();
+    }
+
+    fn level_one_equity(symbol: &str, last_price: f64) -> Message {
+        let data = json!({
+            "data": [{
+                "service": "LEVELONE_EQUITIES",
+                "timestamp": 1_715_908_546_300_i64,
+                "command": "SUBS",
+                "content": [{ "key": symbol, "delayed": false, "3": last_price }]
+            }]
+        });
+        Message::text(data.to_string())
+    }
+
+    /// Accept one connection, answer its login with `login_code`, then play `messages` after its next request.
+    async fn mock_streamer(
+        login_code: i64,
+        messages: Vec<serde_json::Value>,
+    ) -> (String, tokio::task::JoinHandle<Vec<serde_json::Value>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let mut requests = vec![];
+
+            let login = read_request(&mut ws).await;
+            send_login_response(&mut ws, &login, login_code).await;
+            requests.push(login);
+            if login_code != 0 {
+                return requests;
+            }
+
+            requests.push(read_request(&mut ws).await);
+            for message in messages {
+                ws.send(Message::text(message.to_string())).await.unwrap();
+            }
+            ws.close(None).await.unwrap();
+
+            requests
+        });
+
+        (url, server)
+    }
+
+    #[tokio::test]
+    async fn test_streamer_client() {
+        let messages = vec![
+            json!({
+                "response": [{
+                    "service": "LEVELONE_EQUITIES",
+                    "command": "SUBS",
+                    "requestid": "1",
+                    "SchwabClientCorrelId": "correl",
+                    "timestamp": 1_715_908_546_200_i64,
+                    "content": { "code": 0, "msg": "SUBS command succeeded" }
+                }]
+            }),
+            json!({ "notify": [{ "heartbeat": "1715908556222" }] }),
+            json!({
+                "data": [{
+                    "service": "LEVELONE_EQUITIES",
+                    "timestamp": 1_715_908_546_300_i64,
+                    "command": "SUBS",
+                    "content": [
+                        { "key": "AAPL", "delayed": false, "1": 189.9, "2": 190.01, "3": 190.0, "8": 53_845_189 },
+                        { "key": "VTI", "delayed": false, "33": 260.27 }
+                    ]
+                }]
+            }),
+        ];
+        let (url, server) = mock_streamer(0, messages).await;
+
+        let mut streamer = StreamerClient::connect(info(url), "access").await.unwrap();
+        streamer
+            .subscribe_level_one_equities(&["AAPL", "VTI"])
+            .await
+            .unwrap();
+        let updates: Vec<_> = streamer.map(Result::unwrap).collect().await;
+
+        let requests = server.await.unwrap();
+        assert_eq!(requests[0]["requests"][0]["command"], "LOGIN");
+        assert_eq!(
+            requests[0]["requests"][0]["parameters"]["Authorization"],
+            "access"
+        );
+        assert_eq!(
+            requests[0]["requests"][0]["SchwabClientCustomerId"],
+            "customer"
+        );
+        assert_eq!(requests[1]["requests"][0]["service"], LEVELONE_EQUITIES);
+        assert_eq!(requests[1]["requests"][0]["parameters"]["keys"], "AAPL,VTI");
+
+        assert_eq!(updates.len(), 2);
+        assert_eq!(updates[0].symbol, "AAPL");
+        assert_eq!(updates[0].bid_price, Some(189.9));
+        assert_eq!(updates[0].total_volume, Some(53_845_189));
+        assert_eq!(updates[0].mark, None);
+        assert_eq!(updates[1].symbol, "VTI");
+        assert_eq!(updates[1].mark, Some(260.27));
+    }
+
+    #[tokio::test]
+    async fn test_streamer_client_login_failed() {
+        let (url, server) = mock_streamer(3, vec![]).await;
+
+        let result = StreamerClient::connect(info(url), "expired").await;
+        assert!(matches!(result, Err(Error::Streamer(_))));
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_streamer_client_relogin() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let mut requests = vec![];
+
+            // the login, then the one after the refresh
+            for _ in 0..2 {
+                let login = read_request(&mut ws).await;
+                send_login_response(&mut ws, &login, 0).await;
+                requests.push(login);
+            }
+            ws.send(level_one_equity("AAPL", 190.0)).await.unwrap();
+            ws.close(None).await.unwrap();
+
+            requests
+        });
+
+        let tokener = crate::token::StaticTokener::new("refreshed".to_string());
+        let streamer = StreamerClient::connect(info(url), "access")
+            .await
+            .unwrap()
+            .relogin_with(Arc::new(tokener), Duration::from_millis(50));
+        let updates: Vec<_> = streamer.map(Result::unwrap).collect().await;
+
+        let requests = server.await.unwrap();
+        assert_eq!(
+            requests[0]["requests"][0]["parameters"]["Authorization"],
+            "access"
+        );
+        assert_eq!(requests[1]["requests"][0]["command"], "LOGIN");
+        assert_eq!(
+            requests[1]["requests"][0]["parameters"]["Authorization"],
+            "refreshed"
+        );
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].last_price, Some(190.0));
+    }
+
+    #[tokio::test]
+    async fn test_streamer_client_reconnect() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut requests = vec![];
+
+            // dropped after the subscription, then served
+            for last_price in [None, Some(190.0)] {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                let login = read_request(&mut ws).await;
+                send_login_response(&mut ws, &login, 0).await;
+                requests.push(login);
+                requests.push(read_request(&mut ws).await);
+                if let Some(last_price) = last_price {
+                    ws.send(level_one_equity("AAPL", last_price)).await.unwrap();
+                }
+                ws.close(None).await.unwrap();
+            }
+
+            requests
+        });
+
+        let mut streamer = StreamerClient::connect(info(url), "access").await.unwrap();
+        streamer
+            .subscribe_level_one_equities(&["AAPL"])
+            .await
+            .unwrap();
+        assert!(streamer.next().await.is_none());
+
+        streamer.reconnect("refreshed").await.unwrap();
+        let updates: Vec<_> = streamer.map(Result::unwrap).collect().await;
+
+        let requests = server.await.unwrap();
+        assert_eq!(requests[2]["requests"][0]["command"], "LOGIN");
+        assert_eq!(
+            requests[2]["requests"][0]["parameters"]["Authorization"],
+            "refreshed"
+        );
+        assert_eq!(requests[3]["requests"][0]["command"], "SUBS");
+        assert_eq!(requests[3]["requests"][0]["parameters"]["keys"], "AAPL");
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].symbol, "AAPL");
+    }
+}
@@ -2,39 +2,157 @@
 
 pub(crate) mod auth;
 pub mod channel_messenger;
+pub mod store;
 
-use chrono::TimeDelta;
+use chrono::{DateTime, TimeDelta, Utc};
+use futures::future::BoxFuture;
 use oauth2::TokenResponse;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Write};
 use std::path::PathBuf;
-use tokio::sync::Mutex;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 
 use crate::error::Error;
 use auth::Authorizer;
-use channel_messenger::local_server::LocalServerMessenger;
+use channel_messenger::event_messenger::{AuthRequiredEvent, EventMessenger};
+use channel_messenger::local_server::{LocalServerMessenger, RustlsConfig};
 use channel_messenger::stdio_messenger::StdioMessenger;
 use channel_messenger::ChannelMessenger;
+use channel_messenger::StdioAuthChannel;
+use store::{FileTokenStore, TokenStore};
 
 pub trait Tokener {
     fn get_access_token(&self) -> impl std::future::Future<Output = Result<String, Error>> + Send;
 
     fn redo_authorization(&self) -> impl std::future::Future<Output = Result<(), Error>> + Send;
+
+    /// When the current access token expires, `None` if unknown
+    fn access_token_expires_at(
+        &self,
+    ) -> impl std::future::Future<Output = Option<DateTime<Utc>>> + Send {
+        async { None }
+    }
+
+    /// When the refresh token expires and authorization is required again, `None` if unknown
+    fn refresh_token_expires_at(
+        &self,
+    ) -> impl std::future::Future<Output = Option<DateTime<Utc>>> + Send {
+        async { None }
+    }
+}
+
+/// Object-safe counterpart of [`Tokener`], implemented for every `Tokener + Send + Sync`.
+///
+/// `Box<dyn DynTokener>` is itself a [`Tokener`], so `Api<Box<dyn DynTokener>>` can hold any checker,
+/// e.g. `Api::new(Box::new(checker) as Box<dyn DynTokener>, client)`.
+pub trait DynTokener: Send + Sync {
+    fn get_access_token_boxed(&self) -> BoxFuture<'_, Result<String, Error>>;
+
+    fn redo_authorization_boxed(&self) -> BoxFuture<'_, Result<(), Error>>;
+
+    fn access_token_expires_at_boxed(&self) -> BoxFuture<'_, Option<DateTime<Utc>>>;
+
+    fn refresh_token_expires_at_boxed(&self) -> BoxFuture<'_, Option<DateTime<Utc>>>;
+}
+
+impl<T: Tokener + Send + Sync> DynTokener for T {
+    fn get_access_token_boxed(&self) -> BoxFuture<'_, Result<String, Error>> {
+        Box::pin(self.get_access_token())
+    }
+
+    fn redo_authorization_boxed(&self) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(self.redo_authorization())
+    }
+
+    fn access_token_expires_at_boxed(&self) -> BoxFuture<'_, Option<DateTime<Utc>>> {
+        Box::pin(self.access_token_expires_at())
+    }
+
+    fn refresh_token_expires_at_boxed(&self) -> BoxFuture<'_, Option<DateTime<Utc>>> {
+        Box::pin(self.refresh_token_expires_at())
+    }
+}
+
+impl<T: Tokener + Send + Sync> Tokener for Arc<T> {
+    fn get_access_token(&self) -> impl std::future::Future<Output = Result<String, Error>> + Send {
+        self.as_ref().get_access_token()
+    }
+
+    fn redo_authorization(&self) -> impl std::future::Future<Output = Result<(), Error>> + Send {
+        self.as_ref().redo_authorization()
+    }
+
+    fn access_token_expires_at(
+        &self,
+    ) -> impl std::future::Future<Output = Option<DateTime<Utc>>> + Send {
+        self.as_ref().access_token_expires_at()
+    }
+
+    fn refresh_token_expires_at(
+        &self,
+    ) -> impl std::future::Future<Output = Option<DateTime<Utc>>> + Send {
+        self.as_ref().refresh_token_expires_at()
+    }
+}
+
+impl Tokener for Box<dyn DynTokener> {
+    fn get_access_token(&self) -> impl std::future::Future<Output = Result<String, Error>> + Send {
+        self.as_ref().get_access_token_boxed()
+    }
+
+    fn redo_authorization(&self) -> impl std::future::Future<Output = Result<(), Error>> + Send {
+        self.as_ref().redo_authorization_boxed()
+    }
+
+    fn access_token_expires_at(
+        &self,
+    ) -> impl std::future::Future<Output = Option<DateTime<Utc>>> + Send {
+        self.as_ref().access_token_expires_at_boxed()
+    }
+
+    fn refresh_token_expires_at(
+        &self,
+    ) -> impl std::future::Future<Output = Option<DateTime<Utc>>> + Send {
+        self.as_ref().refresh_token_expires_at_boxed()
+    }
 }
 
 const ACCESS_TOKEN_LIFETIME: TimeDelta = TimeDelta::minutes(25); // 25 Minutes instead of 30 min
 const REFRESH_TOKEN_LIFETIME: TimeDelta = TimeDelta::days(6); // 6 days instead of 7 days
+const REFRESH_RETRY_SECS: u64 = 60; // after a failed background refresh
 
-#[derive(Debug)]
-pub struct TokenChecker<CM: ChannelMessenger> {
-    path: PathBuf,
+pub struct TokenChecker<CM: ChannelMessenger, S: TokenStore = FileTokenStore> {
+    store: S,
     authorizer: Authorizer<CM>,
-    token: Mutex<Token>,
+    token: RwLock<Token>,
+    on_refresh: Option<OnRefresh>,
+}
+
+/// Called with every new [`Token`], see [`TokenChecker::on_refresh`].
+pub type OnRefresh = Box<dyn Fn(&Token) + Send + Sync>;
+
+impl<CM: ChannelMessenger + std::fmt::Debug, S: TokenStore + std::fmt::Debug> std::fmt::Debug
+    for TokenChecker<CM, S>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenChecker")
+            .field("store", &self.store)
+            .field("authorizer", &self.authorizer)
+            .field("token", &self.token)
+            .field("on_refresh", &self.on_refresh.is_some())
+            .finish()
+    }
 }
 
 impl<CM: ChannelMessenger> TokenChecker<CM> {
+    /// Authorize through any [`ChannelMessenger`], e.g. a web hook or a chat bot for headless services.
+    ///
+    /// [`TokenChecker::new_with_stdio`], [`TokenChecker::new_with_local_server`] and
+    /// [`TokenChecker::new_with_auth_required_channel`] are the built-in ones.
     pub async fn new_with_custom_auth(
         path: PathBuf,
         client_id: String,
@@ -42,46 +160,240 @@ impl<CM: ChannelMessenger> TokenChecker<CM> {
         redirect_url: String,
         async_client: Client,
         messenger: CM,
+    ) -> Result<Self, Error> {
+        Self::new_with_store(
+            FileTokenStore(path),
+            client_id,
+            secret,
+            redirect_url,
+            async_client,
+            messenger,
+            None,
+        )
+        .await
+    }
+
+    /// Same as [`TokenChecker::new_with_custom_auth`], named after [`AuthChannel`](channel_messenger::AuthChannel).
+    pub async fn new_with_channel(
+        path: PathBuf,
+        client_id: String,
+        secret: String,
+        redirect_url: String,
+        async_client: Client,
+        channel: CM,
+    ) -> Result<Self, Error> {
+        Self::new_with_custom_auth(path, client_id, secret, redirect_url, async_client, channel)
+            .await
+    }
+
+    /// Bootstraps the token file from an existing refresh token, skipping the browser-based OAuth flow.
+    ///
+    /// The refresh token is exchanged immediately, so an expired or invalid one is reported as [`Error::Token`].
+    /// Since Schwab doesn't report its expiration, the refresh token is assumed to be freshly issued.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the expiration time is out of range
+    pub async fn from_refresh_token(
+        path: PathBuf,
+        client_id: String,
+        secret: String,
+        redirect_url: String,
+        refresh_token: String,
+        async_client: Client,
+        messenger: CM,
     ) -> Result<Self, Error> {
         let authorizer =
             Authorizer::new(client_id, secret, redirect_url, async_client, messenger).await?;
+        Self::from_refresh_token_with(path, refresh_token, authorizer).await
+    }
+
+    async fn from_refresh_token_with(
+        path: PathBuf,
+        refresh_token: String,
+        authorizer: Authorizer<CM>,
+    ) -> Result<Self, Error> {
+        let rsp = authorizer
+            .access_token(&refresh_token)
+            .await
+            .map_err(|e| Error::Token(e.to_string()))?;
 
-        let token = match Token::load(path.clone()) {
-            Ok(token) => token,
-            Err(_) => authorizer.save(path.clone()).await?,
+        let token = Token {
+            refresh: rsp
+                .refresh_token()
+                .map_or(refresh_token, |x| x.secret().clone()),
+            refresh_expires_in: chrono::Utc::now()
+                .checked_add_signed(REFRESH_TOKEN_LIFETIME)
+                .expect("refresh_expires_in"),
+            access: rsp.access_token().secret().clone(),
+            access_expires_in: chrono::Utc::now()
+                .checked_add_signed(ACCESS_TOKEN_LIFETIME)
+                .expect("access_expires_in"),
+            type_: rsp.token_type().as_ref().to_string(),
+        };
+        let store = FileTokenStore(path);
+        store.save(&token).await?;
+
+        Ok(Self {
+            store,
+            authorizer,
+            token: RwLock::new(token),
+            on_refresh: None,
+        })
+    }
+}
+
+impl<CM: ChannelMessenger, S: TokenStore> TokenChecker<CM, S> {
+    /// Keep the token in any [`TokenStore`] instead of a local file.
+    ///
+    /// `on_refresh` is set up before the token is loaded, so unlike [`TokenChecker::on_refresh`]
+    /// it also sees the authorization or refresh done while constructing the checker.
+    pub async fn new_with_store(
+        store: S,
+        client_id: String,
+        secret: String,
+        redirect_url: String,
+        async_client: Client,
+        messenger: CM,
+        on_refresh: Option<OnRefresh>,
+    ) -> Result<Self, Error> {
+        let authorizer =
+            Authorizer::new(client_id, secret, redirect_url, async_client, messenger).await?;
+        Self::new_with_authorizer(store, authorizer, on_refresh).await
+    }
+
+    async fn new_with_authorizer(
+        store: S,
+        authorizer: Authorizer<CM>,
+        on_refresh: Option<OnRefresh>,
+    ) -> Result<Self, Error> {
+        let loaded = store.load().await?;
+        let authorized = loaded.is_none();
+        let token = match loaded {
+            Some(token) => token,
+            None => Self::authorize(&authorizer, &store).await?,
         };
 
         let checker = Self {
-            path,
+            store,
             authorizer,
-            token: Mutex::new(token),
+            token: RwLock::new(token),
+            on_refresh,
         };
+        if authorized {
+            checker.notify(&*checker.token.read().await);
+        }
 
         checker.check_or_update().await?;
 
         Ok(checker)
     }
 
+    /// Call `callback` with the new token after each refresh and authorization, e.g. to copy it to a
+    /// second store or to count refreshes.
+    ///
+    /// The token obtained while constructing the checker is already saved to its store, but not
+    /// passed to `callback`; give it to [`TokenChecker::new_with_store`] to see that one too.
+    #[must_use]
+    pub fn on_refresh(mut self, callback: OnRefresh) -> Self {
+        self.on_refresh = Some(callback);
+        self
+    }
+
+    fn notify(&self, token: &Token) {
+        if let Some(callback) = &self.on_refresh {
+            callback(token);
+        }
+    }
+
+    async fn authorize(authorizer: &Authorizer<CM>, store: &S) -> Result<Token, Error> {
+        let token = authorizer
+            .authorize()
+            .await
+            .map_err(|e| Error::Token(e.to_string()))?;
+        store.save(&token).await?;
+        Ok(token)
+    }
+
+    /// Spawns a task refreshing the access token `before_expiry` it expires,
+    /// so [`Tokener::get_access_token`] rarely has to refresh inline.
+    ///
+    /// `before_expiry` is capped at half of the access token lifetime.
+    /// The task stops once the checker is dropped, or when the returned handle is aborted.
+    /// Share the checker with [`crate::Api`] through the same [`Arc`].
+    pub fn start_background_refresh(
+        self: &Arc<Self>,
+        before_expiry: std::time::Duration,
+    ) -> JoinHandle<()>
+    where
+        CM: 'static,
+        S: 'static,
+    {
+        let margin = TimeDelta::from_std(before_expiry)
+            .unwrap_or(TimeDelta::MAX)
+            .min(ACCESS_TOKEN_LIFETIME / 2);
+        let checker = Arc::downgrade(self);
+
+        tokio::spawn(async move {
+            loop {
+                let expires_in = match checker.upgrade() {
+                    Some(checker) => checker.token.read().await.access_expires_in,
+                    None => break,
+                };
+
+                let wait = (expires_in - margin - chrono::Utc::now())
+                    .to_std()
+                    .unwrap_or_default();
+                tokio::time::sleep(wait).await;
+
+                let Some(checker) = checker.upgrade() else {
+                    break;
+                };
+                if checker.update(margin).await.is_err() {
+                    // retry later instead of spinning
+                    tokio::time::sleep(std::time::Duration::from_secs(REFRESH_RETRY_SECS)).await;
+                }
+            }
+        })
+    }
+
     async fn check_or_update(&self) -> Result<(), Error> {
-        let mut token = self.token.lock().await;
-        if token.is_access_valid() {
+        self.update(TimeDelta::zero()).await
+    }
+
+    /// update unless the access token is still valid after `margin`
+    ///
+    /// The write lock is held through the refresh, so concurrent callers wait for it and then find the token valid.
+    async fn update(&self, margin: TimeDelta) -> Result<(), Error> {
+        let mut token = self.token.write().await;
+        if token.is_access_valid_for(margin) {
             return Ok(());
         }
 
         if token.is_refresh_valid() {
-            if let Ok(rsp) = self.authorizer.access_token(&token.refresh).await {
-                token.access.clone_from(rsp.access_token().secret());
-                token.access_expires_in = chrono::Utc::now()
-                    .checked_add_signed(ACCESS_TOKEN_LIFETIME)
-                    .expect("access_expires_in");
+            match self.authorizer.access_token(&token.refresh).await {
+                Ok(rsp) => {
+                    token.access.clone_from(rsp.access_token().secret());
+                    token.access_expires_in = chrono::Utc::now()
+                        .checked_add_signed(ACCESS_TOKEN_LIFETIME)
+                        .expect("access_expires_in");
 
-                token.save(self.path.clone())?;
+                    self.store.save(&token).await?;
+                    self.notify(&token);
 
-                return Ok(());
+                    return Ok(());
+                }
+                // transient, e.g. network, so no need to authorize interactively
+                Err(oauth2::RequestTokenError::Request(e)) => {
+                    return Err(Error::Token(e.to_string()));
+                }
+                // rejected by the token endpoint
+                Err(_) => {}
             }
         }
 
-        *token = self.authorizer.save(self.path.clone()).await?;
+        *token = Self::authorize(&self.authorizer, &self.store).await?;
+        self.notify(&token);
         Ok(())
     }
 }
@@ -96,24 +408,65 @@ impl TokenChecker<LocalServerMessenger> {
         async_client: Client,
     ) -> Result<Self, Error> {
         let messenger = LocalServerMessenger::new(&certs_dir).await;
-
-        let authorizer =
-            Authorizer::new(client_id, secret, redirect_url, async_client, messenger).await?;
-
-        let token = match Token::load(path.clone()) {
-            Ok(token) => token,
-            Err(_) => authorizer.save(path.clone()).await?,
-        };
-
-        let checker = Self {
+        Self::new_with_custom_auth(
             path,
-            authorizer,
-            token: Mutex::new(token),
-        };
+            client_id,
+            secret,
+            redirect_url,
+            async_client,
+            messenger,
+        )
+        .await
+    }
+}
 
-        checker.check_or_update().await?;
+impl TokenChecker<LocalServerMessenger> {
+    /// Like [`TokenChecker::new_with_local_server`], with the TLS configuration of the callback server
+    /// given directly instead of a `certs_dir`, e.g. [`RustlsConfig::from_pem`] with certificates kept in memory.
+    pub async fn new_with_tls(
+        path: PathBuf,
+        client_id: String,
+        secret: String,
+        redirect_url: String,
+        tls: RustlsConfig,
+        async_client: Client,
+    ) -> Result<Self, Error> {
+        Self::new_with_custom_auth(
+            path,
+            client_id,
+            secret,
+            redirect_url,
+            async_client,
+            LocalServerMessenger::new_with_tls(tls),
+        )
+        .await
+    }
+}
 
-        Ok(checker)
+impl TokenChecker<EventMessenger> {
+    /// Sends an [`AuthRequiredEvent`] on `tx` whenever the refresh token is rejected by Schwab,
+    /// then waits for the new authorization code replied through the event.
+    ///
+    /// Transient failures, e.g. network errors, are returned as [`Error::Token`] without any event.
+    /// If no token is saved at `path` yet, the first event is sent during construction.
+    pub async fn new_with_auth_required_channel(
+        path: PathBuf,
+        client_id: String,
+        secret: String,
+        redirect_url: String,
+        async_client: Client,
+        tx: async_channel::Sender<AuthRequiredEvent>,
+    ) -> Result<Self, Error> {
+        let messenger = EventMessenger::new(tx);
+        Self::new_with_custom_auth(
+            path,
+            client_id,
+            secret,
+            redirect_url,
+            async_client,
+            messenger,
+        )
+        .await
     }
 }
 
@@ -125,38 +478,86 @@ impl TokenChecker<StdioMessenger> {
         redirect_url: String,
         async_client: Client,
     ) -> Result<Self, Error> {
-        let messenger = StdioMessenger::new();
-        Self::new_with_custom_auth(
+        Self::new_with_channel(
             path,
             client_id,
             secret,
             redirect_url,
             async_client,
-            messenger,
+            StdioAuthChannel::new(),
         )
         .await
     }
 }
 
-impl<CM: ChannelMessenger> Tokener for TokenChecker<CM> {
+impl<CM: ChannelMessenger, S: TokenStore> Tokener for TokenChecker<CM, S> {
     async fn get_access_token(&self) -> Result<String, Error> {
+        {
+            let token = self.token.read().await;
+            if token.is_access_valid() {
+                return Ok(token.access.clone());
+            }
+        }
+
         self.check_or_update().await?;
-        let access_token = self.token.lock().await.access.clone();
+        let access_token = self.token.read().await.access.clone();
         Ok(access_token)
     }
 
     /// must update token in Tokener
     async fn redo_authorization(&self) -> Result<(), Error> {
-        let mut token = self.token.lock().await;
-        *token = self.authorizer.save(self.path.clone()).await?;
+        let mut token = self.token.write().await;
+        *token = Self::authorize(&self.authorizer, &self.store).await?;
+        self.notify(&token);
+
+        Ok(())
+    }
+
+    /// Earlier than Schwab's 30 minutes, as the token is refreshed ahead of it
+    async fn access_token_expires_at(&self) -> Option<DateTime<Utc>> {
+        Some(self.token.read().await.access_expires_in)
+    }
+
+    /// Earlier than Schwab's 7 days, as the token is refreshed ahead of it
+    async fn refresh_token_expires_at(&self) -> Option<DateTime<Utc>> {
+        Some(self.token.read().await.refresh_expires_in)
+    }
+}
+
+/// A [`Tokener`] holding a fixed access token, which is never refreshed.
+///
+/// Useful for tests and ephemeral use-cases where the token is managed elsewhere.
+#[derive(Debug, Clone, Default)]
+pub struct StaticTokener {
+    access_token: String,
+}
+
+impl StaticTokener {
+    #[must_use]
+    pub fn new(access_token: String) -> Self {
+        Self { access_token }
+    }
 
+    /// Swap the access token, e.g. to simulate an expiry
+    pub fn set_access_token(&mut self, access_token: String) {
+        self.access_token = access_token;
+    }
+}
+
+impl Tokener for StaticTokener {
+    async fn get_access_token(&self) -> Result<String, Error> {
+        Ok(self.access_token.clone())
+    }
+
+    /// nothing to redo
+    async fn redo_authorization(&self) -> Result<(), Error> {
         Ok(())
     }
 }
 
-// Define a struct to hold the OAuth2 token
-#[derive(Serialize, Deserialize, Debug, Default)]
-struct Token {
+/// The OAuth token, serializable for any [`TokenStore`]
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Token {
     refresh: String,
     refresh_expires_in: chrono::DateTime<chrono::Utc>,
     access: String,
@@ -194,7 +595,11 @@ impl Token {
     }
 
     fn is_access_valid(&self) -> bool {
-        chrono::Utc::now() < self.access_expires_in
+        self.is_access_valid_for(TimeDelta::zero())
+    }
+
+    fn is_access_valid_for(&self, margin: TimeDelta) -> bool {
+        chrono::Utc::now() + margin < self.access_expires_in
     }
 }
 
@@ -286,6 +691,358 @@ mod tests {
         .unwrap();
     }
 
+    #[tokio::test]
+    #[ignore = "Testing manually for verification. Should be --nocapture"]
+    async fn test_token_checker_from_refresh_token() {
+        let path = dirs::home_dir()
+            .expect("home dir")
+            .join(".credentials")
+            .join("Schwab-rust.json");
+        let refresh_token = Token::load(path.clone()).unwrap().refresh;
+
+        TokenChecker::from_refresh_token(
+            path,
+            client_id_static().to_string(),
+            secret_static().to_string(),
+            callback_url_static().to_string(),
+            refresh_token,
+            Client::new(),
+            StdioMessenger::new(),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_token_checker_from_refresh_token_mock() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/oauth/token")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("grant_type".into(), "refresh_token".into()),
+                mockito::Matcher::UrlEncoded("refresh_token".into(), "refresh".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"access_token":"access","token_type":"Bearer","expires_in":1800,"refresh_token":"refresh2"}"#,
+            )
+            .create_async()
+            .await;
+
+        let path = std::env::temp_dir().join(format!(
+            "schwab_api_from_refresh_token_{}.json",
+            std::process::id()
+        ));
+        let authorizer = Authorizer::new_with_token_url(
+            "client_id".to_string(),
+            "secret".to_string(),
+            "https://127.0.0.1:8080".to_string(),
+            format!("{}/v1/oauth/token", server.url()),
+            Client::new(),
+            StdioMessenger::new(),
+        )
+        .await
+        .unwrap();
+        let checker =
+            TokenChecker::from_refresh_token_with(path.clone(), "refresh".to_string(), authorizer)
+                .await
+                .unwrap();
+        mock.assert_async().await;
+        assert_eq!(checker.get_access_token().await.unwrap(), "access");
+
+        let stored = Token::load(path.clone()).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(stored.access, "access");
+        assert_eq!(stored.refresh, "refresh2");
+        assert!(stored.is_access_valid());
+        assert!(stored.is_refresh_valid());
+    }
+
+    #[tokio::test]
+    async fn test_token_checker_new_with_store() {
+        let (tx, rx) = async_channel::unbounded();
+
+        // no token yet, so authorization is required
+        let result = TokenChecker::new_with_store(
+            store::MemoryTokenStore::default(),
+            "client_id".to_string(),
+            "secret".to_string(),
+            "https://127.0.0.1:8080".to_string(),
+            Client::new(),
+            EventMessenger::new(tx.clone()),
+            None,
+        );
+        let listener = {
+            let rx = rx.clone();
+            // give up on the authorization by dropping the reply
+            tokio::spawn(async move { drop(rx.recv().await.unwrap()) })
+        };
+        assert!(matches!(result.await, Err(Error::Token(_))));
+        listener.await.unwrap();
+
+        let token = Token {
+            refresh: "refresh".to_string(),
+            refresh_expires_in: chrono::Utc::now() + REFRESH_TOKEN_LIFETIME,
+            access: "access".to_string(),
+            access_expires_in: chrono::Utc::now() + ACCESS_TOKEN_LIFETIME,
+            type_: "Bearer".to_string(),
+        };
+        let checker = TokenChecker::new_with_store(
+            store::MemoryTokenStore::new(Some(token)),
+            "client_id".to_string(),
+            "secret".to_string(),
+            "https://127.0.0.1:8080".to_string(),
+            Client::new(),
+            EventMessenger::new(tx),
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(checker.get_access_token().await.unwrap(), "access");
+        assert!(rx.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_token_checker_refresh_with_store() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/oauth/token")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::UrlEncoded("grant_type".into(), "refresh_token".into()),
+                mockito::Matcher::UrlEncoded("refresh_token".into(), "refresh".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"access_token":"access2","token_type":"Bearer","expires_in":1800,"refresh_token":"refresh"}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        // the access token expired, the refresh token is still valid
+        let token = Token {
+            refresh: "refresh".to_string(),
+            refresh_expires_in: chrono::Utc::now() + REFRESH_TOKEN_LIFETIME,
+            access: "access".to_string(),
+            access_expires_in: chrono::Utc::now() - TimeDelta::minutes(1),
+            type_: "Bearer".to_string(),
+        };
+        let authorizer = Authorizer::new_with_token_url(
+            "client_id".to_string(),
+            "secret".to_string(),
+            "https://127.0.0.1:8080".to_string(),
+            format!("{}/v1/oauth/token", server.url()),
+            Client::new(),
+            StdioMessenger::new(),
+        )
+        .await
+        .unwrap();
+        let refreshed = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let checker = TokenChecker {
+            store: store::MemoryTokenStore::new(Some(token.clone())),
+            authorizer,
+            token: RwLock::new(token),
+            on_refresh: None,
+        }
+        .on_refresh({
+            let refreshed = refreshed.clone();
+            Box::new(move |token| {
+                assert_eq!(token.access, "access2");
+                refreshed.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            })
+        });
+
+        assert_eq!(checker.get_access_token().await.unwrap(), "access2");
+        // valid now, so not refreshed again
+        assert_eq!(checker.get_access_token().await.unwrap(), "access2");
+        mock.assert_async().await;
+        assert_eq!(refreshed.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        let stored = checker.store.load().await.unwrap().unwrap();
+        assert_eq!(stored.access, "access2");
+        assert!(stored.is_access_valid());
+    }
+
+    #[tokio::test]
+    async fn test_token_checker_on_refresh_while_constructing() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/oauth/token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"access_token":"access2","token_type":"Bearer","expires_in":1800,"refresh_token":"refresh"}"#,
+            )
+            .expect(2)
+            .create_async()
+            .await;
+        let refreshed = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let on_refresh = || -> OnRefresh {
+            let refreshed = refreshed.clone();
+            Box::new(move |token| {
+                assert_eq!(token.access, "access2");
+                refreshed.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            })
+        };
+        let (tx, rx) = async_channel::unbounded();
+        let authorizer = || {
+            Authorizer::new_with_token_url(
+                "client_id".to_string(),
+                "secret".to_string(),
+                "https://127.0.0.1:8080".to_string(),
+                format!("{}/v1/oauth/token", server.url()),
+                Client::new(),
+                EventMessenger::new(tx.clone()),
+            )
+        };
+
+        // no token yet, so the initial authorization is reported
+        let listener = tokio::spawn(async move {
+            let event: AuthRequiredEvent = rx.recv().await.unwrap();
+            event.reply.send("code".to_string()).unwrap();
+        });
+        let checker = TokenChecker::new_with_authorizer(
+            store::MemoryTokenStore::default(),
+            authorizer().await.unwrap(),
+            Some(on_refresh()),
+        )
+        .await
+        .unwrap();
+        listener.await.unwrap();
+        assert_eq!(checker.get_access_token().await.unwrap(), "access2");
+        assert_eq!(refreshed.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // the access token expired, so the refresh while constructing is reported
+        let token = Token {
+            refresh: "refresh".to_string(),
+            refresh_expires_in: chrono::Utc::now() + REFRESH_TOKEN_LIFETIME,
+            access: "access".to_string(),
+            access_expires_in: chrono::Utc::now() - TimeDelta::minutes(1),
+            type_: "Bearer".to_string(),
+        };
+        let checker = TokenChecker::new_with_authorizer(
+            store::MemoryTokenStore::new(Some(token)),
+            authorizer().await.unwrap(),
+            Some(on_refresh()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(checker.get_access_token().await.unwrap(), "access2");
+        assert_eq!(refreshed.load(std::sync::atomic::Ordering::SeqCst), 2);
+        mock.assert_async().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_token_checker_refresh_concurrently() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/oauth/token")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"access_token":"access2","token_type":"Bearer","expires_in":1800,"refresh_token":"refresh"}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let token = Token {
+            refresh: "refresh".to_string(),
+            refresh_expires_in: chrono::Utc::now() + REFRESH_TOKEN_LIFETIME,
+            access: "access".to_string(),
+            access_expires_in: chrono::Utc::now() - TimeDelta::minutes(1),
+            type_: "Bearer".to_string(),
+        };
+        let authorizer = Authorizer::new_with_token_url(
+            "client_id".to_string(),
+            "secret".to_string(),
+            "https://127.0.0.1:8080".to_string(),
+            format!("{}/v1/oauth/token", server.url()),
+            Client::new(),
+            StdioMessenger::new(),
+        )
+        .await
+        .unwrap();
+        let checker = Arc::new(TokenChecker {
+            store: store::MemoryTokenStore::new(Some(token.clone())),
+            authorizer,
+            token: RwLock::new(token),
+            on_refresh: None,
+        });
+
+        let tasks: Vec<_> = (0..50)
+            .map(|_| {
+                let checker = checker.clone();
+                tokio::spawn(async move { checker.get_access_token().await })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await.unwrap().unwrap(), "access2");
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_token_expires_at() {
+        let path = std::env::temp_dir().join(format!(
+            "schwab_api_token_expires_at_{}.json",
+            std::process::id()
+        ));
+        std::fs::write(
+            &path,
+            r#"{
+  "refresh": "refresh",
+  "refresh_expires_in": "2099-01-07T00:00:00Z",
+  "access": "access",
+  "access_expires_in": "2099-01-01T00:25:00Z",
+  "type": "Bearer"
+}"#,
+        )
+        .unwrap();
+
+        let (tx, _rx) = async_channel::unbounded();
+        let checker = TokenChecker::new_with_store(
+            FileTokenStore(path.clone()),
+            "client_id".to_string(),
+            "secret".to_string(),
+            "https://127.0.0.1:8080".to_string(),
+            Client::new(),
+            EventMessenger::new(tx),
+            None,
+        )
+        .await
+        .unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(
+            checker
+                .access_token_expires_at()
+                .await
+                .unwrap()
+                .to_rfc3339(),
+            "2099-01-01T00:25:00+00:00"
+        );
+        assert_eq!(
+            checker
+                .refresh_token_expires_at()
+                .await
+                .unwrap()
+                .to_rfc3339(),
+            "2099-01-07T00:00:00+00:00"
+        );
+
+        // through the object-safe counterpart
+        let tokener: Box<dyn DynTokener> = Box::new(checker);
+        assert!(tokener.access_token_expires_at().await.is_some());
+
+        // unknown
+        let tokener = StaticTokener::new("access".to_string());
+        assert_eq!(tokener.access_token_expires_at().await, None);
+        assert_eq!(tokener.refresh_token_expires_at().await, None);
+    }
+
     #[test]
     fn test_save_token() {
         let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -309,6 +1066,42 @@ mod tests {
         println!("{token:?}");
     }
 
+    #[tokio::test]
+    async fn test_dyn_tokener() {
+        let tokeners: Vec<Box<dyn DynTokener>> = vec![
+            Box::new(StaticTokener::new("a".to_string())),
+            Box::new(StaticTokener::new("b".to_string())),
+        ];
+
+        let mut tokens = vec![];
+        for tokener in &tokeners {
+            tokener.redo_authorization().await.unwrap();
+            tokens.push(tokener.get_access_token().await.unwrap());
+        }
+        assert_eq!(tokens, vec!["a", "b"]);
+    }
+
+    #[tokio::test]
+    async fn test_arc_tokener() {
+        let tokener = Arc::new(StaticTokener::new("token".to_string()));
+        let shared = tokener.clone();
+        assert_eq!(shared.get_access_token().await.unwrap(), "token");
+        shared.redo_authorization().await.unwrap();
+        assert_eq!(tokener.get_access_token().await.unwrap(), "token");
+    }
+
+    #[tokio::test]
+    async fn test_static_tokener() {
+        let mut tokener = StaticTokener::new("token".to_string());
+        assert_eq!(tokener.get_access_token().await.unwrap(), "token");
+
+        tokener.redo_authorization().await.unwrap();
+        assert_eq!(tokener.get_access_token().await.unwrap(), "token");
+
+        tokener.set_access_token("expired".to_string());
+        assert_eq!(tokener.get_access_token().await.unwrap(), "expired");
+    }
+
     #[test]
     fn test_token_expire_in() {
         let token = Token {
@@ -336,5 +1129,7 @@ mod tests {
 
         assert!(token.is_refresh_valid());
         assert!(token.is_access_valid());
+        assert!(token.is_access_valid_for(TimeDelta::hours(1)));
+        assert!(!token.is_access_valid_for(TimeDelta::days(2)));
     }
 }
@@ -5,7 +5,6 @@ use oauth2::{
 };
 use reqwest::Client;
 use serde::Deserialize;
-use std::path::PathBuf;
 use url::Url;
 
 use super::channel_messenger::{AuthContext, ChannelMessenger};
@@ -28,6 +27,9 @@ pub(super) struct Authorizer<CM: ChannelMessenger> {
     messenger: CM,
 }
 
+const AUTH_URL: &str = "https://api.schwabapi.com/v1/oauth/authorize";
+const TOKEN_URL: &str = "https://api.schwabapi.com/v1/oauth/token";
+
 impl<CM: ChannelMessenger> Authorizer<CM> {
     pub(super) async fn new(
         app_key: String,
@@ -35,13 +37,31 @@ impl<CM: ChannelMessenger> Authorizer<CM> {
         redirect_url: String,
         async_client: Client,
         messenger: CM,
+    ) -> Result<Self, Error> {
+        Self::new_with_token_url(
+            app_key,
+            secret,
+            redirect_url,
+            TOKEN_URL.to_string(),
+            async_client,
+            messenger,
+        )
+        .await
+    }
+
+    pub(super) async fn new_with_token_url(
+        app_key: String,
+        secret: String,
+        redirect_url: String,
+        token_url: String,
+        async_client: Client,
+        messenger: CM,
     ) -> Result<Self, Error> {
         let app_key = ClientId::new(app_key);
         let secret = ClientSecret::new(secret);
-        let auth_url = AuthUrl::new("https://api.schwabapi.com/v1/oauth/authorize".to_string())
-            .expect("Invalid authorization endpoint URL");
-        let token_url = TokenUrl::new("https://api.schwabapi.com/v1/oauth/token".to_string())
-            .expect("Invalid token endpoint URL");
+        let auth_url =
+            AuthUrl::new(AUTH_URL.to_string()).expect("Invalid authorization endpoint URL");
+        let token_url = TokenUrl::new(token_url).expect("Invalid token endpoint URL");
         let redirect_url = RedirectUrl::new(redirect_url).expect("Invalid redirect URL");
 
         let oauth2_client = BasicClient::new(app_key)
@@ -61,15 +81,10 @@ impl<CM: ChannelMessenger> Authorizer<CM> {
         Ok(auth)
     }
 
-    async fn authorize(&self) -> Result<Token, Error> {
+    pub(super) async fn authorize(&self) -> Result<Token, Error> {
         let auth_code = {
             self.messenger.send_auth_message().await?;
-            AuthorizationCode::new(
-                self.messenger
-                    .receive_auth_message()
-                    .await
-                    .expect("Failed to get auth message."),
-            )
+            AuthorizationCode::new(self.messenger.receive_auth_message().await?)
         };
 
         let token_result = self
@@ -83,11 +98,11 @@ impl<CM: ChannelMessenger> Authorizer<CM> {
                 .refresh_token()
                 .expect("should have refresh_token")
                 .secret()
-                .to_string(),
+                .clone(),
             refresh_expires_in: chrono::Utc::now()
                 .checked_add_signed(super::REFRESH_TOKEN_LIFETIME)
                 .expect("refresh_expires_in"),
-            access: token_result.access_token().secret().to_string(),
+            access: token_result.access_token().secret().clone(),
             access_expires_in: chrono::Utc::now()
                 .checked_add_signed(super::ACCESS_TOKEN_LIFETIME)
                 .expect("access_expires_in"),
@@ -142,15 +157,6 @@ impl<CM: ChannelMessenger> Authorizer<CM> {
         };
         context
     }
-
-    pub(super) async fn save(&self, path: PathBuf) -> Result<Token, Error> {
-        let token = self
-            .authorize()
-            .await
-            .map_err(|e| Error::Token(e.to_string()))?;
-        token.save(path)?;
-        Ok(token)
-    }
 }
 
 #[cfg(test)]
@@ -158,6 +164,7 @@ mod tests {
     use super::*;
 
     use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
     use std::{borrow::Cow, collections::HashMap};
 
     use crate::token::channel_messenger::compound_messenger::CompoundMessenger;
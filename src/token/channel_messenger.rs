@@ -1,4 +1,5 @@
 pub mod compound_messenger;
+pub mod event_messenger;
 pub mod local_server;
 pub mod stdio_messenger;
 
@@ -6,9 +7,16 @@ use oauth2::CsrfToken;
 use url::Url;
 
 pub use compound_messenger::CompoundMessenger;
+pub use event_messenger::{AuthRequiredEvent, EventMessenger};
 pub use local_server::LocalServerMessenger;
 pub use stdio_messenger::StdioMessenger;
 
+/// Another name for [`ChannelMessenger`], the channel delivering the manual authorization.
+pub use self::ChannelMessenger as AuthChannel;
+
+/// Another name for [`StdioMessenger`], the default [`AuthChannel`].
+pub type StdioAuthChannel = StdioMessenger;
+
 use crate::Error;
 
 #[derive(Debug, Clone)]
@@ -22,24 +30,26 @@ pub struct AuthContext {
 ///
 /// Implementors of this trait provide a way to send messages to a recipient
 /// and receive responses.
+///
+/// This is how the manual authorization is delivered, so any channel (a web hook, a chat bot,
+/// a `tokio::sync::mpsc` pair, ...) can be plugged into
+/// [`TokenChecker::new_with_custom_auth`](crate::token::TokenChecker::new_with_custom_auth).
+/// [`StdioMessenger`], [`LocalServerMessenger`] and [`EventMessenger`] are the built-in ones.
 pub trait ChannelMessenger: Sync + Send {
+    /// Receives the authorization context, i.e. the `auth_url` to send and the `csrf` to check against.
     fn with_context(
         &mut self,
         context: AuthContext,
     ) -> impl std::future::Future<Output = Result<(), Error>> + Send;
 
-    /// Transmits a message through the `tx` channel.
-    ///
-    /// # Arguments
-    ///
-    /// * `message`: The message to be sent.
+    /// Transmits the `auth_url` to the user.
     fn send_auth_message(&self) -> impl std::future::Future<Output = Result<(), Error>> + Send;
 
-    /// Receives a message from the `rx` channel.
+    /// Receives the redirected callback from the user.
     ///
     /// # Returns
     ///
-    /// The received message as a `String`.
+    /// The authorization code as a `String`.
     fn receive_auth_message(
         &self,
     ) -> impl std::future::Future<Output = Result<String, Error>> + Send;
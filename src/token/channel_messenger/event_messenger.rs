@@ -0,0 +1,110 @@
+//! A messenger that emits an event whenever authorization is required.
+
+use async_channel::Sender;
+use tokio::sync::{oneshot, Mutex};
+
+use super::{AuthContext, ChannelMessenger};
+use crate::error::Error;
+
+/// Emitted when the refresh token can no longer be used, e.g. revoked or expired.
+///
+/// Open `context.auth_url`, log in, and send the authorization code (the `code` query of the redirected URL)
+/// through `reply`. Dropping `reply` fails the pending authorization with [`Error::ChannelMessenger`].
+#[derive(Debug)]
+pub struct AuthRequiredEvent {
+    pub context: AuthContext,
+    pub reply: oneshot::Sender<String>,
+}
+
+/// Lets long-running services recover from an expired refresh token without a restart.
+#[derive(Debug)]
+pub struct EventMessenger {
+    context: Option<AuthContext>,
+    tx: Sender<AuthRequiredEvent>,
+    rx: Mutex<Option<oneshot::Receiver<String>>>,
+}
+
+impl EventMessenger {
+    #[must_use]
+    pub fn new(tx: Sender<AuthRequiredEvent>) -> Self {
+        Self {
+            context: None,
+            tx,
+            rx: Mutex::new(None),
+        }
+    }
+}
+
+impl ChannelMessenger for EventMessenger {
+    async fn with_context(&mut self, context: AuthContext) -> Result<(), Error> {
+        self.context = Some(context);
+        Ok(())
+    }
+
+    async fn send_auth_message(&self) -> Result<(), Error> {
+        let context = self
+            .context
+            .clone()
+            .ok_or(Error::ChannelMessenger("No context".to_string()))?;
+
+        let (reply, rx) = oneshot::channel();
+        *self.rx.lock().await = Some(rx);
+        self.tx
+            .send(AuthRequiredEvent { context, reply })
+            .await
+            .map_err(|e| Error::ChannelMessenger(e.to_string()))
+    }
+
+    async fn receive_auth_message(&self) -> Result<String, Error> {
+        let rx = self
+            .rx
+            .lock()
+            .await
+            .take()
+            .ok_or(Error::ChannelMessenger("No pending event".to_string()))?;
+
+        rx.await
+            .map_err(|e| Error::ChannelMessenger(format!("reply dropped: {e}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use oauth2::CsrfToken;
+
+    #[tokio::test]
+    async fn test_event_messenger() {
+        let context = AuthContext {
+            auth_url: Some("https://127.0.0.1:8081".parse().unwrap()),
+            csrf: Some(CsrfToken::new("CSRF".to_string())),
+            redirect_url: Some("https://127.0.0.1:8081".parse().unwrap()),
+        };
+
+        let (tx, rx) = async_channel::unbounded();
+        let mut messenger = EventMessenger::new(tx);
+        messenger.with_context(context).await.unwrap();
+
+        // nothing sent yet
+        assert!(messenger.receive_auth_message().await.is_err());
+
+        messenger.send_auth_message().await.unwrap();
+        let event = rx.recv().await.unwrap();
+        assert_eq!(
+            event.context.auth_url.unwrap().as_str(),
+            "https://127.0.0.1:8081/"
+        );
+        event.reply.send("code".to_string()).unwrap();
+        assert_eq!("code", messenger.receive_auth_message().await.unwrap());
+
+        // the caller gives up
+        messenger.send_auth_message().await.unwrap();
+        drop(rx.recv().await.unwrap());
+        assert!(messenger.receive_auth_message().await.is_err());
+
+        // nobody listens
+        drop(rx);
+        assert!(messenger.send_auth_message().await.is_err());
+    }
+}
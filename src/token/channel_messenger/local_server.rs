@@ -5,10 +5,14 @@ use axum::{
     routing::get,
     Router,
 };
-use axum_server::tls_rustls::RustlsConfig;
+pub use axum_server::tls_rustls::RustlsConfig;
 use oauth2::CsrfToken;
-use std::{net::SocketAddr, path::Path, result::Result};
-use url::Url;
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    path::{Path, PathBuf},
+    result::Result,
+};
+use url::{Host, Url};
 
 use super::{AuthContext, ChannelMessenger};
 use crate::{error::Error, token::auth::AuthRequest};
@@ -16,27 +20,49 @@ use crate::{error::Error, token::auth::AuthRequest};
 #[derive(Debug)]
 pub struct LocalServerMessenger {
     config: RustlsConfig,
+    certs_dir: Option<PathBuf>,
 
     addr: Option<SocketAddr>,
+    path: Option<String>,
     rx: Option<Receiver<String>>,
     app_state: Option<AppState>,
     auth_url: Option<Url>,
 }
 
 impl LocalServerMessenger {
+    /// Serve the callback with `cert.pem` and `key.pem` in `certs_dir`.
+    ///
+    /// A subdirectory named after the callback host, e.g. `certs_dir/localhost/`, takes precedence,
+    /// so each host can have a certificate issued for it.
+    ///
     /// # Panics
     ///
     /// Will panic without cert.pem and key.pem
     pub async fn new(certs_dir: &Path) -> Self {
         Self {
-            config: RustlsConfig::from_pem_file(
-                certs_dir.join("cert.pem"),
-                certs_dir.join("key.pem"),
+            certs_dir: Some(certs_dir.to_path_buf()),
+            ..Self::new_with_tls(
+                RustlsConfig::from_pem_file(certs_dir.join("cert.pem"), certs_dir.join("key.pem"))
+                    .await
+                    .expect("certs setting ok"),
             )
-            .await
-            .expect("certs setting ok"),
+        }
+    }
+
+    /// Serve the callback with the PEM encoded certificate chain and private key, e.g. loaded from a secrets manager
+    pub async fn from_pem(cert: Vec<u8>, key: Vec<u8>) -> Result<Self, Error> {
+        Ok(Self::new_with_tls(RustlsConfig::from_pem(cert, key).await?))
+    }
+
+    /// Serve the callback with any TLS configuration, e.g. built from a [`rustls::ServerConfig`](https://docs.rs/rustls)
+    #[must_use]
+    pub fn new_with_tls(config: RustlsConfig) -> Self {
+        Self {
+            config,
+            certs_dir: None,
 
             addr: None,
+            path: None,
             rx: None,
             app_state: None,
             auth_url: None,
@@ -67,6 +93,17 @@ impl ChannelMessenger for LocalServerMessenger {
                 .clone(),
         );
         self.addr = Some(parse_socket_addr(redirect_uri).map_err(Error::ChannelMessenger)?);
+        self.path = Some(redirect_uri.path().to_string());
+
+        if let Some(host_dir) = self
+            .certs_dir
+            .as_deref()
+            .and_then(|certs_dir| host_certs_dir(certs_dir, redirect_uri))
+        {
+            self.config =
+                RustlsConfig::from_pem_file(host_dir.join("cert.pem"), host_dir.join("key.pem"))
+                    .await?;
+        }
 
         Ok(())
     }
@@ -83,11 +120,13 @@ impl ChannelMessenger for LocalServerMessenger {
     }
 
     async fn receive_auth_message(&self) -> Result<String, Error> {
-        let service = app(self
-            .app_state
-            .as_ref()
-            .ok_or(Error::ChannelMessenger("No app_state".to_string()))?
-            .clone())
+        let service = app(
+            self.app_state
+                .as_ref()
+                .ok_or(Error::ChannelMessenger("No app_state".to_string()))?
+                .clone(),
+            self.path.as_deref().unwrap_or("/"),
+        )
         .into_make_service();
         tokio::spawn(
             axum_server::bind_rustls(
@@ -111,9 +150,9 @@ impl ChannelMessenger for LocalServerMessenger {
     }
 }
 
-fn app(app_state: AppState) -> Router {
+fn app(app_state: AppState, path: &str) -> Router {
     Router::new()
-        .route("/", get(get_code))
+        .route(path, get(get_code))
         .with_state(app_state)
 }
 
@@ -143,17 +182,29 @@ async fn get_code(
 }
 
 fn parse_socket_addr(url: &Url) -> Result<SocketAddr, String> {
-    let Some(hostname) = url.host_str() else {
-        return Err("No hostname found in URL".to_string());
-    };
+    let port = url.port_or_known_default().unwrap_or(443); // default to HTTPS port if not specified
+
+    match url.host() {
+        None => Err("No hostname found in URL".to_string()),
+        Some(Host::Ipv4(ip)) => Ok(SocketAddr::new(ip.into(), port)),
+        Some(Host::Ipv6(ip)) => Ok(SocketAddr::new(ip.into(), port)),
+        Some(Host::Domain("localhost")) => Ok(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port)),
+        Some(Host::Domain(hostname)) => format!("{hostname}:{port}")
+            .parse::<SocketAddr>()
+            .map_err(|err| format!("Failed to parse socket address: {err}")),
+    }
+}
 
-    let port = url.port().unwrap_or(443); // default to HTTPS port if not specified
+/// The subdirectory of `certs_dir` named after the host of `url`, if it has a cert.pem
+fn host_certs_dir(certs_dir: &Path, url: &Url) -> Option<PathBuf> {
+    let host = match url.host()? {
+        Host::Ipv4(ip) => ip.to_string(),
+        Host::Ipv6(ip) => ip.to_string(),
+        Host::Domain(hostname) => hostname.to_string(),
+    };
+    let dir = certs_dir.join(host);
 
-    let addr = format!("{hostname}:{port}");
-    match addr.parse::<SocketAddr>() {
-        Ok(addr) => Ok(addr),
-        Err(err) => Err(format!("Failed to parse socket address: {err}")),
-    }
+    dir.join("cert.pem").is_file().then_some(dir)
 }
 
 #[cfg(test)]
@@ -201,6 +252,21 @@ mod tests {
         let addr = parse_socket_addr(&"https://192.168.1.1:8080".parse().unwrap()).unwrap();
         assert_eq!(addr, expected_addr);
 
+        // IPv6 address with port
+        let expected_addr = SocketAddr::from((std::net::Ipv6Addr::LOCALHOST, 8080));
+        let addr = parse_socket_addr(&"https://[::1]:8080".parse().unwrap()).unwrap();
+        assert_eq!(addr, expected_addr);
+
+        // URL with a path after the port
+        let expected_addr = SocketAddr::from(([127, 0, 0, 1], 9443));
+        let addr = parse_socket_addr(&"https://127.0.0.1:9443/callback".parse().unwrap()).unwrap();
+        assert_eq!(addr, expected_addr);
+
+        // localhost
+        let expected_addr = SocketAddr::from(([127, 0, 0, 1], 8080));
+        let addr = parse_socket_addr(&"https://localhost:8080".parse().unwrap()).unwrap();
+        assert_eq!(addr, expected_addr);
+
         // URL with hostname and port; for now, this is not supported
         let addr = parse_socket_addr(&"http://example.com:80".parse().unwrap()).unwrap_err();
         assert_eq!(
@@ -219,7 +285,7 @@ mod tests {
             .build()
             .unwrap();
 
-        let response = app(config(csrf, tx))
+        let response = app(config(csrf, tx), "/")
             .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
             .await
             .unwrap();
@@ -235,6 +301,121 @@ mod tests {
         assert_eq!(rx.recv().await.unwrap(), "code");
     }
 
+    #[tokio::test]
+    async fn test_router_with_path() {
+        let (tx, rx) = async_channel::unbounded();
+        let csrf = CsrfToken::new_random();
+
+        let uri = Uri::builder()
+            .path_and_query(format!("/callback?code=code&state={}", csrf.secret()))
+            .build()
+            .unwrap();
+
+        let response = app(config(csrf, tx), "/callback")
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(rx.recv().await.unwrap(), "code");
+    }
+
+    #[tokio::test]
+    async fn test_local_server_messenger_from_pem() {
+        let cert = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/certs/cert.pem"));
+        let key = include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/certs/key.pem"));
+        assert!(
+            LocalServerMessenger::from_pem(b"not a cert".to_vec(), key.to_vec())
+                .await
+                .is_err()
+        );
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let context = AuthContext {
+            auth_url: Some("https://127.0.0.1/".parse().unwrap()),
+            csrf: Some(CsrfToken::new("CSRF".to_string())),
+            redirect_url: Some(
+                format!("https://127.0.0.1:{port}/callback")
+                    .parse()
+                    .unwrap(),
+            ),
+        };
+        let mut messenger = LocalServerMessenger::from_pem(cert.to_vec(), key.to_vec())
+            .await
+            .unwrap();
+        messenger.with_context(context).await.unwrap();
+        let code = tokio::spawn(async move { messenger.receive_auth_message().await });
+
+        // the self-signed certificate is served
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .build()
+            .unwrap();
+        let url = format!("https://127.0.0.1:{port}/callback?code=code&state=CSRF");
+        let response = loop {
+            match client.get(&url).send().await {
+                Ok(response) => break response,
+                // not listening yet
+                Err(e) if e.is_connect() => {
+                    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                }
+                Err(e) => panic!("{e:?}"),
+            }
+        };
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(code.await.unwrap().unwrap(), "code");
+    }
+
+    #[tokio::test]
+    async fn test_local_server_messenger_host_certs() {
+        let certs_dir =
+            std::env::temp_dir().join(format!("schwab_api_host_certs_{}", std::process::id()));
+        let host_dir = certs_dir.join("127.0.0.1");
+        std::fs::create_dir_all(&host_dir).unwrap();
+        let tests_certs_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/certs");
+        for file in ["cert.pem", "key.pem"] {
+            std::fs::copy(tests_certs_dir.join(file), certs_dir.join(file)).unwrap();
+        }
+        std::fs::write(host_dir.join("cert.pem"), "not a cert").unwrap();
+        std::fs::write(host_dir.join("key.pem"), "not a key").unwrap();
+
+        let context = |redirect_url: &str| AuthContext {
+            auth_url: Some("https://127.0.0.1/".parse().unwrap()),
+            csrf: Some(CsrfToken::new("CSRF".to_string())),
+            redirect_url: Some(redirect_url.parse().unwrap()),
+        };
+
+        assert_eq!(
+            host_certs_dir(&certs_dir, &"https://127.0.0.1:8080".parse().unwrap()),
+            Some(host_dir.clone())
+        );
+        assert_eq!(
+            host_certs_dir(&certs_dir, &"https://[::1]:8080".parse().unwrap()),
+            None
+        );
+
+        // no certs for localhost, the ones in certs_dir are used
+        let mut messenger = LocalServerMessenger::new(&certs_dir).await;
+        assert!(messenger
+            .with_context(context("https://localhost:8080/callback"))
+            .await
+            .is_ok());
+
+        // the certs of the callback host are loaded
+        assert!(matches!(
+            messenger
+                .with_context(context("https://127.0.0.1:8080/callback"))
+                .await,
+            Err(Error::Stdio(_))
+        ));
+
+        std::fs::remove_dir_all(&certs_dir).unwrap();
+    }
+
     #[tokio::test]
     #[ignore = "Testing manually for browser verification. Should be --nocapture"]
     async fn test_local_server_messenger() {
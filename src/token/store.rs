@@ -0,0 +1,97 @@
+//! Backends persisting the [`Token`], e.g. a file, a secrets manager or a database.
+
+use std::path::PathBuf;
+use tokio::sync::Mutex;
+
+use super::Token;
+use crate::error::Error;
+
+/// Where [`TokenChecker`](super::TokenChecker) loads and saves the [`Token`].
+///
+/// [`FileTokenStore`] is the default, [`MemoryTokenStore`] keeps it in memory only.
+pub trait TokenStore: Send + Sync {
+    /// Returns `None` when no token has been saved yet, which triggers the authorization.
+    fn load(&self) -> impl std::future::Future<Output = Result<Option<Token>, Error>> + Send;
+
+    fn save(&self, token: &Token) -> impl std::future::Future<Output = Result<(), Error>> + Send;
+}
+
+/// Saves the [`Token`] as JSON at the given path.
+#[derive(Debug, Clone)]
+pub struct FileTokenStore(pub PathBuf);
+
+impl TokenStore for FileTokenStore {
+    /// An unreadable file is treated as no token, so it is authorized again
+    async fn load(&self) -> Result<Option<Token>, Error> {
+        Ok(Token::load(self.0.clone()).ok())
+    }
+
+    async fn save(&self, token: &Token) -> Result<(), Error> {
+        token.save(self.0.clone()).map_err(std::convert::Into::into)
+    }
+}
+
+/// Keeps the [`Token`] in memory, so it is lost when dropped.
+#[derive(Debug, Default)]
+pub struct MemoryTokenStore {
+    token: Mutex<Option<Token>>,
+}
+
+impl MemoryTokenStore {
+    #[must_use]
+    pub fn new(token: Option<Token>) -> Self {
+        Self {
+            token: Mutex::new(token),
+        }
+    }
+}
+
+impl TokenStore for MemoryTokenStore {
+    async fn load(&self) -> Result<Option<Token>, Error> {
+        Ok(self.token.lock().await.clone())
+    }
+
+    async fn save(&self, token: &Token) -> Result<(), Error> {
+        *self.token.lock().await = Some(token.clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_file_token_store() {
+        let store = FileTokenStore(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("schwab")
+                .join("token")
+                .join("normal.json"),
+        );
+        assert!(store.load().await.unwrap().is_some());
+
+        let store = FileTokenStore(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("schwab")
+                .join("token")
+                .join("not_exist.json"),
+        );
+        assert!(store.load().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_memory_token_store() {
+        let store = MemoryTokenStore::default();
+        assert!(store.load().await.unwrap().is_none());
+
+        let token = Token {
+            access: "access".to_string(),
+            ..Default::default()
+        };
+        store.save(&token).await.unwrap();
+        assert_eq!(store.load().await.unwrap().unwrap().access, "access");
+    }
+}